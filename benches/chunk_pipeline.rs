@@ -0,0 +1,218 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use meinkraft::components::{world_to_chunk_coords, BlockType, ChunkCoord, ChunkData, LOD};
+use meinkraft::resources::{Config, MeshGenerator, WorldGenerator};
+use meinkraft::ChunkCache;
+use std::collections::HashMap;
+
+const LODS: [LOD; 4] = [LOD::LOD1, LOD::LOD2, LOD::LOD4, LOD::LOD8];
+
+struct Fixture {
+    name: &'static str,
+    coord: ChunkCoord,
+    data: ChunkData,
+}
+
+// One layer per distinct face texture, numbered in first-use order.
+fn texture_layers() -> HashMap<String, f32> {
+    let mut layers = HashMap::new();
+    for textures in BlockType::ALL
+        .iter()
+        .filter_map(BlockType::get_face_textures)
+    {
+        for name in textures {
+            let next_layer = layers.len() as f32;
+            layers.entry(name.to_string()).or_insert(next_layer);
+        }
+    }
+    layers
+}
+
+// The highest and lowest surface columns on a coarse grid around the origin.
+fn extreme_columns(world_generator: &WorldGenerator) -> ((i32, i32, i32), (i32, i32, i32)) {
+    let mut highest = (0, i32::MIN, 0);
+    let mut lowest = (0, i32::MAX, 0);
+    for gx in -16..16 {
+        for gz in -16..16 {
+            let (x, z) = (gx * 97, gz * 97);
+            let height = world_generator.calculate_terrain_height(x, z);
+            if height > highest.1 {
+                highest = (x, height, z);
+            }
+            if height < lowest.1 {
+                lowest = (x, height, z);
+            }
+        }
+    }
+    (highest, lowest)
+}
+
+fn flat_chunk(config: &Config) -> ChunkData {
+    let mut chunk = ChunkData::new(config);
+    for x in 0..config.chunk_width {
+        for z in 0..config.chunk_depth {
+            for y in 0..8 {
+                let block = match y {
+                    7 => BlockType::GrassyDirt,
+                    4..=6 => BlockType::Dirt,
+                    _ => BlockType::Stone,
+                };
+                chunk.set_block(config, x, y, z, block);
+            }
+        }
+    }
+    chunk
+}
+
+// Solid stone riddled with tunnels and pockets, roughly half air, close to the worst case
+// for face count.
+fn cave_chunk(config: &Config) -> ChunkData {
+    let mut chunk = ChunkData::new(config);
+    for y in 0..config.chunk_height {
+        for z in 0..config.chunk_depth {
+            for x in 0..config.chunk_width {
+                let tunnel = (x + 2 * y) % 5 < 2 || (z + 3 * y) % 7 < 2;
+                let pocket = (x * 7 + y * 13 + z * 17) % 11 < 3;
+                if !(tunnel && pocket) {
+                    chunk.set_block(config, x, y, z, BlockType::Stone);
+                }
+            }
+        }
+    }
+    chunk
+}
+
+fn fixtures(config: &Config, world_generator: &WorldGenerator) -> Vec<Fixture> {
+    let (highest, lowest) = extreme_columns(world_generator);
+    let mountain = world_to_chunk_coords(config, highest.0, highest.1, highest.2);
+    let ocean = world_to_chunk_coords(config, lowest.0, config.sea_level, lowest.2);
+    vec![
+        Fixture {
+            name: "flat",
+            coord: ChunkCoord(0, 0, 0),
+            data: flat_chunk(config),
+        },
+        Fixture {
+            name: "mountain",
+            coord: mountain,
+            data: world_generator.generate_chunk_data(mountain),
+        },
+        Fixture {
+            name: "ocean",
+            coord: ocean,
+            data: world_generator.generate_chunk_data(ocean),
+        },
+        Fixture {
+            name: "caves",
+            coord: ChunkCoord(0, 0, 0),
+            data: cave_chunk(config),
+        },
+        Fixture {
+            name: "air",
+            coord: ChunkCoord(0, config.max_chunk_y, 0),
+            data: ChunkData::new(config),
+        },
+    ]
+}
+
+fn bench_generation(c: &mut Criterion) {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let mut group = c.benchmark_group("generate_chunk_data");
+    for fixture in fixtures(&config, &world_generator) {
+        if matches!(fixture.name, "flat" | "caves") {
+            continue;
+        }
+        group.bench_with_input(
+            BenchmarkId::from_parameter(fixture.name),
+            &fixture.coord,
+            |b, coord| b.iter(|| world_generator.generate_chunk_data(black_box(*coord))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_meshing(c: &mut Criterion) {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let mesh_generator = MeshGenerator::new();
+    let layers = texture_layers();
+    let mut group = c.benchmark_group("generate_chunk_mesh");
+    for fixture in fixtures(&config, &world_generator) {
+        // Surrounded by copies of itself so border faces are culled like in a loaded world.
+        let neighbors: [Option<ChunkData>; 6] = std::array::from_fn(|_| Some(fixture.data.clone()));
+        for lod in LODS {
+            group.bench_with_input(
+                BenchmarkId::new(fixture.name, format!("{:?}", lod)),
+                &lod,
+                |b, lod| {
+                    b.iter(|| {
+                        mesh_generator.generate_chunk_mesh(
+                            fixture.coord,
+                            black_box(&fixture.data),
+                            &neighbors,
+                            &[*lod; 6],
+                            &layers,
+                            *lod,
+                            &config,
+                        )
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_downsampling(c: &mut Criterion) {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let mesh_generator = MeshGenerator::new();
+    let mut group = c.benchmark_group("downsample_chunk");
+    for fixture in fixtures(&config, &world_generator) {
+        for lod in &LODS[1..] {
+            let factor = lod.downsample_factor();
+            group.bench_with_input(
+                BenchmarkId::new(fixture.name, factor),
+                &factor,
+                |b, factor| {
+                    b.iter(|| {
+                        mesh_generator.downsample_chunk(black_box(&fixture.data), *factor, &config)
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_cache(c: &mut Criterion) {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let cache_dir = std::env::temp_dir().join(format!("meinkraft-bench-{}", std::process::id()));
+    let cache = ChunkCache::in_dir(cache_dir.clone()).expect("Failed to create bench cache");
+
+    let mut group = c.benchmark_group("chunk_cache");
+    for fixture in fixtures(&config, &world_generator) {
+        group.bench_function(BenchmarkId::new("save", fixture.name), |b| {
+            b.iter(|| cache.save_chunk(fixture.coord, black_box(&fixture.data)))
+        });
+        cache
+            .save_chunk(fixture.coord, &fixture.data)
+            .expect("Failed to save bench chunk");
+        group.bench_function(BenchmarkId::new("load", fixture.name), |b| {
+            b.iter(|| cache.load_chunk(black_box(fixture.coord)))
+        });
+    }
+    group.finish();
+
+    let _ = std::fs::remove_dir_all(cache_dir);
+}
+
+criterion_group!(
+    benches,
+    bench_generation,
+    bench_meshing,
+    bench_downsampling,
+    bench_cache
+);
+criterion_main!(benches);
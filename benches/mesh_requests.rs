@@ -0,0 +1,75 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fnv::FnvHashMap;
+use hecs::{Entity, World};
+use meinkraft::components::{ChunkCoord, ChunkData, LOD};
+use meinkraft::resources::{Config, WorldGenerator};
+
+const NEIGHBOR_OFFSETS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+// Copies the buffers the way ChunkData::clone did before they were shared.
+fn deep_copy(chunk: &ChunkData) -> ChunkData {
+    ChunkData {
+        blocks: chunk.blocks.to_vec().into(),
+        fluid_levels: chunk.fluid_levels.to_vec().into(),
+    }
+}
+
+// The main-thread part of a remesh request, as collect_meshing_requests and get_neighbor_data
+// do it: look the chunk and its six neighbors up in the world and copy their data.
+fn build_request(
+    world: &World,
+    chunk_entity_map: &FnvHashMap<ChunkCoord, Entity>,
+    coord: ChunkCoord,
+    copy: impl Fn(&ChunkData) -> ChunkData,
+) {
+    let entity = chunk_entity_map[&coord];
+    let chunk = copy(&world.get::<&ChunkData>(entity).unwrap());
+    let neighbors: [Option<ChunkData>; 6] = NEIGHBOR_OFFSETS.map(|(x, y, z)| {
+        let neighbor = ChunkCoord(coord.0 + x, coord.1 + y, coord.2 + z);
+        chunk_entity_map
+            .get(&neighbor)
+            .and_then(|entity| world.get::<&ChunkData>(*entity).ok())
+            .map(|data| copy(&data))
+    });
+    black_box((
+        entity,
+        coord,
+        chunk,
+        Box::new(neighbors),
+        LOD::LOD1,
+        [LOD::LOD1; 6],
+    ));
+}
+
+fn mesh_request_build(c: &mut Criterion) {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let center = ChunkCoord(0, 3, 0);
+
+    let mut world = World::new();
+    let mut chunk_entity_map = FnvHashMap::default();
+    for (x, y, z) in std::iter::once((0, 0, 0)).chain(NEIGHBOR_OFFSETS) {
+        let coord = ChunkCoord(center.0 + x, center.1 + y, center.2 + z);
+        let entity = world.spawn((coord, world_generator.generate_chunk_data(coord)));
+        chunk_entity_map.insert(coord, entity);
+    }
+
+    let mut group = c.benchmark_group("mesh_request_build");
+    group.bench_function("deep_copy", |b| {
+        b.iter(|| build_request(&world, &chunk_entity_map, center, deep_copy))
+    });
+    group.bench_function("shared", |b| {
+        b.iter(|| build_request(&world, &chunk_entity_map, center, ChunkData::clone))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, mesh_request_build);
+criterion_main!(benches);
@@ -13,6 +13,9 @@ use winit::window::WindowId;
 pub struct App {
     window_manager: WindowManager,
     last_update_time: Instant,
+    // Frames since the debug stats in the window title were last refreshed.
+    title_frames: u32,
+    last_title_update: Instant,
     input_manager: InputManager,
     game_state: Option<GameState>,
     system_scheduler: SystemScheduler,
@@ -27,6 +30,8 @@ impl App {
             system_scheduler: SystemScheduler::new(),
             input_manager: InputManager::new(),
             last_update_time: Instant::now(),
+            title_frames: 0,
+            last_title_update: Instant::now(),
             exit_state: Ok(()),
         }
     }
@@ -122,6 +127,19 @@ impl ApplicationHandler for App {
             game_state.input_state.reset_frame_state();
 
             self.window_manager.swap_buffers();
+
+            self.title_frames += 1;
+            let title_elapsed = now.duration_since(self.last_title_update).as_secs_f32();
+            let interval = game_state.config.debug_title_interval;
+            if interval > 0.0 && title_elapsed >= interval {
+                let fps = self.title_frames as f32 / title_elapsed;
+                self.window_manager.set_title(&format!(
+                    "Meinkraft | {:.0} FPS | {} | {}",
+                    fps, game_state.renderer.stats, game_state.worker_report
+                ));
+                self.title_frames = 0;
+                self.last_title_update = now;
+            }
         }
     }
 
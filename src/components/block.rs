@@ -23,6 +23,27 @@ pub enum BlockType {
 }
 
 impl BlockType {
+    pub const ALL: [BlockType; 18] = [
+        BlockType::Air,
+        BlockType::Dirt,
+        BlockType::GrassyDirt,
+        BlockType::Stone,
+        BlockType::Snow,
+        BlockType::SnowyDirt,
+        BlockType::Sand,
+        BlockType::Glass,
+        BlockType::Log,
+        BlockType::Planks,
+        BlockType::Leaves,
+        BlockType::Water,
+        BlockType::Ice,
+        BlockType::Gravel,
+        BlockType::Andesite,
+        BlockType::Granite,
+        BlockType::Diorite,
+        BlockType::Cobblestone,
+    ];
+
     pub fn is_culled_by(&self) -> bool {
         !matches!(
             self,
@@ -38,6 +59,19 @@ impl BlockType {
         matches!(self, BlockType::Water)
     }
 
+    pub fn is_affected_by_gravity(&self) -> bool {
+        matches!(self, BlockType::Sand | BlockType::Gravel)
+    }
+
+    pub fn is_replaceable(&self) -> bool {
+        matches!(self, BlockType::Air | BlockType::Water)
+    }
+
+    // Solid tops that snow settles on. Snow itself is excluded so cover stays one block deep.
+    pub fn can_hold_snow(&self) -> bool {
+        self.is_culled_by() && !matches!(self, BlockType::Snow)
+    }
+
     pub fn get_face_textures(&self) -> Option<[&'static str; 6]> {
         match self {
             BlockType::Air => None,
@@ -1,23 +1,96 @@
-use crate::components::BlockType;
-use crate::resources::Config;
+use crate::components::{BlockType, FLUID_SOURCE_LEVEL};
+use crate::resources::{Config, Frustum};
 use glam::Vec3;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ChunkCoord(pub i32, pub i32, pub i32);
 
+// Copy-on-write storage for chunk arrays. Cloning only bumps a reference count, so mesh
+// requests can snapshot a chunk and its neighbors cheaply; the first write to a buffer that a
+// worker still holds copies it. Serializes exactly like the Vec it wraps.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SharedBuffer<T>(Arc<Vec<T>>);
+
+impl<T> SharedBuffer<T> {
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Clone for SharedBuffer<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> From<Vec<T>> for SharedBuffer<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self(Arc::new(values))
+    }
+}
+
+impl<T> Deref for SharedBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for SharedBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        Arc::make_mut(&mut self.0).as_mut_slice()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SharedBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: Serialize> Serialize for SharedBuffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SharedBuffer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(Self::from)
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ChunkData {
-    pub blocks: Vec<BlockType>,
+    pub blocks: SharedBuffer<BlockType>,
+    pub fluid_levels: SharedBuffer<u8>,
 }
 
 impl ChunkData {
     pub fn new(config: &Config) -> Self {
         Self {
-            blocks: vec![BlockType::Air; config.chunk_size],
+            blocks: vec![BlockType::Air; config.chunk_size].into(),
+            fluid_levels: vec![FLUID_SOURCE_LEVEL; config.chunk_size].into(),
         }
     }
 
+    // Heap memory held by the block and fluid buffers.
+    pub fn byte_size(&self) -> usize {
+        self.blocks.len() * std::mem::size_of::<BlockType>()
+            + self.fluid_levels.len() * std::mem::size_of::<u8>()
+    }
+
     #[inline]
     fn local_coords_to_index(config: &Config, x: usize, y: usize, z: usize) -> Option<usize> {
         if x < config.chunk_width && y < config.chunk_height && z < config.chunk_depth {
@@ -45,6 +118,32 @@ impl ChunkData {
             if index < self.blocks.len() {
                 self.blocks[index] = block_type;
             }
+            if index < self.fluid_levels.len() {
+                self.fluid_levels[index] = FLUID_SOURCE_LEVEL;
+            }
+        }
+    }
+
+    pub fn get_fluid_level(&self, config: &Config, x: usize, y: usize, z: usize) -> u8 {
+        Self::local_coords_to_index(config, x, y, z)
+            .and_then(|index| self.fluid_levels.get(index).copied())
+            .unwrap_or(FLUID_SOURCE_LEVEL)
+    }
+
+    pub fn set_fluid(
+        &mut self,
+        config: &Config,
+        x: usize,
+        y: usize,
+        z: usize,
+        block_type: BlockType,
+        level: u8,
+    ) {
+        if let Some(index) = Self::local_coords_to_index(config, x, y, z) {
+            if index < self.blocks.len() && index < self.fluid_levels.len() {
+                self.blocks[index] = block_type;
+                self.fluid_levels[index] = level;
+            }
         }
     }
 }
@@ -80,6 +179,69 @@ pub fn world_to_local_coords(
     )
 }
 
+// Squared distance in chunks, with the vertical axis scaled by `lod_vertical_weight`.
+#[inline]
+pub fn weighted_chunk_distance_sq(config: &Config, from: ChunkCoord, to: ChunkCoord) -> f32 {
+    let dx = (to.0 - from.0) as f32;
+    let dy = (to.1 - from.1) as f32 * config.lod_vertical_weight;
+    let dz = (to.2 - from.2) as f32;
+    dx * dx + dy * dy + dz * dz
+}
+
+// Chunks are loaded, meshed and drawn within render distance.
+pub fn is_within_render_distance(
+    config: &Config,
+    camera_chunk: ChunkCoord,
+    coord: ChunkCoord,
+) -> bool {
+    let render_dist = config.render_distance;
+    weighted_chunk_distance_sq(config, camera_chunk, coord) <= (render_dist * render_dist) as f32
+}
+
+// Loaded chunks are kept a little past render distance so moving back and forth across the
+// boundary doesn't unload and reload the same chunks.
+pub fn is_past_unload_distance(
+    config: &Config,
+    camera_chunk: ChunkCoord,
+    coord: ChunkCoord,
+) -> bool {
+    let unload_dist = config.render_distance + config.unload_distance_padding.max(0);
+    weighted_chunk_distance_sq(config, camera_chunk, coord) > (unload_dist * unload_dist) as f32
+}
+
+// Lower loads first. Vertically a chunk counts as close if it is near either the camera or
+// the terrain surface of its column, so the ground under a flying camera still arrives early.
+pub fn chunk_load_priority(
+    config: &Config,
+    camera_chunk: ChunkCoord,
+    coord: ChunkCoord,
+    surface_chunk_y: i32,
+) -> f32 {
+    let dx = (coord.0 - camera_chunk.0) as f32;
+    let dz = (coord.2 - camera_chunk.2) as f32;
+    let vertical = (coord.1 - camera_chunk.1)
+        .abs()
+        .min((coord.1 - surface_chunk_y).abs()) as f32
+        * config.lod_vertical_weight;
+    dx * dx + dz * dz + vertical * vertical
+}
+
+// Pushes chunks outside the view frustum back in the queues so what the player is looking at
+// arrives first.
+pub fn chunk_view_priority(
+    config: &Config,
+    frustum: &Frustum,
+    coord: ChunkCoord,
+    base_priority: f32,
+) -> f32 {
+    let center = chunk_coord_to_aabb_center(config, coord);
+    if frustum.intersects_aabb(center, get_chunk_extents(config)) {
+        base_priority
+    } else {
+        (base_priority + 1.0) * config.out_of_view_priority_penalty
+    }
+}
+
 #[inline]
 pub fn chunk_coord_to_world_pos(config: &Config, coord: ChunkCoord) -> Vec3 {
     Vec3::new(
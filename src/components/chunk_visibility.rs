@@ -0,0 +1,41 @@
+// Which pairs of chunk boundary faces are connected through see-through blocks.
+// Face indices follow the mesher: 0 +X, 1 -X, 2 +Y, 3 -Y, 4 +Z, 5 -Z.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkVisibility {
+    connections: u64,
+}
+
+impl ChunkVisibility {
+    pub fn none() -> Self {
+        Self { connections: 0 }
+    }
+
+    pub fn all() -> Self {
+        let mut visibility = Self::none();
+        for from in 0..6 {
+            for to in 0..6 {
+                visibility.connect(from, to);
+            }
+        }
+        visibility
+    }
+
+    pub fn connect(&mut self, from: usize, to: usize) {
+        self.connections |= 1 << (from * 6 + to);
+        self.connections |= 1 << (to * 6 + from);
+    }
+
+    pub fn is_connected(&self, from: usize, to: usize) -> bool {
+        self.connections & (1 << (from * 6 + to)) != 0
+    }
+
+    pub fn opposite_face(face: usize) -> usize {
+        face ^ 1
+    }
+}
+
+impl Default for ChunkVisibility {
+    fn default() -> Self {
+        Self::all()
+    }
+}
@@ -0,0 +1,15 @@
+use crate::components::BlockType;
+
+pub struct FallingBlock {
+    pub block_type: BlockType,
+    pub velocity: f32,
+}
+
+impl FallingBlock {
+    pub fn new(block_type: BlockType) -> Self {
+        Self {
+            block_type,
+            velocity: 0.0,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+pub const FLUID_SOURCE_LEVEL: u8 = 0;
+pub const FLUID_MAX_FLOW_LEVEL: u8 = 7;
+pub const FLUID_FALLING_LEVEL: u8 = 8;
+
+#[inline]
+pub fn is_fluid_source(level: u8) -> bool {
+    level == FLUID_SOURCE_LEVEL
+}
+
+#[inline]
+pub fn is_fluid_falling(level: u8) -> bool {
+    level >= FLUID_FALLING_LEVEL
+}
+
+// Height of the fluid surface as a fraction of a block. Sources sit slightly below
+// the top of the block and each flow step drops it further, unless something covers it.
+#[inline]
+pub fn fluid_surface_height(level: u8, covered: bool) -> f32 {
+    if covered {
+        return 1.0;
+    }
+    let effective_level = if is_fluid_falling(level) {
+        FLUID_SOURCE_LEVEL
+    } else {
+        level.min(FLUID_MAX_FLOW_LEVEL)
+    };
+    (FLUID_MAX_FLOW_LEVEL + 1 - effective_level) as f32 / (FLUID_MAX_FLOW_LEVEL + 2) as f32
+}
@@ -1,3 +1,5 @@
+use crate::resources::Config;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LOD {
     LOD1,
@@ -24,4 +26,20 @@ impl LOD {
             LOD::LOD8 => 8,
         }
     }
+
+    // The LOD a chunk gets at the given weighted squared distance, or None past render distance.
+    pub fn for_distance_sq(distance_sq: f32, config: &Config) -> Option<LOD> {
+        let within = |distance: i32| distance_sq <= (distance * distance) as f32;
+        if within(config.load_distance) {
+            Some(LOD::LOD1)
+        } else if within(config.lod2_distance) {
+            Some(LOD::LOD2)
+        } else if within(config.lod4_distance) {
+            Some(LOD::LOD4)
+        } else if within(config.render_distance) {
+            Some(LOD::LOD8)
+        } else {
+            None
+        }
+    }
 }
@@ -2,6 +2,8 @@
 pub struct Renderable {
     pub opaque_mesh_id: Option<usize>,
     pub transparent_mesh_id: Option<usize>,
+    pub water_mesh_id: Option<usize>,
+    pub shadow_mesh_id: Option<usize>,
 }
 
 impl Renderable {
@@ -9,6 +11,19 @@ impl Renderable {
         Self {
             opaque_mesh_id,
             transparent_mesh_id,
+            water_mesh_id: None,
+            shadow_mesh_id: None,
         }
     }
+
+    pub fn mesh_ids(&self) -> impl Iterator<Item = usize> {
+        [
+            self.opaque_mesh_id,
+            self.transparent_mesh_id,
+            self.water_mesh_id,
+            self.shadow_mesh_id,
+        ]
+        .into_iter()
+        .flatten()
+    }
 }
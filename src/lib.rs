@@ -6,18 +6,30 @@ mod scheduler;
 mod state;
 mod window;
 
+pub use persistence::ChunkCache;
+
 pub mod components {
     mod block;
     mod chunk;
+    mod chunk_visibility;
+    mod falling_block;
+    mod fluid;
     mod lod;
     mod renderable;
     mod transform;
 
     pub use block::BlockType;
     pub use chunk::{
-        chunk_coord_to_aabb_center, chunk_coord_to_world_pos, get_chunk_extents,
-        world_to_chunk_coords, world_to_local_coords, ChunkCoord, ChunkData, ChunkDirty,
-        ChunkModified,
+        chunk_coord_to_aabb_center, chunk_coord_to_world_pos, chunk_load_priority,
+        chunk_view_priority, get_chunk_extents, is_past_unload_distance, is_within_render_distance,
+        weighted_chunk_distance_sq, world_to_chunk_coords, world_to_local_coords, ChunkCoord,
+        ChunkData, ChunkDirty, ChunkModified, SharedBuffer,
+    };
+    pub use chunk_visibility::ChunkVisibility;
+    pub use falling_block::FallingBlock;
+    pub use fluid::{
+        fluid_surface_height, is_fluid_falling, is_fluid_source, FLUID_FALLING_LEVEL,
+        FLUID_MAX_FLOW_LEVEL, FLUID_SOURCE_LEVEL,
     };
     pub use lod::LOD;
     pub use renderable::Renderable;
@@ -25,35 +37,86 @@ pub mod components {
 }
 
 pub mod resources {
+    mod block_access;
+    mod buffer_arena;
     mod camera;
+    mod clouds;
     mod config;
+    mod far_terrain;
+    mod frame_budget;
     mod input_state;
+    mod lru_cache;
     mod mesh;
     mod mesh_generator;
+    mod post_passes;
+    mod post_processing;
     mod renderer;
+    mod request_queue;
     mod shader_program;
+    mod shadow_cascades;
+    mod sky;
     mod texture_manager;
+    mod weather;
+    mod workers;
     mod world_generator;
 
-    pub use camera::Camera;
+    pub use block_access::BlockAccess;
+    pub use buffer_arena::{ArenaAllocation, BufferArena};
+    pub use camera::{Camera, Frustum};
+    pub use clouds::{generate_cloud_density, CloudMode};
     pub use config::{Config, GameAction};
+    pub use far_terrain::{
+        far_terrain_inner_radius, far_terrain_radius, far_tile_origin, far_tiles_in_range,
+        generate_far_terrain_tile, view_distance, FarTerrain, FarTerrainTile, FarTileCoord,
+    };
+    pub use frame_budget::FrameBudget;
     pub use input_state::InputState;
+    pub use lru_cache::LruCache;
     pub use mesh::{ChunkMeshData, Mesh, MeshRegistry};
     pub use mesh_generator::MeshGenerator;
-    pub use renderer::Renderer;
+    pub use post_passes::{BloomPass, ExposurePass, ToneMapPass};
+    pub use post_processing::{
+        bloom_mip_sizes, fullscreen_program, PostProcessChain, PostProcessFrame, PostProcessPass,
+        RenderTarget, ToneMapOperator,
+    };
+    pub use renderer::{ChunkDrawList, RenderStats, Renderer};
+    pub use request_queue::{CancellationSet, FailureTracker, RequestQueue};
     pub use shader_program::ShaderProgram;
+    pub use shadow_cascades::{
+        calculate_cascade_splits, calculate_shadow_cascades, ShadowCascade, MAX_SHADOW_CASCADES,
+    };
+    pub use sky::{fog_range, light_direction, light_level, scattering_sky_color, sun_direction};
     pub use texture_manager::TextureManager;
+    pub use weather::{
+        precipitation_at, Precipitation, PrecipitationHeightmap, Weather, WeatherKind,
+    };
+    pub use workers::{
+        panic_message, run_catching_panics, worker_thread_budget, JobCounts, JobKind, JobStats,
+        WorkerReport, WorkerStats, WorkerThreads,
+    };
     pub use world_generator::WorldGenerator;
 }
 
 pub mod systems {
     mod chunk_loading_system;
     mod chunk_meshing_system;
+    mod falling_block_system;
+    mod far_terrain_system;
+    mod fluid_system;
     mod input_system;
+    mod random_tick_system;
     mod render_system;
+    mod weather_system;
+    mod worker_stats_system;
 
     pub use chunk_loading_system::ChunkLoadingSystem;
     pub use chunk_meshing_system::ChunkMeshingSystem;
+    pub use falling_block_system::FallingBlockSystem;
+    pub use far_terrain_system::FarTerrainSystem;
+    pub use fluid_system::FluidSystem;
     pub use input_system::InputSystem;
+    pub use random_tick_system::RandomTickSystem;
     pub use render_system::RenderSystem;
+    pub use weather_system::WeatherSystem;
+    pub use worker_stats_system::WorkerStatsSystem;
 }
@@ -1,24 +1,42 @@
-use crate::components::{ChunkCoord, ChunkData};
-use crate::resources::{Config, MeshGenerator, WorldGenerator};
+use crate::components::{BlockType, ChunkCoord, ChunkData, ChunkVisibility, FLUID_SOURCE_LEVEL};
+use crate::resources::{
+    generate_far_terrain_tile, run_catching_panics, worker_thread_budget, CancellationSet, Config,
+    FarTileCoord, JobKind, Mesh, MeshGenerator, WorkerStats, WorkerThreads, WorldGenerator,
+};
 use crate::state::{MeshRequestData, MeshResultData};
 use bincode::config::{standard, Configuration};
 use crossbeam_channel::{Receiver, Sender};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 use std::collections::HashMap as StdHashMap;
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter, Error as IoError, ErrorKind};
+use std::io::{BufWriter, Error as IoError, ErrorKind, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::thread;
+use std::time::Instant;
 
 const BINCODE_CONFIG: Configuration = standard();
 
+// Cached chunk files start with this magic and a little-endian format version. Files without
+// it predate fluid levels and hold only the block array.
+const CHUNK_FILE_MAGIC: &[u8; 4] = b"MKCH";
+const CHUNK_FORMAT_VERSION: u32 = 2;
+
+#[derive(Deserialize)]
+struct LegacyChunkData {
+    blocks: Vec<BlockType>,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum LoadRequest {
     LoadOrGenerate(ChunkCoord),
     LoadFromCache(ChunkCoord),
 }
 
-pub type LoadResult = (ChunkCoord, Option<ChunkData>);
+// An `Err` carries the message of a job that panicked, so the requester can retry it or give up.
+pub type LoadResult = (ChunkCoord, Result<Option<ChunkData>, String>);
+
+pub type FarTileResult = (FarTileCoord, Result<Mesh, String>);
 
 pub struct WorkerResources {
     pub world_generator: Arc<WorldGenerator>,
@@ -26,6 +44,9 @@ pub struct WorkerResources {
     pub texture_manager_layers: Arc<StdHashMap<String, f32>>,
     pub chunk_cache: ChunkCache,
     pub config: Config,
+    pub gen_cancellations: Arc<CancellationSet<ChunkCoord>>,
+    pub mesh_cancellations: Arc<CancellationSet<ChunkCoord>>,
+    pub stats: Arc<WorkerStats>,
 }
 
 pub struct WorkerChannels {
@@ -33,6 +54,8 @@ pub struct WorkerChannels {
     pub mesh_request_rx: Receiver<MeshRequestData>,
     pub gen_result_tx: Sender<LoadResult>,
     pub mesh_result_tx: Sender<MeshResultData>,
+    pub far_tile_request_rx: Receiver<FarTileCoord>,
+    pub far_tile_result_tx: Sender<FarTileResult>,
 }
 
 #[derive(Clone)]
@@ -47,7 +70,10 @@ impl ChunkCache {
             .join("cache")
             .join(world_name)
             .join("chunks");
+        Self::in_dir(cache_dir)
+    }
 
+    pub fn in_dir(cache_dir: PathBuf) -> Result<Self, String> {
         fs::create_dir_all(&cache_dir)
             .map_err(|e| format!("Failed to create cache directory {:?}: {}", cache_dir, e))?;
 
@@ -64,11 +90,11 @@ impl ChunkCache {
         match File::create(&path) {
             Ok(file) => {
                 let mut writer = BufWriter::new(file);
+                writer.write_all(CHUNK_FILE_MAGIC)?;
+                writer.write_all(&CHUNK_FORMAT_VERSION.to_le_bytes())?;
                 bincode::serde::encode_into_std_write(chunk_data, &mut writer, BINCODE_CONFIG)
-                    .map(|_| ())
-                    .map_err(|e| {
-                        IoError::new(ErrorKind::Other, format!("Bincode encode error: {}", e))
-                    })
+                    .map_err(|e| IoError::other(format!("Bincode encode error: {}", e)))?;
+                writer.flush()
             }
             Err(e) => {
                 eprintln!("Failed to create/truncate file {:?}: {}", path, e);
@@ -83,16 +109,52 @@ impl ChunkCache {
             return Ok(None);
         }
 
-        let file = File::open(&path)?;
-        let mut reader = BufReader::new(file);
-        let chunk_data: ChunkData =
-            bincode::serde::decode_from_std_read(&mut reader, BINCODE_CONFIG).map_err(|e| {
-                IoError::new(
-                    ErrorKind::InvalidData,
-                    format!("Bincode decode error at {:?}: {}", path, e),
-                )
-            })?;
-        Ok(Some(chunk_data))
+        let bytes = fs::read(&path)?;
+        let invalid = |message: String| {
+            IoError::new(ErrorKind::InvalidData, format!("{} at {:?}", message, path))
+        };
+
+        let Some(payload) = bytes.strip_prefix(CHUNK_FILE_MAGIC) else {
+            return Self::decode_legacy(&bytes).map(Some).map_err(invalid);
+        };
+        let (version, payload) = payload
+            .split_first_chunk::<4>()
+            .ok_or_else(|| invalid("Truncated chunk header".to_string()))?;
+        match u32::from_le_bytes(*version) {
+            CHUNK_FORMAT_VERSION => Self::decode_exact::<ChunkData>(payload)
+                .map(Some)
+                .map_err(invalid),
+            version => Err(invalid(format!(
+                "Unsupported chunk format version {} (expected {})",
+                version, CHUNK_FORMAT_VERSION
+            ))),
+        }
+    }
+
+    // Headerless files: block-only chunks from before fluid levels existed, or fluid-level
+    // chunks written before the header was added.
+    fn decode_legacy(bytes: &[u8]) -> Result<ChunkData, String> {
+        if let Ok(chunk_data) = Self::decode_exact::<ChunkData>(bytes) {
+            return Ok(chunk_data);
+        }
+        let legacy = Self::decode_exact::<LegacyChunkData>(bytes)?;
+        let fluid_levels = vec![FLUID_SOURCE_LEVEL; legacy.blocks.len()];
+        Ok(ChunkData {
+            blocks: legacy.blocks.into(),
+            fluid_levels: fluid_levels.into(),
+        })
+    }
+
+    fn decode_exact<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+        let (value, read) = bincode::serde::decode_from_slice(bytes, BINCODE_CONFIG)
+            .map_err(|e| format!("Bincode decode error: {}", e))?;
+        if read != bytes.len() {
+            return Err(format!(
+                "Bincode decode error: {} trailing bytes",
+                bytes.len() - read
+            ));
+        }
+        Ok(value)
     }
 
     pub fn delete_chunk(&self, coord: ChunkCoord) -> Result<(), IoError> {
@@ -107,151 +169,284 @@ impl ChunkCache {
 
 pub type NeighborData = Box<[Option<ChunkData>; 6]>;
 
-#[allow(dead_code)]
-pub struct WorkerPool {
+// Blocks while the bounded result channel is full, but gives up when the pool shuts down so a
+// worker never hangs on a receiver nobody drains anymore.
+fn send_result<T>(tx: &Sender<T>, result: T, shutdown_rx: &Receiver<()>) -> bool {
+    crossbeam_channel::select! {
+        send(tx, result) -> res => res.is_ok(),
+        recv(shutdown_rx) -> _ => false,
+    }
+}
+
+// Everything a worker thread needs, cloned once per thread.
+#[derive(Clone)]
+struct WorkerContext {
     world_generator: Arc<WorldGenerator>,
     mesh_generator: Arc<MeshGenerator>,
     texture_manager_layers: Arc<StdHashMap<String, f32>>,
     chunk_cache: ChunkCache,
     config: Config,
+    gen_cancellations: Arc<CancellationSet<ChunkCoord>>,
+    mesh_cancellations: Arc<CancellationSet<ChunkCoord>>,
+    stats: Arc<WorkerStats>,
     gen_request_rx: Receiver<LoadRequest>,
     mesh_request_rx: Receiver<MeshRequestData>,
     gen_result_tx: Sender<LoadResult>,
     mesh_result_tx: Sender<MeshResultData>,
+    far_tile_request_rx: Receiver<FarTileCoord>,
+    far_tile_result_tx: Sender<FarTileResult>,
+    shutdown_rx: Receiver<()>,
+}
+
+// Generation threads load or generate chunks and build far terrain tiles; meshing threads only
+// mesh, so a flood of generation requests can't starve remeshing of chunks already loaded.
+#[derive(Clone, Copy, Debug)]
+enum WorkerRole {
+    Generation,
+    Meshing,
+}
+
+impl WorkerRole {
+    fn name(self) -> &'static str {
+        match self {
+            WorkerRole::Generation => "generation",
+            WorkerRole::Meshing => "meshing",
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub struct WorkerPool {
+    context: WorkerContext,
+    generation_threads: usize,
+    meshing_threads: usize,
     shutdown_tx: Sender<()>,
-    worker_handles: Vec<thread::JoinHandle<()>>,
+    workers: WorkerThreads,
 }
 
 impl WorkerPool {
     pub fn new(resources: WorkerResources, channels: WorkerChannels) -> Self {
-        let num_threads = num_cpus::get().saturating_sub(1).max(1);
-        let mut worker_handles = Vec::with_capacity(num_threads);
+        let available = num_cpus::get().saturating_sub(1);
+        let (generation_threads, meshing_threads) =
+            worker_thread_budget(&resources.config, available);
+        let num_threads = generation_threads + meshing_threads;
         let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded::<()>(num_threads);
-        let wg = Arc::clone(&resources.world_generator);
-        let mg = Arc::clone(&resources.mesh_generator);
-        let tm_layers = Arc::clone(&resources.texture_manager_layers);
-        let cache = resources.chunk_cache.clone();
-        let config = resources.config.clone();
-        let gen_rx = channels.gen_request_rx.clone();
-        let mesh_rx = channels.mesh_request_rx.clone();
-        let gen_tx = channels.gen_result_tx.clone();
-        let mesh_tx = channels.mesh_result_tx.clone();
-
-        for i in 0..num_threads {
-            let wg_clone = Arc::clone(&wg);
-            let mg_clone = Arc::clone(&mg);
-            let tm_layers_clone = Arc::clone(&tm_layers);
-            let cache_clone = cache.clone();
-            let config_clone = config.clone();
-            let gen_rx_clone = gen_rx.clone();
-            let mesh_rx_clone = mesh_rx.clone();
-            let gen_tx_clone = gen_tx.clone();
-            let mesh_tx_clone = mesh_tx.clone();
-            let shutdown_rx_clone = shutdown_rx.clone();
-
-            let handle = thread::Builder::new()
-                .name(format!("worker-{}", i))
-                .spawn(move || {
-                    let wg = wg_clone;
-                    let mg = mg_clone;
-                    let tm_layers = tm_layers_clone;
-                    let cache = cache_clone;
-                    let config = config_clone;
-                    let gen_rx = gen_rx_clone;
-                    let mesh_rx = mesh_rx_clone;
-                    let gen_tx = gen_tx_clone;
-                    let mesh_tx = mesh_tx_clone;
-
-                    loop {
-                        crossbeam_channel::select! {
-                            recv(gen_rx) -> msg => match msg {
-                                Ok(request) => {
-                                    let (coord, should_generate) = match request {
-                                        LoadRequest::LoadOrGenerate(c) => (c, true),
-                                        LoadRequest::LoadFromCache(c) => (c, false),
-                                    };
-
-                                    match cache.load_chunk(coord) {
-                                        Ok(Some(data)) => {
-                                            if gen_tx.send((coord, Some(data))).is_err() { break; }
-                                        },
-                                        Ok(None) => {
-                                            if should_generate {
-                                                let data = wg.generate_chunk_data(coord);
-                                                if let Err(e) = cache.save_chunk(coord, &data) {
-                                                     eprintln!("Worker {}: Error saving newly generated chunk {:?}: {}", i, coord, e);
-                                                }
-                                                if gen_tx.send((coord, Some(data))).is_err() { break; }
-                                            } else if gen_tx.send((coord, None)).is_err() { break; }
-                                        },
-                                        Err(e) => {
-                                            eprintln!("Worker {}: Error loading chunk {:?} from cache: {}", i, coord, e);
-                                            if should_generate {
-                                                let data = wg.generate_chunk_data(coord);
-                                                 if let Err(e_save) = cache.save_chunk(coord, &data) {
-                                                     eprintln!("Worker {}: Error saving chunk {:?} after load fail: {}", i, coord, e_save);
-                                                }
-                                                if gen_tx.send((coord, Some(data))).is_err() { break; }
-                                            } else if gen_tx.send((coord, None)).is_err() { break; }
-                                        }
-                                    }
-                                },
-                                Err(_) => { break; }
-                            },
-                            recv(mesh_rx) -> msg => match msg {
-                                Ok((entity, coord, chunk_data, neighbors, lod)) => {
-                                    let mesh_result = mg.generate_chunk_mesh(
-                                        coord,
-                                        &chunk_data,
-                                        &neighbors,
-                                        &tm_layers,
-                                        lod,
-                                        &config,
-                                    );
-                                    if mesh_tx.send((entity, coord, mesh_result, lod)).is_err() {
-                                        break;
-                                    }
-                                },
-                                Err(_) => { break; }
-                            },
-                            recv(shutdown_rx_clone) -> _ => {
-                                break;
-                            }
-                        }
-                    }
-                })
-                .expect("Failed to spawn worker thread");
 
-            worker_handles.push(handle);
-        }
-
-        Self {
+        let context = WorkerContext {
             world_generator: resources.world_generator,
             mesh_generator: resources.mesh_generator,
             texture_manager_layers: resources.texture_manager_layers,
             chunk_cache: resources.chunk_cache,
             config: resources.config,
+            gen_cancellations: resources.gen_cancellations,
+            mesh_cancellations: resources.mesh_cancellations,
+            stats: resources.stats,
             gen_request_rx: channels.gen_request_rx,
             mesh_request_rx: channels.mesh_request_rx,
             gen_result_tx: channels.gen_result_tx,
             mesh_result_tx: channels.mesh_result_tx,
+            far_tile_request_rx: channels.far_tile_request_rx,
+            far_tile_result_tx: channels.far_tile_result_tx,
+            shutdown_rx,
+        };
+
+        let mut workers = WorkerThreads::new();
+        for i in 0..generation_threads {
+            Self::spawn_worker(&mut workers, WorkerRole::Generation, i, &context);
+        }
+        for i in 0..meshing_threads {
+            Self::spawn_worker(&mut workers, WorkerRole::Meshing, i, &context);
+        }
+
+        Self {
+            context,
+            generation_threads,
+            meshing_threads,
             shutdown_tx,
-            worker_handles,
+            workers,
         }
     }
 
+    pub fn thread_counts(&self) -> (usize, usize) {
+        (self.generation_threads, self.meshing_threads)
+    }
+
+    fn spawn_worker(
+        workers: &mut WorkerThreads,
+        role: WorkerRole,
+        index: usize,
+        context: &WorkerContext,
+    ) {
+        let context = context.clone();
+        workers.spawn(
+            format!("{}-worker-{}", role.name(), index),
+            move || match role {
+                WorkerRole::Generation => generation_worker_loop(index, &context),
+                WorkerRole::Meshing => meshing_worker_loop(&context),
+            },
+        );
+    }
+
+    pub fn respawn_dead_workers(&mut self) -> usize {
+        self.workers.respawn_dead()
+    }
+
     pub fn shutdown(self) {
-        drop(self.gen_request_rx);
-        drop(self.mesh_request_rx);
-        for _ in 0..self.worker_handles.len() {
+        drop(self.context);
+        for _ in 0..self.workers.len() {
             let _ = self.shutdown_tx.send(());
         }
         drop(self.shutdown_tx);
+        self.workers.join();
+    }
+}
+
+fn generation_worker_loop(index: usize, context: &WorkerContext) {
+    loop {
+        crossbeam_channel::select! {
+            recv(context.gen_request_rx) -> msg => match msg {
+                Ok(request) => {
+                    let coord = match request {
+                        LoadRequest::LoadOrGenerate(c) | LoadRequest::LoadFromCache(c) => c,
+                    };
+                    if context.gen_cancellations.take(&coord) {
+                        continue;
+                    }
+                    let start = Instant::now();
+                    let result = run_catching_panics(|| load_or_generate_chunk(index, context, request))
+                        .unwrap_or_else(|message| {
+                            eprintln!("Worker {}: Loading chunk {:?} panicked: {}", index, coord, message);
+                            (coord, Err(message))
+                        });
+                    context.stats.record(JobKind::Generation, start.elapsed());
+                    if !send_result(&context.gen_result_tx, result, &context.shutdown_rx) {
+                        break;
+                    }
+                },
+                Err(_) => { break; }
+            },
+            recv(context.far_tile_request_rx) -> msg => match msg {
+                Ok(tile) => {
+                    let start = Instant::now();
+                    let mesh = run_catching_panics(|| {
+                        generate_far_terrain_tile(
+                            &context.world_generator,
+                            tile,
+                            &context.texture_manager_layers,
+                            &context.config,
+                        )
+                    });
+                    if let Err(message) = &mesh {
+                        eprintln!("Worker {}: Far terrain tile {:?} panicked: {}", index, tile, message);
+                    }
+                    context.stats.record(JobKind::FarTerrain, start.elapsed());
+                    if !send_result(&context.far_tile_result_tx, (tile, mesh), &context.shutdown_rx) {
+                        break;
+                    }
+                },
+                Err(_) => { break; }
+            },
+            recv(context.shutdown_rx) -> _ => {
+                break;
+            }
+        }
+    }
+}
 
-        for (i, handle) in self.worker_handles.into_iter().enumerate() {
-            if let Err(e) = handle.join() {
-                eprintln!("WorkerPool: Worker thread {} panicked: {:?}", i, e);
+fn meshing_worker_loop(context: &WorkerContext) {
+    loop {
+        crossbeam_channel::select! {
+            recv(context.mesh_request_rx) -> msg => match msg {
+                Ok(request) => {
+                    let (entity, coord, lod) = (request.0, request.1, request.4);
+                    if context.mesh_cancellations.take(&coord) {
+                        continue;
+                    }
+                    let start = Instant::now();
+                    let result = run_catching_panics(|| mesh_chunk(context, request))
+                        .unwrap_or_else(|message| {
+                            eprintln!("Meshing chunk {:?} panicked: {}", coord, message);
+                            (entity, coord, Err(message), ChunkVisibility::all(), lod)
+                        });
+                    context.stats.record(JobKind::Meshing, start.elapsed());
+                    if !send_result(&context.mesh_result_tx, result, &context.shutdown_rx) {
+                        break;
+                    }
+                },
+                Err(_) => { break; }
+            },
+            recv(context.shutdown_rx) -> _ => {
+                break;
             }
         }
     }
 }
+
+fn load_or_generate_chunk(
+    index: usize,
+    context: &WorkerContext,
+    request: LoadRequest,
+) -> LoadResult {
+    let (coord, should_generate) = match request {
+        LoadRequest::LoadOrGenerate(c) => (c, true),
+        LoadRequest::LoadFromCache(c) => (c, false),
+    };
+    let cache = &context.chunk_cache;
+
+    match cache.load_chunk(coord) {
+        Ok(Some(data)) => return (coord, Ok(Some(data))),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!(
+                "Worker {}: Discarding unreadable cached chunk {:?}, it will be regenerated: {}",
+                index, coord, e
+            );
+        }
+    }
+    if !should_generate {
+        return (coord, Ok(None));
+    }
+
+    let data = context.world_generator.generate_chunk_data(coord);
+    if let Err(e) = cache.save_chunk(coord, &data) {
+        eprintln!(
+            "Worker {}: Error saving newly generated chunk {:?}: {}",
+            index, coord, e
+        );
+    }
+    (coord, Ok(Some(data)))
+}
+
+fn mesh_chunk(context: &WorkerContext, request: MeshRequestData) -> MeshResultData {
+    let (entity, coord, chunk_data, neighbors, lod, neighbor_lods) = request;
+    let config = &context.config;
+    let mg = &context.mesh_generator;
+    let tm_layers = &context.texture_manager_layers;
+
+    let mut mesh_result = mg.generate_chunk_mesh(
+        coord,
+        &chunk_data,
+        &neighbors,
+        &neighbor_lods,
+        tm_layers,
+        lod,
+        config,
+    );
+    if config.coarse_shadow_casters && lod < config.shadow_caster_lod {
+        if let Some(chunk_mesh) = mesh_result.as_mut() {
+            chunk_mesh.shadow = mg
+                .generate_chunk_mesh(
+                    coord,
+                    &chunk_data,
+                    &neighbors,
+                    &[config.shadow_caster_lod; 6],
+                    tm_layers,
+                    config.shadow_caster_lod,
+                    config,
+                )
+                .and_then(|coarse| coarse.opaque);
+        }
+    }
+    let visibility = mg.compute_chunk_visibility(&chunk_data, config);
+    (entity, coord, Ok(mesh_result), visibility, lod)
+}
@@ -0,0 +1,64 @@
+use crate::components::{BlockType, FLUID_SOURCE_LEVEL};
+use crate::resources::Config;
+
+// World-space block reads and writes for the systems that simulate blocks. GameState backs
+// it with the loaded chunk entities.
+pub trait BlockAccess {
+    fn config(&self) -> &Config;
+
+    // None when the chunk holding the block isn't loaded.
+    fn get_loaded_block_world(&self, world_x: i32, world_y: i32, world_z: i32)
+        -> Option<BlockType>;
+
+    fn get_fluid_level_world(&self, world_x: i32, world_y: i32, world_z: i32) -> u8;
+
+    // Returns false when the chunk holding the block isn't loaded.
+    fn set_fluid_world(
+        &mut self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+        block_type: BlockType,
+        level: u8,
+    ) -> bool;
+
+    fn get_block_world(&self, world_x: i32, world_y: i32, world_z: i32) -> BlockType {
+        self.get_loaded_block_world(world_x, world_y, world_z)
+            .unwrap_or(BlockType::Air)
+    }
+
+    fn set_block_world(
+        &mut self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+        block_type: BlockType,
+    ) -> bool {
+        self.set_fluid_world(world_x, world_y, world_z, block_type, FLUID_SOURCE_LEVEL)
+    }
+
+    // For changes the world makes on its own, like snow settling. Unlike player edits they
+    // don't get an untouched chunk saved; it is regenerated instead.
+    fn set_natural_block_world(
+        &mut self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+        block_type: BlockType,
+    ) -> bool {
+        self.set_block_world(world_x, world_y, world_z, block_type)
+    }
+
+    fn is_exposed_to_sky(&self, world_x: i32, world_y: i32, world_z: i32) -> bool {
+        let config = self.config();
+        let max_world_y = (config.max_chunk_y + 1) * config.chunk_height as i32;
+        for y in (world_y + 1)..max_world_y {
+            match self.get_loaded_block_world(world_x, y, world_z) {
+                Some(block) if block.is_culled_by() => return false,
+                Some(_) => {}
+                None => return true,
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,128 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaAllocation {
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl ArenaAllocation {
+    pub fn end(&self) -> usize {
+        self.offset + self.size
+    }
+}
+
+// First-fit free-list allocator over a linear range of elements. It only does the
+// bookkeeping; the renderer owns the GPU buffers the ranges refer to.
+pub struct BufferArena {
+    capacity: usize,
+    used: usize,
+    free_blocks: Vec<ArenaAllocation>,
+}
+
+impl BufferArena {
+    pub fn new(capacity: usize) -> Self {
+        let free_blocks = if capacity > 0 {
+            vec![ArenaAllocation {
+                offset: 0,
+                size: capacity,
+            }]
+        } else {
+            Vec::new()
+        };
+        Self {
+            capacity,
+            used: 0,
+            free_blocks,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    pub fn free_block_count(&self) -> usize {
+        self.free_blocks.len()
+    }
+
+    pub fn largest_free_block(&self) -> usize {
+        self.free_blocks.iter().map(|b| b.size).max().unwrap_or(0)
+    }
+
+    pub fn allocate(&mut self, size: usize) -> Option<ArenaAllocation> {
+        if size == 0 {
+            return None;
+        }
+
+        let index = self.free_blocks.iter().position(|b| b.size >= size)?;
+        let block = self.free_blocks[index];
+        let allocation = ArenaAllocation {
+            offset: block.offset,
+            size,
+        };
+
+        if block.size == size {
+            self.free_blocks.remove(index);
+        } else {
+            self.free_blocks[index] = ArenaAllocation {
+                offset: block.offset + size,
+                size: block.size - size,
+            };
+        }
+        self.used += size;
+        Some(allocation)
+    }
+
+    pub fn free(&mut self, allocation: ArenaAllocation) {
+        if allocation.size == 0 || allocation.end() > self.capacity {
+            return;
+        }
+
+        let index = self
+            .free_blocks
+            .partition_point(|b| b.offset < allocation.offset);
+
+        let overlaps_previous = index > 0 && self.free_blocks[index - 1].end() > allocation.offset;
+        let overlaps_next =
+            index < self.free_blocks.len() && allocation.end() > self.free_blocks[index].offset;
+        if overlaps_previous || overlaps_next {
+            eprintln!(
+                "BufferArena: Ignoring free of {:?}, range is already free",
+                allocation
+            );
+            return;
+        }
+
+        self.free_blocks.insert(index, allocation);
+        self.used -= allocation.size;
+
+        if index + 1 < self.free_blocks.len()
+            && self.free_blocks[index].end() == self.free_blocks[index + 1].offset
+        {
+            self.free_blocks[index].size += self.free_blocks[index + 1].size;
+            self.free_blocks.remove(index + 1);
+        }
+        if index > 0 && self.free_blocks[index - 1].end() == self.free_blocks[index].offset {
+            self.free_blocks[index - 1].size += self.free_blocks[index].size;
+            self.free_blocks.remove(index);
+        }
+    }
+
+    pub fn grow(&mut self, new_capacity: usize) {
+        if new_capacity <= self.capacity {
+            return;
+        }
+
+        let added = ArenaAllocation {
+            offset: self.capacity,
+            size: new_capacity - self.capacity,
+        };
+        match self.free_blocks.last_mut() {
+            Some(last) if last.end() == self.capacity => last.size += added.size,
+            _ => self.free_blocks.push(added),
+        }
+        self.capacity = new_capacity;
+    }
+}
@@ -1,4 +1,4 @@
-use crate::resources::Config;
+use crate::resources::{view_distance, Config};
 use glam::{Mat4, Vec3};
 
 #[derive(Clone, Copy, Debug)]
@@ -30,6 +30,59 @@ pub struct Frustum {
 }
 
 impl Frustum {
+    pub fn from_matrix(m: Mat4) -> Self {
+        let mut frustum = Frustum {
+            planes: [Plane {
+                normal: Vec3::ZERO,
+                distance: 0.0,
+            }; 6],
+        };
+
+        let p0 = m.row(3) + m.row(0);
+        frustum.planes[0] = Plane {
+            normal: Vec3::new(p0.x, p0.y, p0.z),
+            distance: p0.w,
+        };
+        frustum.planes[0].normalize();
+
+        let p1 = m.row(3) - m.row(0);
+        frustum.planes[1] = Plane {
+            normal: Vec3::new(p1.x, p1.y, p1.z),
+            distance: p1.w,
+        };
+        frustum.planes[1].normalize();
+
+        let p2 = m.row(3) + m.row(1);
+        frustum.planes[2] = Plane {
+            normal: Vec3::new(p2.x, p2.y, p2.z),
+            distance: p2.w,
+        };
+        frustum.planes[2].normalize();
+
+        let p3 = m.row(3) - m.row(1);
+        frustum.planes[3] = Plane {
+            normal: Vec3::new(p3.x, p3.y, p3.z),
+            distance: p3.w,
+        };
+        frustum.planes[3].normalize();
+
+        let p4 = m.row(2);
+        frustum.planes[4] = Plane {
+            normal: Vec3::new(p4.x, p4.y, p4.z),
+            distance: p4.w,
+        };
+        frustum.planes[4].normalize();
+
+        let p5 = m.row(3) - m.row(2);
+        frustum.planes[5] = Plane {
+            normal: Vec3::new(p5.x, p5.y, p5.z),
+            distance: p5.w,
+        };
+        frustum.planes[5].normalize();
+
+        frustum
+    }
+
     pub fn intersects_aabb(&self, center: Vec3, extents: Vec3) -> bool {
         for plane in &self.planes {
             let r = extents.x * plane.normal.x.abs()
@@ -65,10 +118,36 @@ impl Camera {
     pub fn z_far(&self) -> f32 {
         self.z_far
     }
+
+    pub fn z_near(&self) -> f32 {
+        self.z_near
+    }
+
+    pub fn frustum_corners(&mut self, near: f32, far: f32) -> [Vec3; 8] {
+        self.ensure_updated();
+        let inverse_view = self.view_matrix.inverse();
+        let tan_half_fov = (self.fov_y_radians / 2.0).tan();
+
+        let mut corners = [Vec3::ZERO; 8];
+        for (i, distance) in [near, far].into_iter().enumerate() {
+            let half_height = distance * tan_half_fov;
+            let half_width = half_height * self.aspect_ratio;
+            let view_corners = [
+                Vec3::new(-half_width, -half_height, -distance),
+                Vec3::new(half_width, -half_height, -distance),
+                Vec3::new(half_width, half_height, -distance),
+                Vec3::new(-half_width, half_height, -distance),
+            ];
+            for (j, corner) in view_corners.into_iter().enumerate() {
+                corners[i * 4 + j] = inverse_view.transform_point3(corner);
+            }
+        }
+        corners
+    }
     pub fn new(position: Vec3, target: Vec3, up: Vec3, aspect_ratio: f32, config: &Config) -> Self {
         let fov_y_radians = 90.0f32.to_radians();
         let z_near = 0.1;
-        let z_far = (config.render_distance as f32 + 1.0) * (config.chunk_width as f32) * 1.5;
+        let z_far = (view_distance(config) + config.chunk_width as f32) * 1.5;
 
         let mut camera = Self {
             position,
@@ -107,49 +186,7 @@ impl Camera {
     }
 
     fn calculate_frustum(&mut self) {
-        let m = self.view_projection_matrix;
-
-        let p0 = m.row(3) + m.row(0);
-        self.frustum.planes[0] = Plane {
-            normal: Vec3::new(p0.x, p0.y, p0.z),
-            distance: p0.w,
-        };
-        self.frustum.planes[0].normalize();
-
-        let p1 = m.row(3) - m.row(0);
-        self.frustum.planes[1] = Plane {
-            normal: Vec3::new(p1.x, p1.y, p1.z),
-            distance: p1.w,
-        };
-        self.frustum.planes[1].normalize();
-
-        let p2 = m.row(3) + m.row(1);
-        self.frustum.planes[2] = Plane {
-            normal: Vec3::new(p2.x, p2.y, p2.z),
-            distance: p2.w,
-        };
-        self.frustum.planes[2].normalize();
-
-        let p3 = m.row(3) - m.row(1);
-        self.frustum.planes[3] = Plane {
-            normal: Vec3::new(p3.x, p3.y, p3.z),
-            distance: p3.w,
-        };
-        self.frustum.planes[3].normalize();
-
-        let p4 = m.row(2);
-        self.frustum.planes[4] = Plane {
-            normal: Vec3::new(p4.x, p4.y, p4.z),
-            distance: p4.w,
-        };
-        self.frustum.planes[4].normalize();
-
-        let p5 = m.row(3) - m.row(2);
-        self.frustum.planes[5] = Plane {
-            normal: Vec3::new(p5.x, p5.y, p5.z),
-            distance: p5.w,
-        };
-        self.frustum.planes[5].normalize();
+        self.frustum = Frustum::from_matrix(self.view_projection_matrix);
     }
 
     pub fn update_aspect_ratio(&mut self, width: f32, height: f32) {
@@ -0,0 +1,34 @@
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin};
+use std::f64::consts::TAU;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloudMode {
+    Flat,
+    Raymarched,
+}
+
+// Tileable cloud density map. Each texture axis is wrapped around a circle in 4D noise space,
+// so the map repeats seamlessly when the clouds drift across the sky.
+pub fn generate_cloud_density(seed: u32, resolution: usize) -> Vec<u8> {
+    let noise = Fbm::<Perlin>::new(seed.wrapping_add(7))
+        .set_octaves(5)
+        .set_lacunarity(2.0)
+        .set_persistence(0.5);
+    let radius = 1.5;
+
+    let mut density = Vec::with_capacity(resolution * resolution);
+    for y in 0..resolution {
+        let angle_y = y as f64 / resolution as f64 * TAU;
+        for x in 0..resolution {
+            let angle_x = x as f64 / resolution as f64 * TAU;
+            let value = noise.get([
+                angle_x.cos() * radius,
+                angle_x.sin() * radius,
+                angle_y.cos() * radius,
+                angle_y.sin() * radius,
+            ]);
+            density.push(((value * 0.5 + 0.5).clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+    density
+}
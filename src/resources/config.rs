@@ -1,3 +1,5 @@
+use crate::components::LOD;
+use crate::resources::{CloudMode, ToneMapOperator};
 use std::collections::HashMap;
 use winit::keyboard::{Key, NamedKey};
 
@@ -21,8 +23,28 @@ pub struct Config {
     pub lod4_distance: i32,
     pub lod8_distance: i32,
     pub render_distance: i32,
+    pub unload_distance_padding: i32,
+    pub chunk_memory_budget_bytes: usize,
+    pub lod_vertical_weight: f32,
+    pub out_of_view_priority_penalty: f32,
+    pub max_in_flight_load_requests: usize,
+    pub max_in_flight_mesh_requests: usize,
+    pub worker_channel_capacity: usize,
+    pub generation_threads: usize,
+    pub meshing_threads: usize,
+    pub parallel_chunk_generation: bool,
+    pub worker_stats_interval: f32,
+    pub max_job_attempts: u32,
+    pub upload_budget_bytes_per_frame: usize,
+    pub upload_budget_ms_per_frame: f32,
+    pub spawn_budget_ms_per_frame: f32,
+    pub far_terrain_enabled: bool,
+    pub far_terrain_distance_multiplier: f32,
+    pub far_terrain_tile_size: usize,
+    pub far_terrain_sample_spacing: usize,
     pub world_seed: u32,
     pub day_cycle_speed: f32,
+    pub debug_title_interval: f32,
     pub chunk_width: usize,
     pub chunk_height: usize,
     pub chunk_depth: usize,
@@ -57,8 +79,89 @@ pub struct Config {
     pub exposed_stone_threshold: f64,
     pub seabed_gravel_threshold: f64,
     pub ice_patch_threshold: f64,
+    pub river_freq: f64,
+    pub river_width: f64,
+    pub river_depth: i32,
+    pub river_bank_height: i32,
+    pub river_max_carve: f64,
+    pub climate_freq: f64,
+    pub cold_snow_line_drop: f64,
+    pub fluid_tick_interval: f32,
+    pub max_fluid_updates_per_tick: usize,
+    pub falling_block_gravity: f32,
+    pub falling_block_max_speed: f32,
+    pub random_tick_interval: f32,
+    pub random_ticks_per_chunk: usize,
+    pub random_tick_distance: i32,
+    pub leaf_decay_distance: i32,
+    pub chunk_vertex_arena_capacity: usize,
+    pub chunk_index_arena_capacity: usize,
+    pub occlusion_culling: bool,
+    pub fog_enabled: bool,
+    pub fog_start_fraction: f32,
+    pub height_fog_density: f32,
+    pub height_fog_falloff: f32,
+    pub scattering_sky: bool,
+    pub sky_sun_intensity: f32,
+    pub sky_mie_anisotropy: f32,
+    pub clouds_enabled: bool,
+    pub cloud_mode: CloudMode,
+    pub cloud_altitude: f32,
+    pub cloud_thickness: f32,
+    pub cloud_coverage: f32,
+    pub cloud_scale: f32,
+    pub cloud_wind: glam::Vec2,
+    pub cloud_texture_resolution: usize,
+    pub cloud_raymarch_steps: i32,
+    pub cloud_shadows: bool,
+    pub weather_enabled: bool,
+    pub weather_min_duration: f32,
+    pub weather_max_duration: f32,
+    pub weather_transition_duration: f32,
+    pub rain_darkening: f32,
+    pub thunderstorm_darkening: f32,
+    pub snow_darkening: f32,
+    pub overcast_cloud_coverage: f32,
+    pub lightning_interval: f32,
+    pub precipitation_radius: usize,
+    pub precipitation_height: f32,
+    pub heightmap_refresh_interval: f32,
+    pub rain_particle_count: usize,
+    pub snow_particle_count: usize,
+    pub rain_fall_speed: f32,
+    pub snow_fall_speed: f32,
+    pub hdr_enabled: bool,
+    pub tone_map_operator: ToneMapOperator,
+    pub exposure_adaptation: bool,
+    pub exposure_adaptation_speed: f32,
+    pub exposure_key: f32,
+    pub min_exposure: f32,
+    pub max_exposure: f32,
+    pub manual_exposure: f32,
+    pub bloom_enabled: bool,
+    pub bloom_threshold: f32,
+    pub bloom_soft_knee: f32,
+    pub bloom_intensity: f32,
+    pub bloom_levels: usize,
+    pub gamma: f32,
+    pub sun_brightness: f32,
+    pub water_rendering: bool,
+    pub water_color: glam::Vec3,
+    pub water_absorption: f32,
+    pub water_wave_strength: f32,
+    pub water_wave_speed: f32,
+    pub water_refraction_strength: f32,
+    pub transparent_sort_distance: i32,
+    pub max_transparent_resorts_per_frame: usize,
     pub shadow_distance: i32,
     pub shadow_map_resolution: u32,
+    pub shadow_cascade_count: usize,
+    pub shadow_cascade_split_lambda: f32,
+    pub shadow_cascade_blend_fraction: f32,
+    pub shadow_cascade_pcf_radius: [i32; 4],
+    pub shadow_bias_texels: f32,
+    pub coarse_shadow_casters: bool,
+    pub shadow_caster_lod: LOD,
 }
 
 impl Config {
@@ -81,8 +184,28 @@ impl Config {
             lod8_distance: 14,   // 28
             lod4_distance: 15,   // 30
             render_distance: 16, // 32
+            unload_distance_padding: 2,
+            chunk_memory_budget_bytes: 128 * 1024 * 1024,
+            lod_vertical_weight: 1.0,
+            out_of_view_priority_penalty: 4.0,
+            max_in_flight_load_requests: 64,
+            max_in_flight_mesh_requests: 32,
+            worker_channel_capacity: 64,
+            generation_threads: 0,
+            meshing_threads: 0,
+            parallel_chunk_generation: false,
+            worker_stats_interval: 1.0,
+            max_job_attempts: 3,
+            upload_budget_bytes_per_frame: 8 * 1024 * 1024,
+            upload_budget_ms_per_frame: 4.0,
+            spawn_budget_ms_per_frame: 2.0,
+            far_terrain_enabled: true,
+            far_terrain_distance_multiplier: 3.0,
+            far_terrain_tile_size: 64,
+            far_terrain_sample_spacing: 8,
             world_seed: 42069,
             day_cycle_speed: 0.01,
+            debug_title_interval: 1.0,
             chunk_width: 16,
             chunk_height: 16,
             chunk_depth: 16,
@@ -117,8 +240,89 @@ impl Config {
             exposed_stone_threshold: 0.6,
             seabed_gravel_threshold: 0.2,
             ice_patch_threshold: 0.4,
+            river_freq: 1.0 / 900.0,
+            river_width: 0.045,
+            river_depth: 3,
+            river_bank_height: 2,
+            river_max_carve: 160.0,
+            climate_freq: 1.0 / 1600.0,
+            cold_snow_line_drop: 200.0,
+            fluid_tick_interval: 0.25,
+            max_fluid_updates_per_tick: 4096,
+            falling_block_gravity: 32.0,
+            falling_block_max_speed: 40.0,
+            random_tick_interval: 0.05,
+            random_ticks_per_chunk: 3,
+            random_tick_distance: 8,
+            leaf_decay_distance: 4,
+            chunk_vertex_arena_capacity: 1 << 20,
+            chunk_index_arena_capacity: 3 << 19,
+            occlusion_culling: true,
+            fog_enabled: true,
+            fog_start_fraction: 0.6,
+            height_fog_density: 0.01,
+            height_fog_falloff: 0.08,
+            scattering_sky: false,
+            sky_sun_intensity: 12.0,
+            sky_mie_anisotropy: 0.76,
+            clouds_enabled: true,
+            cloud_mode: CloudMode::Flat,
+            cloud_altitude: 192.0,
+            cloud_thickness: 24.0,
+            cloud_coverage: 0.45,
+            cloud_scale: 1024.0,
+            cloud_wind: glam::Vec2::new(3.0, 1.0),
+            cloud_texture_resolution: 256,
+            cloud_raymarch_steps: 32,
+            cloud_shadows: true,
+            weather_enabled: true,
+            weather_min_duration: 120.0,
+            weather_max_duration: 480.0,
+            weather_transition_duration: 20.0,
+            rain_darkening: 0.35,
+            thunderstorm_darkening: 0.6,
+            snow_darkening: 0.2,
+            overcast_cloud_coverage: 0.85,
+            lightning_interval: 8.0,
+            precipitation_radius: 32,
+            precipitation_height: 40.0,
+            heightmap_refresh_interval: 1.0,
+            rain_particle_count: 8000,
+            snow_particle_count: 6000,
+            rain_fall_speed: 20.0,
+            snow_fall_speed: 2.5,
+            hdr_enabled: true,
+            tone_map_operator: ToneMapOperator::Aces,
+            exposure_adaptation: true,
+            exposure_adaptation_speed: 1.5,
+            exposure_key: 0.18,
+            min_exposure: 0.25,
+            max_exposure: 4.0,
+            manual_exposure: 1.0,
+            bloom_enabled: true,
+            bloom_threshold: 1.0,
+            bloom_soft_knee: 0.5,
+            bloom_intensity: 0.6,
+            bloom_levels: 5,
+            gamma: 2.2,
+            sun_brightness: 6.0,
+            water_rendering: true,
+            water_color: glam::Vec3::new(0.05, 0.25, 0.35),
+            water_absorption: 0.35,
+            water_wave_strength: 0.15,
+            water_wave_speed: 1.2,
+            water_refraction_strength: 0.02,
+            transparent_sort_distance: 4,
+            max_transparent_resorts_per_frame: 8,
             shadow_distance: 8,
-            shadow_map_resolution: 4096,
+            shadow_map_resolution: 2048,
+            shadow_cascade_count: 4,
+            shadow_cascade_split_lambda: 0.75,
+            shadow_cascade_blend_fraction: 0.1,
+            shadow_cascade_pcf_radius: [2, 2, 1, 1],
+            shadow_bias_texels: 1.5,
+            coarse_shadow_casters: false,
+            shadow_caster_lod: LOD::LOD2,
         }
     }
 
@@ -0,0 +1,180 @@
+use crate::components::world_to_chunk_coords;
+use crate::resources::{Config, Mesh, WorldGenerator};
+use fnv::FnvHashMap;
+use glam::{Vec2, Vec3};
+use std::collections::HashMap;
+
+pub type FarTileCoord = (i32, i32);
+
+pub struct FarTerrainTile {
+    pub mesh_id: usize,
+    pub min_height: f32,
+    pub max_height: f32,
+}
+
+// Heightfield tiles drawn past the loaded chunks. Tiles are keyed by their XZ tile coordinate;
+// FarTerrainSystem decides which ones exist.
+pub struct FarTerrain {
+    pub tiles: FnvHashMap<FarTileCoord, FarTerrainTile>,
+}
+
+impl FarTerrain {
+    pub fn new() -> Self {
+        Self {
+            tiles: FnvHashMap::default(),
+        }
+    }
+}
+
+impl Default for FarTerrain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn far_terrain_radius(config: &Config) -> f32 {
+    config.render_distance as f32
+        * config.far_terrain_distance_multiplier
+        * config.chunk_width as f32
+}
+
+// Inside this horizontal radius every column's ground lies in a chunk within loading range,
+// whatever its height. Chunks load by weighted 3D distance, so the radius shrinks as the
+// camera moves away from the ground it could be looking at.
+pub fn far_terrain_inner_radius(config: &Config, camera_chunk_y: i32) -> f32 {
+    let vertical = (camera_chunk_y - config.min_chunk_y)
+        .max(config.max_chunk_y - camera_chunk_y)
+        .max(0) as f32
+        * config.lod_vertical_weight;
+    let render_distance = config.render_distance as f32;
+    let reach = (render_distance * render_distance - vertical * vertical)
+        .max(0.0)
+        .sqrt();
+    // A point's chunk offset can be up to a chunk diagonal further than its own distance.
+    (reach - std::f32::consts::SQRT_2).max(0.0) * config.chunk_width as f32
+}
+
+// How far anything is drawn: the far terrain edge when it is on, the chunk edge otherwise.
+pub fn view_distance(config: &Config) -> f32 {
+    if config.far_terrain_enabled {
+        far_terrain_radius(config)
+    } else {
+        config.render_distance as f32 * config.chunk_width as f32
+    }
+}
+
+pub fn far_tile_origin(tile: FarTileCoord, config: &Config) -> Vec2 {
+    let size = config.far_terrain_tile_size as f32;
+    Vec2::new(tile.0 as f32 * size, tile.1 as f32 * size)
+}
+
+// Tiles overlapping the ring between the inner and outer radius, nearest first.
+pub fn far_tiles_in_range(camera_position: Vec3, config: &Config) -> Vec<FarTileCoord> {
+    let size = config.far_terrain_tile_size as f32;
+    let camera_xz = Vec2::new(camera_position.x, camera_position.z);
+    let camera_chunk = world_to_chunk_coords(
+        config,
+        camera_position.x.floor() as i32,
+        camera_position.y.floor() as i32,
+        camera_position.z.floor() as i32,
+    );
+    let outer = far_terrain_radius(config);
+    let inner = far_terrain_inner_radius(config, camera_chunk.1);
+    let reach = (outer / size).ceil() as i32 + 1;
+    let camera_tile = (
+        (camera_xz.x / size).floor() as i32,
+        (camera_xz.y / size).floor() as i32,
+    );
+
+    let mut tiles: Vec<(f32, FarTileCoord)> = Vec::new();
+    for dz in -reach..=reach {
+        for dx in -reach..=reach {
+            let tile = (camera_tile.0 + dx, camera_tile.1 + dz);
+            let min = far_tile_origin(tile, config);
+            let max = min + Vec2::splat(size);
+            let nearest = camera_xz.clamp(min, max).distance(camera_xz);
+            let farthest = [min, max, Vec2::new(min.x, max.y), Vec2::new(max.x, min.y)]
+                .iter()
+                .map(|corner| corner.distance(camera_xz))
+                .fold(0.0, f32::max);
+            if nearest <= outer && farthest >= inner {
+                tiles.push((nearest, tile));
+            }
+        }
+    }
+    tiles.sort_by(|a, b| a.0.total_cmp(&b.0));
+    tiles.into_iter().map(|(_, tile)| tile).collect()
+}
+
+// One quad per sample cell, textured with the top of the column's surface block. Vertices
+// are in world space, like the chunk meshes in the shared arena.
+pub fn generate_far_terrain_tile(
+    world_generator: &WorldGenerator,
+    tile: FarTileCoord,
+    texture_layers: &HashMap<String, f32>,
+    config: &Config,
+) -> Mesh {
+    let spacing = config.far_terrain_sample_spacing.max(1) as i32;
+    let cells = (config.far_terrain_tile_size as i32 / spacing).max(1);
+    let origin = far_tile_origin(tile, config);
+    let (origin_x, origin_z) = (origin.x as i32, origin.y as i32);
+
+    // One extra sample on every side for the normals.
+    let samples = (cells + 3) as usize;
+    let mut heights = vec![0.0f32; samples * samples];
+    let mut layers = vec![0.0f32; samples * samples];
+    for sz in 0..samples {
+        for sx in 0..samples {
+            let world_x = origin_x + (sx as i32 - 1) * spacing;
+            let world_z = origin_z + (sz as i32 - 1) * spacing;
+            let (height, block) = world_generator.surface_block(world_x, world_z);
+            heights[sz * samples + sx] = height as f32 + 0.5;
+            layers[sz * samples + sx] = block
+                .get_face_textures()
+                .and_then(|textures| texture_layers.get(textures[0]).copied())
+                .unwrap_or(0.0);
+        }
+    }
+
+    let height_at = |gx: i32, gz: i32| heights[(gz + 1) as usize * samples + (gx + 1) as usize];
+    let normal_at = |gx: i32, gz: i32| {
+        let dx = height_at(gx + 1, gz) - height_at(gx - 1, gz);
+        let dz = height_at(gx, gz + 1) - height_at(gx, gz - 1);
+        Vec3::new(-dx, 2.0 * spacing as f32, -dz).normalize()
+    };
+
+    let mut vertices = Vec::with_capacity((cells * cells * 4 * 9) as usize);
+    let mut indices = Vec::with_capacity((cells * cells * 6) as usize);
+    for gz in 0..cells {
+        for gx in 0..cells {
+            let layer = layers[(gz + 1) as usize * samples + (gx + 1) as usize];
+            let index_offset = (vertices.len() / 9) as u32;
+            for (cx, cz) in [(gx, gz), (gx, gz + 1), (gx + 1, gz + 1), (gx + 1, gz)] {
+                let x = (origin_x + cx * spacing) as f32 - 0.5;
+                let z = (origin_z + cz * spacing) as f32 - 0.5;
+                let normal = normal_at(cx, cz);
+                vertices.extend_from_slice(&[
+                    x,
+                    height_at(cx, cz),
+                    z,
+                    x,
+                    z,
+                    layer,
+                    normal.x,
+                    normal.y,
+                    normal.z,
+                ]);
+            }
+            indices.extend_from_slice(&[
+                index_offset,
+                index_offset + 1,
+                index_offset + 2,
+                index_offset,
+                index_offset + 2,
+                index_offset + 3,
+            ]);
+        }
+    }
+
+    Mesh { vertices, indices }
+}
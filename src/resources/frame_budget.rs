@@ -0,0 +1,49 @@
+use std::time::{Duration, Instant};
+
+// Caps how much main-thread work (GPU uploads, ECS spawns) one frame may take on. The first
+// item is always accepted so work keeps moving even if a single item is over budget; anything
+// refused is meant to be carried to the next frame.
+pub struct FrameBudget {
+    max_bytes: usize,
+    max_duration: Duration,
+    start: Instant,
+    spent_bytes: usize,
+    items: usize,
+}
+
+impl FrameBudget {
+    pub fn new(max_bytes: usize, max_millis: f32) -> Self {
+        Self {
+            max_bytes,
+            max_duration: Duration::from_secs_f32(max_millis.max(0.0) / 1000.0),
+            start: Instant::now(),
+            spent_bytes: 0,
+            items: 0,
+        }
+    }
+
+    pub fn from_millis(max_millis: f32) -> Self {
+        Self::new(usize::MAX, max_millis)
+    }
+
+    // Accepts an item of `bytes` if it fits in what is left of the budget.
+    pub fn try_spend(&mut self, bytes: usize) -> bool {
+        if self.items > 0
+            && (self.spent_bytes.saturating_add(bytes) > self.max_bytes
+                || self.start.elapsed() >= self.max_duration)
+        {
+            return false;
+        }
+        self.spent_bytes = self.spent_bytes.saturating_add(bytes);
+        self.items += 1;
+        true
+    }
+
+    pub fn spent_bytes(&self) -> usize {
+        self.spent_bytes
+    }
+
+    pub fn items(&self) -> usize {
+        self.items
+    }
+}
@@ -0,0 +1,114 @@
+use fnv::FnvHashMap;
+use std::collections::BTreeMap;
+use std::hash::Hash;
+
+struct LruEntry<V> {
+    tick: u64,
+    bytes: usize,
+    value: V,
+}
+
+// In-memory cache bounded by the total size its entries report, evicting the least recently
+// used entries first once the budget is exceeded.
+pub struct LruCache<K, V> {
+    max_bytes: usize,
+    bytes: usize,
+    entries: FnvHashMap<K, LruEntry<V>>,
+    // Recency order: oldest tick first.
+    order: BTreeMap<u64, K>,
+    next_tick: u64,
+    evictions: u64,
+}
+
+impl<K: Copy + Eq + Hash, V> LruCache<K, V> {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            bytes: 0,
+            entries: FnvHashMap::default(),
+            order: BTreeMap::new(),
+            next_tick: 0,
+            evictions: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    // Total entries dropped to stay under budget since the cache was created.
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
+    pub fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = max_bytes;
+        self.evict_to_budget();
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    // Inserts or replaces `key` as the most recently used entry. An entry larger than the
+    // whole budget is not kept.
+    pub fn insert(&mut self, key: K, value: V, bytes: usize) {
+        self.remove(&key);
+        if bytes > self.max_bytes {
+            return;
+        }
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        self.order.insert(tick, key);
+        self.entries.insert(key, LruEntry { tick, bytes, value });
+        self.bytes += bytes;
+        self.evict_to_budget();
+    }
+
+    // Looks up `key` and marks it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let entry = self.entries.get_mut(key)?;
+        self.order.remove(&entry.tick);
+        entry.tick = self.next_tick;
+        self.next_tick += 1;
+        self.order.insert(entry.tick, *key);
+        Some(&entry.value)
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let entry = self.entries.remove(key)?;
+        self.order.remove(&entry.tick);
+        self.bytes -= entry.bytes;
+        Some(entry.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.bytes = 0;
+    }
+
+    fn evict_to_budget(&mut self) {
+        while self.bytes > self.max_bytes {
+            let Some((_, key)) = self.order.pop_first() else {
+                break;
+            };
+            if let Some(entry) = self.entries.remove(&key) {
+                self.bytes -= entry.bytes;
+                self.evictions += 1;
+            }
+        }
+    }
+}
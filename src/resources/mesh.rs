@@ -1,4 +1,7 @@
 use fnv::FnvHashMap;
+use glam::Vec3;
+
+const VERTEX_FLOATS: usize = 9;
 
 #[derive(Clone)]
 pub struct Mesh {
@@ -6,10 +9,62 @@ pub struct Mesh {
     pub indices: Vec<u32>,
 }
 
+impl Mesh {
+    pub fn byte_size(&self) -> usize {
+        std::mem::size_of_val(self.vertices.as_slice())
+            + std::mem::size_of_val(self.indices.as_slice())
+    }
+
+    fn vertex_position(&self, index: u32) -> Vec3 {
+        let start = index as usize * VERTEX_FLOATS;
+        Vec3::new(
+            self.vertices[start],
+            self.vertices[start + 1],
+            self.vertices[start + 2],
+        )
+    }
+
+    pub fn sort_triangles_back_to_front(&mut self, view_pos: Vec3) {
+        let mut triangles: Vec<(f32, [u32; 3])> = self
+            .indices
+            .chunks_exact(3)
+            .map(|triangle| {
+                let centroid = (self.vertex_position(triangle[0])
+                    + self.vertex_position(triangle[1])
+                    + self.vertex_position(triangle[2]))
+                    / 3.0;
+                (
+                    centroid.distance_squared(view_pos),
+                    [triangle[0], triangle[1], triangle[2]],
+                )
+            })
+            .collect();
+
+        triangles.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        self.indices.clear();
+        for (_, triangle) in triangles {
+            self.indices.extend_from_slice(&triangle);
+        }
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct ChunkMeshData {
     pub opaque: Option<Mesh>,
     pub transparent: Option<Mesh>,
+    pub water: Option<Mesh>,
+    pub shadow: Option<Mesh>,
+}
+
+impl ChunkMeshData {
+    pub fn byte_size(&self) -> usize {
+        [&self.opaque, &self.transparent, &self.water, &self.shadow]
+            .into_iter()
+            .flatten()
+            .map(Mesh::byte_size)
+            .sum()
+    }
 }
 
 pub struct MeshRegistry {
@@ -1,4 +1,7 @@
-use crate::components::{BlockType, ChunkCoord, ChunkData, LOD};
+use crate::components::{
+    fluid_surface_height, BlockType, ChunkCoord, ChunkData, ChunkVisibility, FLUID_SOURCE_LEVEL,
+    LOD,
+};
 use crate::resources::{ChunkMeshData, Config, Mesh};
 use std::collections::HashMap;
 
@@ -51,6 +54,56 @@ struct FaceParams {
     layer_index: f32,
     scale: f32,
     normal: [f32; 3],
+    bottom: f32,
+    top: f32,
+}
+
+// Chunk boundary planes shared with a finer-LOD neighbor, and that neighbor's block size.
+// Face edges lying on one of these planes get extra vertices on the neighbor's grid so the
+// two meshes share vertices instead of meeting in T-junctions.
+#[derive(Clone, Copy, Default)]
+struct SeamPlanes {
+    factors: [Option<usize>; 6],
+    positions: [f32; 6],
+}
+
+impl SeamPlanes {
+    fn new(
+        neighbors: &[Option<ChunkData>; 6],
+        neighbor_lods: &[LOD; 6],
+        downsample_factor: usize,
+        config: &Config,
+    ) -> Self {
+        let mut factors = [None; 6];
+        for (face_index, factor) in factors.iter_mut().enumerate() {
+            let neighbor_factor = neighbor_lods[face_index].downsample_factor();
+            if neighbors[face_index].is_some() && neighbor_factor < downsample_factor {
+                *factor = Some(neighbor_factor);
+            }
+        }
+        Self {
+            factors,
+            positions: [
+                config.chunk_width as f32 - 0.5,
+                -0.5,
+                config.chunk_height as f32 - 0.5,
+                -0.5,
+                config.chunk_depth as f32 - 0.5,
+                -0.5,
+            ],
+        }
+    }
+
+    fn edge_step(&self, a: [f32; 3], b: [f32; 3]) -> Option<usize> {
+        (0..6)
+            .filter_map(|face_index| {
+                let factor = self.factors[face_index]?;
+                let axis = face_index / 2;
+                let plane = self.positions[face_index];
+                ((a[axis] - plane).abs() < 1e-4 && (b[axis] - plane).abs() < 1e-4).then_some(factor)
+            })
+            .min()
+    }
 }
 
 pub struct MeshGenerator {}
@@ -60,11 +113,13 @@ impl MeshGenerator {
         Self {}
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn generate_chunk_mesh(
         &self,
         chunk_coord: ChunkCoord,
         chunk_data: &ChunkData,
         neighbors: &[Option<ChunkData>; 6],
+        neighbor_lods: &[LOD; 6],
         texture_layers: &HashMap<String, f32>,
         lod: LOD,
         config: &Config,
@@ -77,12 +132,16 @@ impl MeshGenerator {
         let mut transparent_indices: Vec<u32> = Vec::new();
         let mut transparent_index_offset: u32 = 0;
 
+        let mut water_vertices: Vec<f32> = Vec::new();
+        let mut water_indices: Vec<u32> = Vec::new();
+        let mut water_index_offset: u32 = 0;
+
         let scale_factor = lod.scale_factor();
         let downsample_factor = lod.downsample_factor();
 
-        if config.chunk_width % downsample_factor != 0
-            || config.chunk_height % downsample_factor != 0
-            || config.chunk_depth % downsample_factor != 0
+        if !config.chunk_width.is_multiple_of(downsample_factor)
+            || !config.chunk_height.is_multiple_of(downsample_factor)
+            || !config.chunk_depth.is_multiple_of(downsample_factor)
         {
             eprintln!("Warning: Chunk dimensions ({},{},{}) not divisible by downsample factor {} for LOD {:?}. Skipping mesh generation for {:?}.",
                 config.chunk_width, config.chunk_height, config.chunk_depth, downsample_factor, lod, chunk_coord);
@@ -105,6 +164,9 @@ impl MeshGenerator {
             chunk_data
         };
 
+        let seams = SeamPlanes::new(neighbors, neighbor_lods, downsample_factor, config);
+        let mut face_pieces: Vec<([f32; 3], f32, f32, f32)> = Vec::new();
+
         for ey in 0..effective_height {
             for ez in 0..effective_depth {
                 for ex in 0..effective_width {
@@ -126,18 +188,50 @@ impl MeshGenerator {
                         None => continue,
                     };
 
+                    let fluid_height = if downsample_factor == 1 && current_block_type.is_water() {
+                        Some(Self::lod1_fluid_height(
+                            chunk_data, neighbors, ex as i32, ey as i32, ez as i32, config,
+                        ))
+                    } else {
+                        None
+                    };
+
+                    // Coarse water gets the same absolute surface drop as a LOD1 source block.
+                    let coarse_water_top = if config.water_rendering
+                        && downsample_factor > 1
+                        && current_block_type.is_water()
+                    {
+                        let covered = ey + 1 < effective_height
+                            && data_to_mesh
+                                .get_effective_block(
+                                    ex,
+                                    ey + 1,
+                                    ez,
+                                    effective_width,
+                                    effective_depth,
+                                    config,
+                                )
+                                .is_water();
+                        let drop = 1.0 - fluid_surface_height(FLUID_SOURCE_LEVEL, covered);
+                        1.0 - drop / scale_factor
+                    } else {
+                        1.0
+                    };
+
                     for face_index in 0..6 {
                         let (nex, ney, nez) =
                             Self::get_effective_neighbor_coords(ex, ey, ez, face_index);
 
-                        let neighbor_block_type = if nex < 0
+                        let crosses_border = nex < 0
                             || nex >= effective_width as i32
                             || ney < 0
                             || ney >= effective_height as i32
                             || nez < 0
-                            || nez >= effective_depth as i32
-                        {
-                            let neighbor_chunk_index = Self::face_to_neighbor_index(face_index);
+                            || nez >= effective_depth as i32;
+                        let neighbor_chunk_index = Self::face_to_neighbor_index(face_index);
+
+                        face_pieces.clear();
+                        let neighbor_block_type = if crosses_border {
                             match &neighbors[neighbor_chunk_index] {
                                 Some(neighbor_chunk_data) => {
                                     let (nnex, nney, nnez) = Self::wrap_effective_neighbor_coords(
@@ -149,21 +243,20 @@ impl MeshGenerator {
                                         effective_depth,
                                     );
 
-                                    if downsample_factor > 1 {
-                                        let x_start = nnex * downsample_factor;
-                                        let y_start = nney * downsample_factor;
-                                        let z_start = nnez * downsample_factor;
-                                        Self::calculate_representative_block(
-                                            neighbor_chunk_data,
-                                            x_start,
-                                            y_start,
-                                            z_start,
-                                            downsample_factor,
-                                            config,
-                                        )
-                                    } else {
-                                        neighbor_chunk_data.get_block(config, nnex, nney, nnez)
-                                    }
+                                    // Look at the neighbor the way it is rendered: a coarser
+                                    // neighbor is sampled at its own block size, so nothing is
+                                    // culled against detail it does not draw.
+                                    let neighbor_factor = neighbor_lods[neighbor_chunk_index]
+                                        .downsample_factor()
+                                        .max(downsample_factor);
+                                    Self::block_at_factor(
+                                        neighbor_chunk_data,
+                                        nnex * downsample_factor,
+                                        nney * downsample_factor,
+                                        nnez * downsample_factor,
+                                        neighbor_factor,
+                                        config,
+                                    )
                                 }
                                 None => BlockType::Air,
                             }
@@ -178,23 +271,58 @@ impl MeshGenerator {
                             )
                         };
 
-                        let should_draw_face = match neighbor_block_type {
-                            BlockType::Air => true,
-                            neighbor if !neighbor.is_culled_by() => {
-                                current_block_type.is_culled_by()
-                                    || current_block_type != neighbor_block_type
+                        let mut should_draw_face =
+                            Self::should_draw_face(current_block_type, neighbor_block_type);
+
+                        let (face_bottom, face_top) = match fluid_height {
+                            Some(own_height) => {
+                                let is_side_face = !matches!(face_index, 2 | 3);
+                                if is_side_face && neighbor_block_type.is_water() {
+                                    let neighbor_height = Self::lod1_fluid_height(
+                                        chunk_data, neighbors, nex, ney, nez, config,
+                                    );
+                                    if neighbor_height < own_height {
+                                        should_draw_face = true;
+                                    }
+                                    (neighbor_height, own_height)
+                                } else {
+                                    (0.0, own_height)
+                                }
                             }
-                            _ => !current_block_type.is_culled_by(),
+                            None => (0.0, coarse_water_top),
                         };
 
-                        if should_draw_face {
-                            let pos_x =
-                                (ex * downsample_factor) as f32 + (scale_factor / 2.0) - 0.5;
-                            let pos_y =
-                                (ey * downsample_factor) as f32 + (scale_factor / 2.0) - 0.5;
-                            let pos_z =
-                                (ez * downsample_factor) as f32 + (scale_factor / 2.0) - 0.5;
+                        let cell_origin = [
+                            ex * downsample_factor,
+                            ey * downsample_factor,
+                            ez * downsample_factor,
+                        ];
+                        match (crosses_border, seams.factors[face_index]) {
+                            (true, Some(neighbor_factor)) => {
+                                if let Some(neighbor_chunk_data) = &neighbors[neighbor_chunk_index]
+                                {
+                                    Self::collect_stitched_pieces(
+                                        current_block_type,
+                                        neighbor_chunk_data,
+                                        cell_origin,
+                                        downsample_factor,
+                                        neighbor_factor,
+                                        face_index,
+                                        (face_bottom, face_top),
+                                        config,
+                                        &mut face_pieces,
+                                    );
+                                }
+                            }
+                            _ if should_draw_face => {
+                                let center =
+                                    cell_origin.map(|c| c as f32 + scale_factor / 2.0 - 0.5);
+                                face_pieces.push((center, scale_factor, face_bottom, face_top));
+                            }
+                            _ => {}
+                        }
 
+                        if !face_pieces.is_empty() {
                             let texture_name = face_textures[Self::face_texture_index(face_index)];
                             let layer_index = *texture_layers.get(texture_name).unwrap_or_else(|| {
                                 eprintln!(
@@ -204,19 +332,19 @@ impl MeshGenerator {
                                 &0.0
                             });
 
-                            let normal = match face_index {
-                                0 => [1.0, 0.0, 0.0],  // Right (+X)
-                                1 => [-1.0, 0.0, 0.0], // Left (-X)
-                                2 => [0.0, 1.0, 0.0],  // Top (+Y)
-                                3 => [0.0, -1.0, 0.0], // Bottom (-Y)
-                                4 => [0.0, 0.0, 1.0],  // Front (+Z)
-                                5 => [0.0, 0.0, -1.0], // Back (-Z)
-                                _ => [0.0, 1.0, 0.0],  // Default Up
-                            };
+                            let normal = Self::face_normal(face_index);
 
                             let is_transparent = !current_block_type.is_culled_by();
+                            let is_water_surface =
+                                config.water_rendering && current_block_type.is_water();
                             let (target_vertices, target_indices, target_index_offset) =
-                                if is_transparent {
+                                if is_water_surface {
+                                    (
+                                        &mut water_vertices,
+                                        &mut water_indices,
+                                        &mut water_index_offset,
+                                    )
+                                } else if is_transparent {
                                     (
                                         &mut transparent_vertices,
                                         &mut transparent_indices,
@@ -230,18 +358,23 @@ impl MeshGenerator {
                                     )
                                 };
 
-                            Self::add_scaled_face(
-                                FaceParams {
-                                    position: [pos_x, pos_y, pos_z],
-                                    face_index,
-                                    layer_index,
-                                    scale: scale_factor,
-                                    normal,
-                                },
-                                target_vertices,
-                                target_indices,
-                                target_index_offset,
-                            );
+                            for &(position, scale, bottom, top) in &face_pieces {
+                                Self::add_scaled_face(
+                                    FaceParams {
+                                        position,
+                                        face_index,
+                                        layer_index,
+                                        scale,
+                                        normal,
+                                        bottom,
+                                        top,
+                                    },
+                                    &seams,
+                                    target_vertices,
+                                    target_indices,
+                                    target_index_offset,
+                                );
+                            }
                         }
                     }
                 }
@@ -266,17 +399,308 @@ impl MeshGenerator {
             None
         };
 
-        if opaque_mesh.is_some() || transparent_mesh.is_some() {
+        let water_mesh = if !water_vertices.is_empty() {
+            Some(Mesh {
+                vertices: water_vertices,
+                indices: water_indices,
+            })
+        } else {
+            None
+        };
+
+        if opaque_mesh.is_some() || transparent_mesh.is_some() || water_mesh.is_some() {
             Some(ChunkMeshData {
                 opaque: opaque_mesh,
                 transparent: transparent_mesh,
+                water: water_mesh,
+                shadow: None,
             })
         } else {
             None
         }
     }
 
-    fn downsample_chunk(
+    pub fn compute_chunk_visibility(
+        &self,
+        chunk_data: &ChunkData,
+        config: &Config,
+    ) -> ChunkVisibility {
+        let (width, height, depth) = (config.chunk_width, config.chunk_height, config.chunk_depth);
+        let see_through_count = chunk_data
+            .blocks
+            .iter()
+            .filter(|block| !block.is_culled_by())
+            .count();
+        if see_through_count == 0 {
+            return ChunkVisibility::none();
+        }
+        if see_through_count == chunk_data.blocks.len() {
+            return ChunkVisibility::all();
+        }
+
+        let index = |x: usize, y: usize, z: usize| y * width * depth + z * width + x;
+        let mut visited = vec![false; width * height * depth];
+        let mut visibility = ChunkVisibility::none();
+        let mut stack: Vec<(usize, usize, usize)> = Vec::new();
+
+        for y in 0..height {
+            for z in 0..depth {
+                for x in 0..width {
+                    if visited[index(x, y, z)]
+                        || chunk_data.get_block(config, x, y, z).is_culled_by()
+                    {
+                        continue;
+                    }
+
+                    let mut touched_faces = [false; 6];
+                    visited[index(x, y, z)] = true;
+                    stack.push((x, y, z));
+
+                    while let Some((cx, cy, cz)) = stack.pop() {
+                        for (face_index, offset) in Self::FACE_OFFSETS.iter().enumerate() {
+                            let nx = cx as i32 + offset[0];
+                            let ny = cy as i32 + offset[1];
+                            let nz = cz as i32 + offset[2];
+                            if nx < 0
+                                || ny < 0
+                                || nz < 0
+                                || nx >= width as i32
+                                || ny >= height as i32
+                                || nz >= depth as i32
+                            {
+                                touched_faces[face_index] = true;
+                                continue;
+                            }
+
+                            let (nx, ny, nz) = (nx as usize, ny as usize, nz as usize);
+                            let neighbor_index = index(nx, ny, nz);
+                            if visited[neighbor_index]
+                                || chunk_data.get_block(config, nx, ny, nz).is_culled_by()
+                            {
+                                continue;
+                            }
+                            visited[neighbor_index] = true;
+                            stack.push((nx, ny, nz));
+                        }
+                    }
+
+                    for from in 0..6 {
+                        for to in 0..6 {
+                            if touched_faces[from] && touched_faces[to] {
+                                visibility.connect(from, to);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        visibility
+    }
+
+    pub fn generate_block_mesh(
+        &self,
+        block_type: BlockType,
+        texture_layers: &HashMap<String, f32>,
+    ) -> Option<Mesh> {
+        let face_textures = block_type.get_face_textures()?;
+        let mut vertices: Vec<f32> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        let mut index_offset: u32 = 0;
+
+        for face_index in 0..6 {
+            let texture_name = face_textures[Self::face_texture_index(face_index)];
+            let layer_index = texture_layers.get(texture_name).copied().unwrap_or(0.0);
+            Self::add_scaled_face(
+                FaceParams {
+                    position: [0.0, 0.0, 0.0],
+                    face_index,
+                    layer_index,
+                    scale: 1.0,
+                    normal: Self::face_normal(face_index),
+                    bottom: 0.0,
+                    top: 1.0,
+                },
+                &SeamPlanes::default(),
+                &mut vertices,
+                &mut indices,
+                &mut index_offset,
+            );
+        }
+
+        Some(Mesh { vertices, indices })
+    }
+
+    fn lod1_block_at(
+        chunk_data: &ChunkData,
+        neighbors: &[Option<ChunkData>; 6],
+        x: i32,
+        y: i32,
+        z: i32,
+        config: &Config,
+    ) -> Option<(BlockType, u8)> {
+        let (w, h, d) = (
+            config.chunk_width as i32,
+            config.chunk_height as i32,
+            config.chunk_depth as i32,
+        );
+        let outside = [
+            (x >= w, 0),
+            (x < 0, 1),
+            (y >= h, 2),
+            (y < 0, 3),
+            (z >= d, 4),
+            (z < 0, 5),
+        ];
+        let mut outside_faces = outside.iter().filter(|(is_out, _)| *is_out);
+        let source = match (outside_faces.next(), outside_faces.next()) {
+            (None, _) => chunk_data,
+            (Some(&(_, face_index)), None) => {
+                neighbors[Self::face_to_neighbor_index(face_index)].as_ref()?
+            }
+            _ => return None,
+        };
+
+        let (lx, ly, lz) = (
+            x.rem_euclid(w) as usize,
+            y.rem_euclid(h) as usize,
+            z.rem_euclid(d) as usize,
+        );
+        Some((
+            source.get_block(config, lx, ly, lz),
+            source.get_fluid_level(config, lx, ly, lz),
+        ))
+    }
+
+    fn lod1_fluid_height(
+        chunk_data: &ChunkData,
+        neighbors: &[Option<ChunkData>; 6],
+        x: i32,
+        y: i32,
+        z: i32,
+        config: &Config,
+    ) -> f32 {
+        let level = Self::lod1_block_at(chunk_data, neighbors, x, y, z, config)
+            .map_or(0, |(_, level)| level);
+        let covered = Self::lod1_block_at(chunk_data, neighbors, x, y + 1, z, config)
+            .is_some_and(|(block, _)| block != BlockType::Air);
+        fluid_surface_height(level, covered)
+    }
+
+    fn should_draw_face(current: BlockType, neighbor: BlockType) -> bool {
+        match neighbor {
+            BlockType::Air => true,
+            neighbor if !neighbor.is_culled_by() => current.is_culled_by() || current != neighbor,
+            _ => !current.is_culled_by(),
+        }
+    }
+
+    // The block covering (x, y, z) when its chunk is meshed with the given downsample factor.
+    fn block_at_factor(
+        chunk_data: &ChunkData,
+        x: usize,
+        y: usize,
+        z: usize,
+        factor: usize,
+        config: &Config,
+    ) -> BlockType {
+        if factor > 1 {
+            Self::calculate_representative_block(
+                chunk_data,
+                x / factor * factor,
+                y / factor * factor,
+                z / factor * factor,
+                factor,
+                config,
+            )
+        } else {
+            chunk_data.get_block(config, x, y, z)
+        }
+    }
+
+    // Splits a coarse border face into pieces on the finer neighbor's grid and keeps the
+    // pieces the neighbor leaves open, so the seam has neither gaps nor overlapping faces.
+    #[allow(clippy::too_many_arguments)]
+    fn collect_stitched_pieces(
+        current_block_type: BlockType,
+        neighbor_chunk_data: &ChunkData,
+        cell_origin: [usize; 3],
+        downsample_factor: usize,
+        neighbor_factor: usize,
+        face_index: usize,
+        (face_bottom, face_top): (f32, f32),
+        config: &Config,
+        pieces: &mut Vec<([f32; 3], f32, f32, f32)>,
+    ) {
+        let axis = face_index / 2;
+        let positive = face_index.is_multiple_of(2);
+        let chunk_size = [config.chunk_width, config.chunk_height, config.chunk_depth];
+        let (u_axis, v_axis) = match axis {
+            0 => (1, 2),
+            1 => (0, 2),
+            _ => (0, 1),
+        };
+        let steps = downsample_factor / neighbor_factor;
+        let piece_scale = neighbor_factor as f32;
+        let face_bottom_y = cell_origin[1] as f32 + face_bottom * downsample_factor as f32;
+        let face_top_y = cell_origin[1] as f32 + face_top * downsample_factor as f32;
+
+        for i in 0..steps {
+            for j in 0..steps {
+                let mut piece_origin = cell_origin;
+                piece_origin[u_axis] += i * neighbor_factor;
+                piece_origin[v_axis] += j * neighbor_factor;
+                if positive {
+                    piece_origin[axis] += downsample_factor - neighbor_factor;
+                }
+
+                let mut neighbor_origin = piece_origin;
+                neighbor_origin[axis] = if positive {
+                    0
+                } else {
+                    chunk_size[axis] - neighbor_factor
+                };
+                let neighbor_block = Self::block_at_factor(
+                    neighbor_chunk_data,
+                    neighbor_origin[0],
+                    neighbor_origin[1],
+                    neighbor_origin[2],
+                    neighbor_factor,
+                    config,
+                );
+                if !Self::should_draw_face(current_block_type, neighbor_block) {
+                    continue;
+                }
+
+                let mut center = piece_origin.map(|c| c as f32 + piece_scale / 2.0 - 0.5);
+                let (bottom, top) = match face_index {
+                    2 => {
+                        center[1] = face_top_y - piece_scale / 2.0 - 0.5;
+                        (0.0, 1.0)
+                    }
+                    3 => {
+                        center[1] = face_bottom_y + piece_scale / 2.0 - 0.5;
+                        (0.0, 1.0)
+                    }
+                    _ => {
+                        let piece_y = piece_origin[1] as f32;
+                        let low = face_bottom_y.max(piece_y);
+                        let high = face_top_y.min(piece_y + piece_scale);
+                        if high <= low {
+                            continue;
+                        }
+                        (
+                            (low - piece_y) / piece_scale,
+                            (high - piece_y) / piece_scale,
+                        )
+                    }
+                };
+                pieces.push((center, piece_scale, bottom, top));
+            }
+        }
+    }
+
+    pub fn downsample_chunk(
         &self,
         chunk_data: &ChunkData,
         factor: usize,
@@ -474,8 +898,22 @@ impl MeshGenerator {
         }
     }
 
+    #[inline]
+    fn face_normal(face_index: usize) -> [f32; 3] {
+        match face_index {
+            0 => [1.0, 0.0, 0.0],  // Right (+X)
+            1 => [-1.0, 0.0, 0.0], // Left (-X)
+            2 => [0.0, 1.0, 0.0],  // Top (+Y)
+            3 => [0.0, -1.0, 0.0], // Bottom (-Y)
+            4 => [0.0, 0.0, 1.0],  // Front (+Z)
+            5 => [0.0, 0.0, -1.0], // Back (-Z)
+            _ => [0.0, 1.0, 0.0],  // Default Up
+        }
+    }
+
     fn add_scaled_face(
         params: FaceParams,
+        seams: &SeamPlanes,
         vertices: &mut Vec<f32>,
         indices: &mut Vec<u32>,
         index_offset: &mut u32,
@@ -485,18 +923,25 @@ impl MeshGenerator {
         let normal = params.normal;
         let scale = params.scale;
         let half_scale = scale / 2.0;
+        let y_bottom = cy - half_scale + params.bottom * scale;
+        let y_top = cy - half_scale + params.top * scale;
 
-        let uv = [[0.0, 0.0], [scale, 0.0], [scale, scale], [0.0, scale]];
+        let (v_min, v_max) = if matches!(params.face_index, 2 | 3) {
+            (0.0, scale)
+        } else {
+            (params.bottom * scale, params.top * scale)
+        };
+        let uv = [[0.0, v_min], [scale, v_min], [scale, v_max], [0.0, v_max]];
 
         let p = [
-            [cx - half_scale, cy - half_scale, cz - half_scale], // 0: Back-Bottom-Left
-            [cx + half_scale, cy - half_scale, cz - half_scale], // 1: Back-Bottom-Right
-            [cx + half_scale, cy + half_scale, cz - half_scale], // 2: Back-Top-Right
-            [cx - half_scale, cy + half_scale, cz - half_scale], // 3: Back-Top-Left
-            [cx - half_scale, cy - half_scale, cz + half_scale], // 4: Front-Bottom-Left
-            [cx + half_scale, cy - half_scale, cz + half_scale], // 5: Front-Bottom-Right
-            [cx + half_scale, cy + half_scale, cz + half_scale], // 6: Front-Top-Right
-            [cx - half_scale, cy + half_scale, cz + half_scale], // 7: Front-Top-Left
+            [cx - half_scale, y_bottom, cz - half_scale], // 0: Back-Bottom-Left
+            [cx + half_scale, y_bottom, cz - half_scale], // 1: Back-Bottom-Right
+            [cx + half_scale, y_top, cz - half_scale],    // 2: Back-Top-Right
+            [cx - half_scale, y_top, cz - half_scale],    // 3: Back-Top-Left
+            [cx - half_scale, y_bottom, cz + half_scale], // 4: Front-Bottom-Left
+            [cx + half_scale, y_bottom, cz + half_scale], // 5: Front-Bottom-Right
+            [cx + half_scale, y_top, cz + half_scale],    // 6: Front-Top-Right
+            [cx - half_scale, y_top, cz + half_scale],    // 7: Front-Top-Left
         ];
 
         let (vertex_indices, uv_indices): ([usize; 4], [usize; 4]) = match params.face_index {
@@ -509,22 +954,91 @@ impl MeshGenerator {
             _ => unreachable!(),
         };
 
+        let corners: [([f32; 3], [f32; 2]); 4] =
+            std::array::from_fn(|i| (p[vertex_indices[i]], uv[uv_indices[i]]));
+
+        let mut outline = Vec::with_capacity(4);
         for i in 0..4 {
-            vertices.extend_from_slice(&p[vertex_indices[i]]);
-            vertices.extend_from_slice(&uv[uv_indices[i]]);
+            let (start, end) = (corners[i], corners[(i + 1) % 4]);
+            outline.push(start);
+            if let Some(step) = seams.edge_step(start.0, end.0) {
+                Self::push_seam_points(start, end, step as f32, &mut outline);
+            }
+        }
+
+        let mut push_vertex = |(position, tex_coord): ([f32; 3], [f32; 2])| {
+            vertices.extend_from_slice(&position);
+            vertices.extend_from_slice(&tex_coord);
             vertices.push(layer);
             vertices.extend_from_slice(&normal);
+        };
+
+        if outline.len() == 4 {
+            for corner in corners {
+                push_vertex(corner);
+            }
+            indices.extend_from_slice(&[
+                *index_offset,
+                *index_offset + 1,
+                *index_offset + 2,
+                *index_offset,
+                *index_offset + 2,
+                *index_offset + 3,
+            ]);
+            *index_offset += 4;
+            return;
+        }
+
+        // Fan around the face center so every seam vertex is used by the triangulation.
+        let center = (
+            std::array::from_fn(|k| corners.iter().map(|c| c.0[k]).sum::<f32>() / 4.0),
+            std::array::from_fn(|k| corners.iter().map(|c| c.1[k]).sum::<f32>() / 4.0),
+        );
+        push_vertex(center);
+        for &point in &outline {
+            push_vertex(point);
+        }
+        let ring = outline.len() as u32;
+        for i in 0..ring {
+            indices.extend_from_slice(&[
+                *index_offset,
+                *index_offset + 1 + i,
+                *index_offset + 1 + (i + 1) % ring,
+            ]);
         }
+        *index_offset += ring + 1;
+    }
 
-        indices.extend_from_slice(&[
-            *index_offset,
-            *index_offset + 1,
-            *index_offset + 2,
-            *index_offset,
-            *index_offset + 2,
-            *index_offset + 3,
-        ]);
-        *index_offset += 4;
+    // Interior grid points of a seam edge, ordered from `start` to `end`.
+    fn push_seam_points(
+        start: ([f32; 3], [f32; 2]),
+        end: ([f32; 3], [f32; 2]),
+        step: f32,
+        outline: &mut Vec<([f32; 3], [f32; 2])>,
+    ) {
+        let Some(axis) = (0..3).find(|&k| (start.0[k] - end.0[k]).abs() > 1e-4) else {
+            return;
+        };
+        let (from, to) = (start.0[axis], end.0[axis]);
+        let (low, high) = (from.min(to), from.max(to));
+        let mut grid_points = Vec::new();
+        let mut grid = ((low + 0.5) / step).floor() * step - 0.5 + step;
+        while grid < high - 1e-4 {
+            if grid > low + 1e-4 {
+                grid_points.push(grid);
+            }
+            grid += step;
+        }
+        if from > to {
+            grid_points.reverse();
+        }
+        for grid in grid_points {
+            let t = (grid - from) / (to - from);
+            outline.push((
+                std::array::from_fn(|k| start.0[k] + (end.0[k] - start.0[k]) * t),
+                std::array::from_fn(|k| start.1[k] + (end.1[k] - start.1[k]) * t),
+            ));
+        }
     }
 }
 
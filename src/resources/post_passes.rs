@@ -0,0 +1,305 @@
+use crate::gl;
+use crate::resources::{
+    bloom_mip_sizes, fullscreen_program, Config, PostProcessFrame, PostProcessPass, RenderTarget,
+    ShaderProgram,
+};
+
+const LUMINANCE_RESOLUTION: i32 = 256;
+
+// Measures the scene's average luminance and eases the exposure towards it. The result is
+// left in a 1x1 texture that the tone mapping pass reads, so nothing is read back to the CPU.
+pub struct ExposurePass {
+    gl: gl::Gl,
+    luminance_program: ShaderProgram,
+    adapt_program: ShaderProgram,
+    luminance_target: RenderTarget,
+    exposure_targets: [RenderTarget; 2],
+    current: usize,
+}
+
+impl ExposurePass {
+    pub fn new(gl: &gl::Gl) -> Result<Self, String> {
+        let mut luminance_program =
+            fullscreen_program(gl, include_str!("../shaders/luminance_fragment.glsl"))?;
+        luminance_program.register_uniform("sourceTexture");
+
+        let mut adapt_program =
+            fullscreen_program(gl, include_str!("../shaders/exposure_adapt_fragment.glsl"))?;
+        for uniform in [
+            "luminanceTexture",
+            "previousExposure",
+            "deltaTime",
+            "adaptationSpeed",
+            "exposureKey",
+            "minExposure",
+            "maxExposure",
+        ] {
+            adapt_program.register_uniform(uniform);
+        }
+
+        let luminance_target = RenderTarget::new(
+            gl,
+            LUMINANCE_RESOLUTION,
+            LUMINANCE_RESOLUTION,
+            gl::R16F,
+            false,
+        );
+        luminance_target.enable_mipmaps();
+
+        let exposure_targets = [
+            RenderTarget::new(gl, 1, 1, gl::R32F, false),
+            RenderTarget::new(gl, 1, 1, gl::R32F, false),
+        ];
+        for target in &exposure_targets {
+            target.clear([1.0, 0.0, 0.0, 1.0]);
+        }
+        unsafe {
+            gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        Ok(Self {
+            gl: gl.clone(),
+            luminance_program,
+            adapt_program,
+            luminance_target,
+            exposure_targets,
+            current: 0,
+        })
+    }
+}
+
+impl PostProcessPass for ExposurePass {
+    fn resize(&mut self, _width: i32, _height: i32) {}
+
+    fn apply(
+        &mut self,
+        frame: &mut PostProcessFrame,
+        input: gl::types::GLuint,
+    ) -> gl::types::GLuint {
+        let config = frame.config;
+        if !config.exposure_adaptation {
+            frame.exposure_texture = 0;
+            return input;
+        }
+
+        self.luminance_target.bind();
+        self.luminance_program.use_program();
+        self.luminance_program.set_uniform_int("sourceTexture", 0);
+        frame.bind_texture(0, input);
+        frame.draw_fullscreen();
+        self.luminance_target.generate_mipmaps();
+
+        let previous = self.current;
+        self.current = 1 - self.current;
+        self.exposure_targets[self.current].bind();
+        self.adapt_program.use_program();
+        self.adapt_program.set_uniform_int("luminanceTexture", 0);
+        self.adapt_program.set_uniform_int("previousExposure", 1);
+        self.adapt_program
+            .set_uniform_float("deltaTime", frame.delta_time);
+        self.adapt_program
+            .set_uniform_float("adaptationSpeed", config.exposure_adaptation_speed);
+        self.adapt_program
+            .set_uniform_float("exposureKey", config.exposure_key);
+        self.adapt_program
+            .set_uniform_float("minExposure", config.min_exposure);
+        self.adapt_program
+            .set_uniform_float("maxExposure", config.max_exposure);
+        frame.bind_texture(0, self.luminance_target.texture());
+        frame.bind_texture(1, self.exposure_targets[previous].texture());
+        frame.draw_fullscreen();
+
+        unsafe {
+            self.gl.ActiveTexture(gl::TEXTURE0);
+        }
+        frame.exposure_texture = self.exposure_targets[self.current].texture();
+        input
+    }
+}
+
+// Threshold, then a downsample/upsample mip chain, then added back onto the scene.
+pub struct BloomPass {
+    gl: gl::Gl,
+    prefilter_program: ShaderProgram,
+    downsample_program: ShaderProgram,
+    upsample_program: ShaderProgram,
+    composite_program: ShaderProgram,
+    levels: usize,
+    mips: Vec<RenderTarget>,
+    output: RenderTarget,
+}
+
+impl BloomPass {
+    pub fn new(gl: &gl::Gl, config: &Config, width: i32, height: i32) -> Result<Self, String> {
+        let mut prefilter_program =
+            fullscreen_program(gl, include_str!("../shaders/bloom_prefilter_fragment.glsl"))?;
+        for uniform in ["sourceTexture", "threshold", "softKnee"] {
+            prefilter_program.register_uniform(uniform);
+        }
+
+        let mut downsample_program = fullscreen_program(
+            gl,
+            include_str!("../shaders/bloom_downsample_fragment.glsl"),
+        )?;
+        downsample_program.register_uniform("sourceTexture");
+
+        let mut upsample_program =
+            fullscreen_program(gl, include_str!("../shaders/bloom_upsample_fragment.glsl"))?;
+        for uniform in ["sourceTexture", "filterRadius"] {
+            upsample_program.register_uniform(uniform);
+        }
+
+        let mut composite_program =
+            fullscreen_program(gl, include_str!("../shaders/bloom_composite_fragment.glsl"))?;
+        for uniform in ["sceneTexture", "bloomTexture", "bloomIntensity"] {
+            composite_program.register_uniform(uniform);
+        }
+
+        let mut pass = Self {
+            gl: gl.clone(),
+            prefilter_program,
+            downsample_program,
+            upsample_program,
+            composite_program,
+            levels: config.bloom_levels,
+            mips: Vec::new(),
+            output: RenderTarget::new(gl, width, height, gl::RGBA16F, false),
+        };
+        pass.resize(width, height);
+        Ok(pass)
+    }
+}
+
+impl PostProcessPass for BloomPass {
+    fn resize(&mut self, width: i32, height: i32) {
+        self.mips = bloom_mip_sizes(width, height, self.levels)
+            .into_iter()
+            .map(|(mip_width, mip_height)| {
+                RenderTarget::new(&self.gl, mip_width, mip_height, gl::RGBA16F, false)
+            })
+            .collect();
+        self.output = RenderTarget::new(&self.gl, width, height, gl::RGBA16F, false);
+    }
+
+    fn apply(
+        &mut self,
+        frame: &mut PostProcessFrame,
+        input: gl::types::GLuint,
+    ) -> gl::types::GLuint {
+        let config = frame.config;
+        if !config.bloom_enabled || self.mips.is_empty() {
+            return input;
+        }
+
+        self.mips[0].bind();
+        self.prefilter_program.use_program();
+        self.prefilter_program.set_uniform_int("sourceTexture", 0);
+        self.prefilter_program
+            .set_uniform_float("threshold", config.bloom_threshold);
+        self.prefilter_program
+            .set_uniform_float("softKnee", config.bloom_soft_knee);
+        frame.bind_texture(0, input);
+        frame.draw_fullscreen();
+
+        self.downsample_program.use_program();
+        self.downsample_program.set_uniform_int("sourceTexture", 0);
+        for level in 1..self.mips.len() {
+            self.mips[level].bind();
+            frame.bind_texture(0, self.mips[level - 1].texture());
+            frame.draw_fullscreen();
+        }
+
+        self.upsample_program.use_program();
+        self.upsample_program.set_uniform_int("sourceTexture", 0);
+        self.upsample_program.set_uniform_float("filterRadius", 1.0);
+        unsafe {
+            self.gl.Enable(gl::BLEND);
+            self.gl.BlendFunc(gl::ONE, gl::ONE);
+        }
+        for level in (1..self.mips.len()).rev() {
+            self.mips[level - 1].bind();
+            frame.bind_texture(0, self.mips[level].texture());
+            frame.draw_fullscreen();
+        }
+        unsafe {
+            self.gl.Disable(gl::BLEND);
+        }
+
+        self.output.bind();
+        self.composite_program.use_program();
+        self.composite_program.set_uniform_int("sceneTexture", 0);
+        self.composite_program.set_uniform_int("bloomTexture", 1);
+        self.composite_program
+            .set_uniform_float("bloomIntensity", config.bloom_intensity);
+        frame.bind_texture(0, input);
+        frame.bind_texture(1, self.mips[0].texture());
+        frame.draw_fullscreen();
+
+        unsafe {
+            self.gl.ActiveTexture(gl::TEXTURE0);
+        }
+        self.output.texture()
+    }
+}
+
+// Exposure, the configured tone mapping curve and gamma; the output is display-ready.
+pub struct ToneMapPass {
+    gl: gl::Gl,
+    program: ShaderProgram,
+    output: RenderTarget,
+}
+
+impl ToneMapPass {
+    pub fn new(gl: &gl::Gl, width: i32, height: i32) -> Result<Self, String> {
+        let mut program = fullscreen_program(gl, include_str!("../shaders/tonemap_fragment.glsl"))?;
+        for uniform in [
+            "sourceTexture",
+            "exposureTexture",
+            "useAdaptedExposure",
+            "manualExposure",
+            "toneMapOperator",
+            "gamma",
+        ] {
+            program.register_uniform(uniform);
+        }
+
+        Ok(Self {
+            gl: gl.clone(),
+            program,
+            output: RenderTarget::new(gl, width, height, gl::RGBA8, false),
+        })
+    }
+}
+
+impl PostProcessPass for ToneMapPass {
+    fn resize(&mut self, width: i32, height: i32) {
+        self.output = RenderTarget::new(&self.gl, width, height, gl::RGBA8, false);
+    }
+
+    fn apply(
+        &mut self,
+        frame: &mut PostProcessFrame,
+        input: gl::types::GLuint,
+    ) -> gl::types::GLuint {
+        let config = frame.config;
+        self.output.bind();
+        self.program.use_program();
+        self.program.set_uniform_int("sourceTexture", 0);
+        self.program.set_uniform_int("exposureTexture", 1);
+        self.program
+            .set_uniform_bool("useAdaptedExposure", frame.exposure_texture != 0);
+        self.program
+            .set_uniform_float("manualExposure", config.manual_exposure);
+        self.program
+            .set_uniform_int("toneMapOperator", config.tone_map_operator.shader_index());
+        self.program.set_uniform_float("gamma", config.gamma);
+        frame.bind_texture(0, input);
+        frame.bind_texture(1, frame.exposure_texture);
+        frame.draw_fullscreen();
+
+        unsafe {
+            self.gl.ActiveTexture(gl::TEXTURE0);
+        }
+        self.output.texture()
+    }
+}
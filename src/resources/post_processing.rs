@@ -0,0 +1,354 @@
+use crate::gl;
+use crate::resources::{BloomPass, Config, ExposurePass, ShaderProgram, ToneMapPass};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMapOperator {
+    Reinhard,
+    Aces,
+    Uncharted2,
+}
+
+impl ToneMapOperator {
+    pub fn shader_index(&self) -> i32 {
+        match self {
+            ToneMapOperator::Reinhard => 0,
+            ToneMapOperator::Aces => 1,
+            ToneMapOperator::Uncharted2 => 2,
+        }
+    }
+}
+
+pub fn bloom_mip_sizes(width: i32, height: i32, levels: usize) -> Vec<(i32, i32)> {
+    let mut sizes = Vec::with_capacity(levels);
+    let (mut mip_width, mut mip_height) = (width / 2, height / 2);
+    while sizes.len() < levels && mip_width >= 1 && mip_height >= 1 {
+        sizes.push((mip_width, mip_height));
+        mip_width /= 2;
+        mip_height /= 2;
+    }
+    sizes
+}
+
+// An offscreen color texture with its framebuffer, optionally with a depth buffer.
+pub struct RenderTarget {
+    gl: gl::Gl,
+    fbo: gl::types::GLuint,
+    texture: gl::types::GLuint,
+    depth_renderbuffer: gl::types::GLuint,
+    width: i32,
+    height: i32,
+}
+
+impl RenderTarget {
+    pub fn new(
+        gl: &gl::Gl,
+        width: i32,
+        height: i32,
+        internal_format: gl::types::GLenum,
+        with_depth: bool,
+    ) -> Self {
+        let mut target = Self {
+            gl: gl.clone(),
+            fbo: 0,
+            texture: 0,
+            depth_renderbuffer: 0,
+            width: width.max(1),
+            height: height.max(1),
+        };
+        unsafe {
+            gl.GenTextures(1, &mut target.texture);
+            gl.BindTexture(gl::TEXTURE_2D, target.texture);
+            gl.TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                internal_format as i32,
+                target.width,
+                target.height,
+                0,
+                gl::RGBA,
+                gl::FLOAT,
+                std::ptr::null(),
+            );
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+
+            gl.GenFramebuffers(1, &mut target.fbo);
+            gl.BindFramebuffer(gl::FRAMEBUFFER, target.fbo);
+            gl.FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                target.texture,
+                0,
+            );
+
+            if with_depth {
+                gl.GenRenderbuffers(1, &mut target.depth_renderbuffer);
+                gl.BindRenderbuffer(gl::RENDERBUFFER, target.depth_renderbuffer);
+                gl.RenderbufferStorage(
+                    gl::RENDERBUFFER,
+                    gl::DEPTH_COMPONENT24,
+                    target.width,
+                    target.height,
+                );
+                gl.FramebufferRenderbuffer(
+                    gl::FRAMEBUFFER,
+                    gl::DEPTH_ATTACHMENT,
+                    gl::RENDERBUFFER,
+                    target.depth_renderbuffer,
+                );
+                gl.BindRenderbuffer(gl::RENDERBUFFER, 0);
+            }
+
+            if gl.CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
+                eprintln!(
+                    "Render target {}x{} framebuffer is not complete",
+                    target.width, target.height
+                );
+            }
+            gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+        target
+    }
+
+    pub fn texture(&self) -> gl::types::GLuint {
+        self.texture
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    pub fn bind(&self) {
+        unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+            self.gl.Viewport(0, 0, self.width, self.height);
+        }
+    }
+
+    pub fn enable_mipmaps(&self) {
+        unsafe {
+            self.gl.BindTexture(gl::TEXTURE_2D, self.texture);
+            self.gl.TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_MIN_FILTER,
+                gl::LINEAR_MIPMAP_NEAREST as i32,
+            );
+            self.gl.GenerateMipmap(gl::TEXTURE_2D);
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    pub fn generate_mipmaps(&self) {
+        unsafe {
+            self.gl.BindTexture(gl::TEXTURE_2D, self.texture);
+            self.gl.GenerateMipmap(gl::TEXTURE_2D);
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    pub fn clear(&self, value: [f32; 4]) {
+        self.bind();
+        unsafe {
+            self.gl.ClearColor(value[0], value[1], value[2], value[3]);
+            self.gl.Clear(gl::COLOR_BUFFER_BIT);
+        }
+    }
+}
+
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        unsafe {
+            if self.depth_renderbuffer != 0 {
+                self.gl.DeleteRenderbuffers(1, &self.depth_renderbuffer);
+            }
+            if self.fbo != 0 {
+                self.gl.DeleteFramebuffers(1, &self.fbo);
+            }
+            if self.texture != 0 {
+                self.gl.DeleteTextures(1, &self.texture);
+            }
+        }
+    }
+}
+
+// What a pass can see while it runs. Passes publish results for later passes here too,
+// e.g. the adapted exposure for tone mapping.
+pub struct PostProcessFrame<'a> {
+    pub gl: &'a gl::Gl,
+    pub config: &'a Config,
+    pub width: i32,
+    pub height: i32,
+    pub delta_time: f32,
+    pub exposure_texture: gl::types::GLuint,
+    empty_vao: gl::types::GLuint,
+}
+
+impl PostProcessFrame<'_> {
+    pub fn draw_fullscreen(&self) {
+        unsafe {
+            self.gl.BindVertexArray(self.empty_vao);
+            self.gl.DrawArrays(gl::TRIANGLES, 0, 3);
+        }
+    }
+
+    pub fn bind_texture(&self, unit: u32, texture: gl::types::GLuint) {
+        unsafe {
+            self.gl.ActiveTexture(gl::TEXTURE0 + unit);
+            self.gl.BindTexture(gl::TEXTURE_2D, texture);
+        }
+    }
+}
+
+pub trait PostProcessPass {
+    fn resize(&mut self, width: i32, height: i32);
+
+    // Reads the HDR color in `input` and returns the texture holding the pass's result,
+    // which may be `input` itself for passes that only gather information.
+    fn apply(
+        &mut self,
+        frame: &mut PostProcessFrame,
+        input: gl::types::GLuint,
+    ) -> gl::types::GLuint;
+}
+
+pub fn fullscreen_program(gl: &gl::Gl, fragment_src: &str) -> Result<ShaderProgram, String> {
+    ShaderProgram::from_sources(
+        gl,
+        include_str!("../shaders/post_vertex.glsl"),
+        fragment_src,
+    )
+}
+
+pub struct PostProcessChain {
+    gl: gl::Gl,
+    scene_target: RenderTarget,
+    passes: Vec<Box<dyn PostProcessPass>>,
+    present_program: ShaderProgram,
+    empty_vao: gl::types::GLuint,
+    width: i32,
+    height: i32,
+    last_time: Option<f32>,
+}
+
+impl PostProcessChain {
+    pub fn new(gl: &gl::Gl, config: &Config, width: i32, height: i32) -> Result<Self, String> {
+        let mut present_program =
+            fullscreen_program(gl, include_str!("../shaders/present_fragment.glsl"))?;
+        present_program.register_uniform("sourceTexture");
+
+        let mut empty_vao = 0;
+        unsafe {
+            gl.GenVertexArrays(1, &mut empty_vao);
+        }
+
+        let passes: Vec<Box<dyn PostProcessPass>> = vec![
+            Box::new(ExposurePass::new(gl)?),
+            Box::new(BloomPass::new(gl, config, width, height)?),
+            Box::new(ToneMapPass::new(gl, width, height)?),
+        ];
+
+        Ok(Self {
+            gl: gl.clone(),
+            scene_target: RenderTarget::new(gl, width, height, gl::RGBA16F, true),
+            passes,
+            present_program,
+            empty_vao,
+            width,
+            height,
+            last_time: None,
+        })
+    }
+
+    pub fn pass_count(&self) -> usize {
+        self.passes.len()
+    }
+
+    // Inserts before the final tone mapping pass, so the new pass still sees HDR color.
+    pub fn add_pass(&mut self, pass: Box<dyn PostProcessPass>) {
+        let index = self.passes.len().saturating_sub(1);
+        self.passes.insert(index, pass);
+    }
+
+    pub fn resize(&mut self, width: i32, height: i32) {
+        if width <= 0 || height <= 0 || (width, height) == (self.width, self.height) {
+            return;
+        }
+        self.width = width;
+        self.height = height;
+        self.scene_target = RenderTarget::new(&self.gl, width, height, gl::RGBA16F, true);
+        for pass in &mut self.passes {
+            pass.resize(width, height);
+        }
+    }
+
+    // Points scene rendering at the HDR target, or straight at the window when HDR is off.
+    pub fn begin_scene(&self, config: &Config) {
+        if config.hdr_enabled {
+            self.scene_target.bind();
+        } else {
+            unsafe {
+                self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+                self.gl.Viewport(0, 0, self.width, self.height);
+            }
+        }
+    }
+
+    pub fn finish(&mut self, config: &Config, total_time: f32) {
+        let delta_time = self
+            .last_time
+            .map_or(0.0, |last_time| (total_time - last_time).max(0.0));
+        self.last_time = Some(total_time);
+        if !config.hdr_enabled {
+            return;
+        }
+
+        unsafe {
+            self.gl.Disable(gl::DEPTH_TEST);
+            self.gl.Disable(gl::BLEND);
+        }
+
+        let mut frame = PostProcessFrame {
+            gl: &self.gl,
+            config,
+            width: self.width,
+            height: self.height,
+            delta_time,
+            exposure_texture: 0,
+            empty_vao: self.empty_vao,
+        };
+        let mut color = self.scene_target.texture();
+        for pass in &mut self.passes {
+            color = pass.apply(&mut frame, color);
+        }
+
+        unsafe {
+            self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
+            self.gl.Viewport(0, 0, self.width, self.height);
+        }
+        self.present_program.use_program();
+        self.present_program.set_uniform_int("sourceTexture", 0);
+        frame.bind_texture(0, color);
+        frame.draw_fullscreen();
+
+        unsafe {
+            self.gl.BindVertexArray(0);
+            self.gl.Enable(gl::DEPTH_TEST);
+            self.gl.Enable(gl::BLEND);
+            self.gl.BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+        }
+    }
+}
+
+impl Drop for PostProcessChain {
+    fn drop(&mut self) {
+        unsafe {
+            if self.empty_vao != 0 {
+                self.gl.DeleteVertexArrays(1, &self.empty_vao);
+            }
+        }
+    }
+}
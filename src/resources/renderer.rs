@@ -1,23 +1,102 @@
 use crate::gl;
-use crate::resources::Config;
+use crate::resources::{ArenaAllocation, BufferArena, Config, MAX_SHADOW_CASCADES};
 use fnv::FnvHashMap;
-use glam::Vec3;
+use glam::{IVec3, Vec3};
 use rand::Rng;
+use std::fmt;
+
+const CHUNK_VERTEX_FLOATS: usize = 9;
+const CHUNK_VERTEX_BYTES: usize = CHUNK_VERTEX_FLOATS * std::mem::size_of::<f32>();
+const CHUNK_INDEX_BYTES: usize = std::mem::size_of::<u32>();
+
+struct ChunkAllocation {
+    vertices: ArenaAllocation,
+    indices: ArenaAllocation,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStats {
+    pub chunks_considered: usize,
+    pub frustum_culled: usize,
+    pub occlusion_culled: usize,
+    pub chunks_drawn: usize,
+    pub shadow_casters_drawn: usize,
+    pub shadow_casters_culled: usize,
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "chunks {}/{} (frustum -{}, occlusion -{}) | shadow casters {} (-{})",
+            self.chunks_drawn,
+            self.chunks_considered,
+            self.frustum_culled,
+            self.occlusion_culled,
+            self.shadow_casters_drawn,
+            self.shadow_casters_culled
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct ChunkDrawList {
+    counts: Vec<gl::types::GLsizei>,
+    index_byte_offsets: Vec<usize>,
+    base_vertices: Vec<gl::types::GLint>,
+}
+
+impl ChunkDrawList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.index_byte_offsets.clear();
+        self.base_vertices.clear();
+    }
+}
 
 pub struct Renderer {
     pub gl: gl::Gl,
     pub vaos: FnvHashMap<usize, gl::types::GLuint>,
     pub vbos: FnvHashMap<usize, gl::types::GLuint>,
     pub ebos: FnvHashMap<usize, gl::types::GLuint>,
+    chunk_vao: gl::types::GLuint,
+    chunk_vbo: gl::types::GLuint,
+    chunk_ebo: gl::types::GLuint,
+    chunk_vertex_arena: BufferArena,
+    chunk_index_arena: BufferArena,
+    chunk_allocations: FnvHashMap<usize, ChunkAllocation>,
+    pub transparent_sort_cells: FnvHashMap<usize, IVec3>,
+    pub stats: RenderStats,
+    scene_color_texture: gl::types::GLuint,
+    scene_depth_texture: gl::types::GLuint,
+    scene_copy_size: (i32, i32),
     celestial_vao: gl::types::GLuint,
     celestial_vbo: gl::types::GLuint,
     celestial_ebo: gl::types::GLuint,
+    empty_vao: gl::types::GLuint,
+    cloud_vao: gl::types::GLuint,
+    cloud_vbo: gl::types::GLuint,
+    cloud_texture: gl::types::GLuint,
+    precipitation_heightmap_texture: gl::types::GLuint,
     star_vao: gl::types::GLuint,
     star_vbo: gl::types::GLuint,
     pub num_stars: usize,
     shadow_fbo: gl::types::GLuint,
     shadow_map_texture: gl::types::GLuint,
     shadow_map_resolution: u32,
+    shadow_cascade_count: usize,
 }
 
 impl Renderer {
@@ -40,24 +119,189 @@ impl Renderer {
             vaos: FnvHashMap::default(),
             vbos: FnvHashMap::default(),
             ebos: FnvHashMap::default(),
+            chunk_vao: 0,
+            chunk_vbo: 0,
+            chunk_ebo: 0,
+            chunk_vertex_arena: BufferArena::new(config.chunk_vertex_arena_capacity),
+            chunk_index_arena: BufferArena::new(config.chunk_index_arena_capacity),
+            chunk_allocations: FnvHashMap::default(),
+            transparent_sort_cells: FnvHashMap::default(),
+            stats: RenderStats::default(),
+            scene_color_texture: 0,
+            scene_depth_texture: 0,
+            scene_copy_size: (0, 0),
             celestial_vao: 0,
             celestial_vbo: 0,
             celestial_ebo: 0,
+            empty_vao: 0,
+            cloud_vao: 0,
+            cloud_vbo: 0,
+            cloud_texture: 0,
+            precipitation_heightmap_texture: 0,
             star_vao: 0,
             star_vbo: 0,
             num_stars: 0,
             shadow_fbo: 0,
             shadow_map_texture: 0,
             shadow_map_resolution: config.shadow_map_resolution,
+            shadow_cascade_count: config.shadow_cascade_count.clamp(1, MAX_SHADOW_CASCADES),
         };
+        renderer.create_chunk_buffers();
         renderer.create_celestial_buffers();
         renderer.create_star_buffers();
+        renderer.create_cloud_buffers();
+        unsafe {
+            renderer.gl.GenVertexArrays(1, &mut renderer.empty_vao);
+        }
         if let Err(e) = renderer.create_shadow_fbo() {
             eprintln!("Failed to create shadow FBO: {}", e);
         }
         renderer
     }
 
+    fn create_chunk_buffers(&mut self) {
+        unsafe {
+            self.gl.GenVertexArrays(1, &mut self.chunk_vao);
+            self.gl.BindVertexArray(self.chunk_vao);
+
+            self.chunk_vbo =
+                self.create_buffer(self.chunk_vertex_arena.capacity() * CHUNK_VERTEX_BYTES);
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, self.chunk_vbo);
+            self.configure_chunk_vertex_attributes();
+
+            self.chunk_ebo =
+                self.create_buffer(self.chunk_index_arena.capacity() * CHUNK_INDEX_BYTES);
+            self.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.chunk_ebo);
+
+            self.gl.BindVertexArray(0);
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, 0);
+            self.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+        }
+    }
+
+    fn create_buffer(&self, size_bytes: usize) -> gl::types::GLuint {
+        let mut buffer = 0;
+        unsafe {
+            self.gl.GenBuffers(1, &mut buffer);
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, buffer);
+            self.gl.BufferData(
+                gl::COPY_WRITE_BUFFER,
+                size_bytes as gl::types::GLsizeiptr,
+                std::ptr::null(),
+                gl::DYNAMIC_DRAW,
+            );
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, 0);
+        }
+        buffer
+    }
+
+    fn grow_buffer(
+        &self,
+        old_buffer: gl::types::GLuint,
+        old_size_bytes: usize,
+        new_size_bytes: usize,
+    ) -> gl::types::GLuint {
+        let new_buffer = self.create_buffer(new_size_bytes);
+        unsafe {
+            self.gl.BindBuffer(gl::COPY_READ_BUFFER, old_buffer);
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, new_buffer);
+            self.gl.CopyBufferSubData(
+                gl::COPY_READ_BUFFER,
+                gl::COPY_WRITE_BUFFER,
+                0,
+                0,
+                old_size_bytes as gl::types::GLsizeiptr,
+            );
+            self.gl.BindBuffer(gl::COPY_READ_BUFFER, 0);
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, 0);
+            self.gl.DeleteBuffers(1, &old_buffer);
+        }
+        new_buffer
+    }
+
+    fn allocate_chunk_vertices(&mut self, count: usize) -> Option<ArenaAllocation> {
+        if let Some(allocation) = self.chunk_vertex_arena.allocate(count) {
+            return Some(allocation);
+        }
+
+        let old_capacity = self.chunk_vertex_arena.capacity();
+        let new_capacity = (old_capacity * 2).max(old_capacity + count);
+        self.chunk_vbo = self.grow_buffer(
+            self.chunk_vbo,
+            old_capacity * CHUNK_VERTEX_BYTES,
+            new_capacity * CHUNK_VERTEX_BYTES,
+        );
+        unsafe {
+            self.gl.BindVertexArray(self.chunk_vao);
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, self.chunk_vbo);
+            self.configure_chunk_vertex_attributes();
+            self.gl.BindVertexArray(0);
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+        self.chunk_vertex_arena.grow(new_capacity);
+        self.chunk_vertex_arena.allocate(count)
+    }
+
+    fn allocate_chunk_indices(&mut self, count: usize) -> Option<ArenaAllocation> {
+        if let Some(allocation) = self.chunk_index_arena.allocate(count) {
+            return Some(allocation);
+        }
+
+        let old_capacity = self.chunk_index_arena.capacity();
+        let new_capacity = (old_capacity * 2).max(old_capacity + count);
+        self.chunk_ebo = self.grow_buffer(
+            self.chunk_ebo,
+            old_capacity * CHUNK_INDEX_BYTES,
+            new_capacity * CHUNK_INDEX_BYTES,
+        );
+        unsafe {
+            self.gl.BindVertexArray(self.chunk_vao);
+            self.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.chunk_ebo);
+            self.gl.BindVertexArray(0);
+        }
+        self.chunk_index_arena.grow(new_capacity);
+        self.chunk_index_arena.allocate(count)
+    }
+
+    fn configure_chunk_vertex_attributes(&self) {
+        let stride = CHUNK_VERTEX_BYTES as gl::types::GLsizei;
+        unsafe {
+            self.gl
+                .VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            self.gl.EnableVertexAttribArray(0);
+
+            self.gl.VertexAttribPointer(
+                1,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (3 * std::mem::size_of::<f32>()) as *const _,
+            );
+            self.gl.EnableVertexAttribArray(1);
+
+            self.gl.VertexAttribPointer(
+                2,
+                1,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (5 * std::mem::size_of::<f32>()) as *const _,
+            );
+            self.gl.EnableVertexAttribArray(2);
+
+            self.gl.VertexAttribPointer(
+                3,
+                3,
+                gl::FLOAT,
+                gl::FALSE,
+                stride,
+                (6 * std::mem::size_of::<f32>()) as *const _,
+            );
+            self.gl.EnableVertexAttribArray(3);
+        }
+    }
+
     fn create_celestial_buffers(&mut self) {
         let vertices: [f32; 32] = [
             -0.5, -0.5, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, // Bottom-left
@@ -167,57 +411,140 @@ impl Renderer {
         }
     }
 
-    fn create_shadow_fbo(&mut self) -> Result<(), String> {
+    fn create_cloud_buffers(&mut self) {
+        let vertices: [f32; 12] = [
+            -1.0, -1.0, 1.0, -1.0, 1.0, 1.0, // First triangle
+            1.0, 1.0, -1.0, 1.0, -1.0, -1.0, // Second triangle
+        ];
+
         unsafe {
-            self.gl.GenTextures(1, &mut self.shadow_map_texture);
-            self.gl.BindTexture(gl::TEXTURE_2D, self.shadow_map_texture);
+            self.gl.GenVertexArrays(1, &mut self.cloud_vao);
+            self.gl.BindVertexArray(self.cloud_vao);
+
+            self.gl.GenBuffers(1, &mut self.cloud_vbo);
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, self.cloud_vbo);
+            self.gl.BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(&vertices) as gl::types::GLsizeiptr,
+                vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            let stride = (2 * std::mem::size_of::<f32>()) as gl::types::GLsizei;
+            self.gl
+                .VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+            self.gl.EnableVertexAttribArray(0);
+
+            self.gl.BindVertexArray(0);
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+    }
+
+    pub fn upload_cloud_texture(&mut self, resolution: usize, density: &[u8]) {
+        unsafe {
+            if self.cloud_texture == 0 {
+                self.gl.GenTextures(1, &mut self.cloud_texture);
+            }
+            self.gl.BindTexture(gl::TEXTURE_2D, self.cloud_texture);
+            self.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
             self.gl.TexImage2D(
                 gl::TEXTURE_2D,
                 0,
+                gl::R8 as i32,
+                resolution as i32,
+                resolution as i32,
+                0,
+                gl::RED,
+                gl::UNSIGNED_BYTE,
+                density.as_ptr() as *const _,
+            );
+            self.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::REPEAT as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::REPEAT as i32);
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    pub fn bind_cloud_texture(&self, texture_unit: gl::types::GLenum) {
+        unsafe {
+            self.gl.ActiveTexture(texture_unit);
+            self.gl.BindTexture(gl::TEXTURE_2D, self.cloud_texture);
+        }
+    }
+
+    // A unit quad in XZ; the cloud shaders scale and place it around the camera.
+    pub fn draw_cloud_plane(&self) {
+        unsafe {
+            self.gl.BindVertexArray(self.cloud_vao);
+            self.gl.DrawArrays(gl::TRIANGLES, 0, 6);
+        }
+    }
+
+    fn create_shadow_fbo(&mut self) -> Result<(), String> {
+        unsafe {
+            self.gl.GenTextures(1, &mut self.shadow_map_texture);
+            self.gl
+                .BindTexture(gl::TEXTURE_2D_ARRAY, self.shadow_map_texture);
+            self.gl.TexImage3D(
+                gl::TEXTURE_2D_ARRAY,
+                0,
                 gl::DEPTH_COMPONENT24 as i32,
                 self.shadow_map_resolution as i32,
                 self.shadow_map_resolution as i32,
+                self.shadow_cascade_count as i32,
                 0,
                 gl::DEPTH_COMPONENT,
                 gl::FLOAT,
                 std::ptr::null(),
             );
-            self.gl
-                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-            self.gl
-                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
             self.gl.TexParameteri(
-                gl::TEXTURE_2D,
+                gl::TEXTURE_2D_ARRAY,
+                gl::TEXTURE_MIN_FILTER,
+                gl::NEAREST as i32,
+            );
+            self.gl.TexParameteri(
+                gl::TEXTURE_2D_ARRAY,
+                gl::TEXTURE_MAG_FILTER,
+                gl::NEAREST as i32,
+            );
+            self.gl.TexParameteri(
+                gl::TEXTURE_2D_ARRAY,
                 gl::TEXTURE_WRAP_S,
                 gl::CLAMP_TO_BORDER as i32,
             );
             self.gl.TexParameteri(
-                gl::TEXTURE_2D,
+                gl::TEXTURE_2D_ARRAY,
                 gl::TEXTURE_WRAP_T,
                 gl::CLAMP_TO_BORDER as i32,
             );
             let border_color: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
             self.gl.TexParameterfv(
-                gl::TEXTURE_2D,
+                gl::TEXTURE_2D_ARRAY,
                 gl::TEXTURE_BORDER_COLOR,
                 border_color.as_ptr(),
             );
 
             self.gl.GenFramebuffers(1, &mut self.shadow_fbo);
             self.gl.BindFramebuffer(gl::FRAMEBUFFER, self.shadow_fbo);
-            self.gl.FramebufferTexture2D(
+            self.gl.FramebufferTextureLayer(
                 gl::FRAMEBUFFER,
                 gl::DEPTH_ATTACHMENT,
-                gl::TEXTURE_2D,
                 self.shadow_map_texture,
                 0,
+                0,
             );
             self.gl.DrawBuffer(gl::NONE);
             self.gl.ReadBuffer(gl::NONE);
 
             if self.gl.CheckFramebufferStatus(gl::FRAMEBUFFER) != gl::FRAMEBUFFER_COMPLETE {
                 self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
-                self.gl.BindTexture(gl::TEXTURE_2D, 0);
+                self.gl.BindTexture(gl::TEXTURE_2D_ARRAY, 0);
                 self.gl.DeleteTextures(1, &self.shadow_map_texture);
                 self.gl.DeleteFramebuffers(1, &self.shadow_fbo);
                 self.shadow_map_texture = 0;
@@ -226,7 +553,7 @@ impl Renderer {
             }
 
             self.gl.BindFramebuffer(gl::FRAMEBUFFER, 0);
-            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+            self.gl.BindTexture(gl::TEXTURE_2D_ARRAY, 0);
         }
         Ok(())
     }
@@ -250,6 +577,59 @@ impl Renderer {
         }
     }
 
+    // The sky shader builds a full-screen triangle from gl_VertexID, so its VAO has no buffers.
+    pub fn draw_sky(&self) {
+        self.draw_procedural(gl::TRIANGLES, 3);
+    }
+
+    // Draws vertices whose shader derives everything from gl_VertexID.
+    pub fn draw_procedural(&self, mode: gl::types::GLenum, vertex_count: usize) {
+        unsafe {
+            self.gl.BindVertexArray(self.empty_vao);
+            self.gl.DrawArrays(mode, 0, vertex_count as i32);
+        }
+    }
+
+    // Takes a ground height and snow flag per column.
+    pub fn upload_precipitation_heightmap(&mut self, size: usize, texels: &[f32]) {
+        unsafe {
+            if self.precipitation_heightmap_texture == 0 {
+                self.gl
+                    .GenTextures(1, &mut self.precipitation_heightmap_texture);
+            }
+            self.gl
+                .BindTexture(gl::TEXTURE_2D, self.precipitation_heightmap_texture);
+            self.gl.TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RG32F as i32,
+                size as i32,
+                size as i32,
+                0,
+                gl::RG,
+                gl::FLOAT,
+                texels.as_ptr() as *const _,
+            );
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    pub fn bind_precipitation_heightmap(&self, texture_unit: gl::types::GLenum) {
+        unsafe {
+            self.gl.ActiveTexture(texture_unit);
+            self.gl
+                .BindTexture(gl::TEXTURE_2D, self.precipitation_heightmap_texture);
+        }
+    }
+
     pub fn bind_star_vao(&self) {
         unsafe {
             self.gl.BindVertexArray(self.star_vao);
@@ -288,53 +668,151 @@ impl Renderer {
                 gl::STATIC_DRAW,
             );
 
-            let stride = (9 * std::mem::size_of::<f32>()) as gl::types::GLsizei;
+            self.configure_chunk_vertex_attributes();
 
-            self.gl
-                .VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
-            self.gl.EnableVertexAttribArray(0);
+            self.vaos.insert(mesh_id, vao);
+            self.vbos.insert(mesh_id, vbo);
+            self.ebos.insert(mesh_id, ebo);
 
-            self.gl.VertexAttribPointer(
-                1,
-                2,
-                gl::FLOAT,
-                gl::FALSE,
-                stride,
-                (3 * std::mem::size_of::<f32>()) as *const _,
+            self.gl.BindVertexArray(0);
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, 0);
+            self.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+        }
+    }
+
+    pub fn upload_chunk_mesh(
+        &mut self,
+        mesh_id: usize,
+        vertices: &[f32],
+        indices: &[u32],
+        origin: Vec3,
+    ) -> bool {
+        self.cleanup_mesh_buffers(mesh_id);
+
+        if vertices.is_empty() || indices.is_empty() {
+            return false;
+        }
+
+        let vertex_count = vertices.len() / CHUNK_VERTEX_FLOATS;
+        let vertex_allocation = match self.allocate_chunk_vertices(vertex_count) {
+            Some(allocation) => allocation,
+            None => {
+                eprintln!("Failed to allocate {} chunk vertices", vertex_count);
+                return false;
+            }
+        };
+        let index_allocation = match self.allocate_chunk_indices(indices.len()) {
+            Some(allocation) => allocation,
+            None => {
+                eprintln!("Failed to allocate {} chunk indices", indices.len());
+                self.chunk_vertex_arena.free(vertex_allocation);
+                return false;
+            }
+        };
+
+        let mut world_vertices = vertices.to_vec();
+        for vertex in world_vertices.chunks_exact_mut(CHUNK_VERTEX_FLOATS) {
+            vertex[0] += origin.x;
+            vertex[1] += origin.y;
+            vertex[2] += origin.z;
+        }
+
+        unsafe {
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, self.chunk_vbo);
+            self.gl.BufferSubData(
+                gl::COPY_WRITE_BUFFER,
+                (vertex_allocation.offset * CHUNK_VERTEX_BYTES) as gl::types::GLintptr,
+                std::mem::size_of_val(world_vertices.as_slice()) as gl::types::GLsizeiptr,
+                world_vertices.as_ptr() as *const _,
             );
-            self.gl.EnableVertexAttribArray(1);
 
-            self.gl.VertexAttribPointer(
-                2,
-                1,
-                gl::FLOAT,
-                gl::FALSE,
-                stride,
-                (5 * std::mem::size_of::<f32>()) as *const _,
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, self.chunk_ebo);
+            self.gl.BufferSubData(
+                gl::COPY_WRITE_BUFFER,
+                (index_allocation.offset * CHUNK_INDEX_BYTES) as gl::types::GLintptr,
+                std::mem::size_of_val(indices) as gl::types::GLsizeiptr,
+                indices.as_ptr() as *const _,
             );
-            self.gl.EnableVertexAttribArray(2);
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, 0);
+        }
 
-            self.gl.VertexAttribPointer(
-                3,
-                3,
-                gl::FLOAT,
-                gl::FALSE,
-                stride,
-                (6 * std::mem::size_of::<f32>()) as *const _,
+        self.chunk_allocations.insert(
+            mesh_id,
+            ChunkAllocation {
+                vertices: vertex_allocation,
+                indices: index_allocation,
+            },
+        );
+        true
+    }
+
+    pub fn update_chunk_indices(&mut self, mesh_id: usize, indices: &[u32]) -> bool {
+        let allocation = match self.chunk_allocations.get(&mesh_id) {
+            Some(allocation) if allocation.indices.size == indices.len() => allocation,
+            _ => return false,
+        };
+
+        unsafe {
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, self.chunk_ebo);
+            self.gl.BufferSubData(
+                gl::COPY_WRITE_BUFFER,
+                (allocation.indices.offset * CHUNK_INDEX_BYTES) as gl::types::GLintptr,
+                std::mem::size_of_val(indices) as gl::types::GLsizeiptr,
+                indices.as_ptr() as *const _,
             );
-            self.gl.EnableVertexAttribArray(3);
+            self.gl.BindBuffer(gl::COPY_WRITE_BUFFER, 0);
+        }
+        true
+    }
 
-            self.vaos.insert(mesh_id, vao);
-            self.vbos.insert(mesh_id, vbo);
-            self.ebos.insert(mesh_id, ebo);
+    pub fn push_chunk_draw(&self, draw_list: &mut ChunkDrawList, mesh_id: usize) -> bool {
+        match self.chunk_allocations.get(&mesh_id) {
+            Some(allocation) => {
+                draw_list
+                    .counts
+                    .push(allocation.indices.size as gl::types::GLsizei);
+                draw_list
+                    .index_byte_offsets
+                    .push(allocation.indices.offset * CHUNK_INDEX_BYTES);
+                draw_list
+                    .base_vertices
+                    .push(allocation.vertices.offset as gl::types::GLint);
+                true
+            }
+            None => false,
+        }
+    }
 
-            self.gl.BindVertexArray(0);
-            self.gl.BindBuffer(gl::ARRAY_BUFFER, 0);
-            self.gl.BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+    pub fn draw_chunk_batch(&self, draw_list: &ChunkDrawList) {
+        if draw_list.is_empty() {
+            return;
+        }
+
+        let index_offsets: Vec<*const std::ffi::c_void> = draw_list
+            .index_byte_offsets
+            .iter()
+            .map(|offset| *offset as *const std::ffi::c_void)
+            .collect();
+
+        unsafe {
+            self.gl.BindVertexArray(self.chunk_vao);
+            self.gl.MultiDrawElementsBaseVertex(
+                gl::TRIANGLES,
+                draw_list.counts.as_ptr(),
+                gl::UNSIGNED_INT,
+                index_offsets.as_ptr(),
+                draw_list.len() as gl::types::GLsizei,
+                draw_list.base_vertices.as_ptr(),
+            );
         }
     }
 
     pub fn cleanup_mesh_buffers(&mut self, mesh_id: usize) {
+        if let Some(allocation) = self.chunk_allocations.remove(&mesh_id) {
+            self.chunk_vertex_arena.free(allocation.vertices);
+            self.chunk_index_arena.free(allocation.indices);
+        }
+        self.transparent_sort_cells.remove(&mesh_id);
         unsafe {
             if let Some(vao) = self.vaos.remove(&mesh_id) {
                 self.gl.DeleteVertexArrays(1, &vao);
@@ -362,7 +840,115 @@ impl Renderer {
         }
     }
 
-    pub fn bind_shadow_fbo(&self) {
+    // Snapshots the current framebuffer's color and depth so later passes (water) can
+    // sample what is behind them.
+    pub fn copy_scene_to_textures(&mut self, width: i32, height: i32) {
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        if self.scene_copy_size != (width, height) {
+            self.cleanup_scene_textures();
+            self.scene_color_texture = self.create_screen_texture(
+                width,
+                height,
+                (gl::RGBA16F, gl::RGBA, gl::FLOAT),
+                gl::LINEAR,
+            );
+            self.scene_depth_texture = self.create_screen_texture(
+                width,
+                height,
+                (gl::DEPTH_COMPONENT24, gl::DEPTH_COMPONENT, gl::FLOAT),
+                gl::NEAREST,
+            );
+            self.scene_copy_size = (width, height);
+        }
+
+        unsafe {
+            self.gl
+                .BindTexture(gl::TEXTURE_2D, self.scene_color_texture);
+            self.gl
+                .CopyTexSubImage2D(gl::TEXTURE_2D, 0, 0, 0, 0, 0, width, height);
+            self.gl
+                .BindTexture(gl::TEXTURE_2D, self.scene_depth_texture);
+            self.gl
+                .CopyTexSubImage2D(gl::TEXTURE_2D, 0, 0, 0, 0, 0, width, height);
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+    }
+
+    pub fn bind_scene_textures(
+        &self,
+        color_unit: gl::types::GLenum,
+        depth_unit: gl::types::GLenum,
+    ) {
+        unsafe {
+            self.gl.ActiveTexture(color_unit);
+            self.gl
+                .BindTexture(gl::TEXTURE_2D, self.scene_color_texture);
+            self.gl.ActiveTexture(depth_unit);
+            self.gl
+                .BindTexture(gl::TEXTURE_2D, self.scene_depth_texture);
+        }
+    }
+
+    fn create_screen_texture(
+        &self,
+        width: i32,
+        height: i32,
+        (internal_format, format, data_type): (
+            gl::types::GLenum,
+            gl::types::GLenum,
+            gl::types::GLenum,
+        ),
+        filter: gl::types::GLenum,
+    ) -> gl::types::GLuint {
+        let mut texture = 0;
+        unsafe {
+            self.gl.GenTextures(1, &mut texture);
+            self.gl.BindTexture(gl::TEXTURE_2D, texture);
+            self.gl.TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                internal_format as i32,
+                width,
+                height,
+                0,
+                format,
+                data_type,
+                std::ptr::null(),
+            );
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            self.gl
+                .TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+        texture
+    }
+
+    fn cleanup_scene_textures(&mut self) {
+        unsafe {
+            if self.scene_color_texture != 0 {
+                self.gl.DeleteTextures(1, &self.scene_color_texture);
+                self.scene_color_texture = 0;
+            }
+            if self.scene_depth_texture != 0 {
+                self.gl.DeleteTextures(1, &self.scene_depth_texture);
+                self.scene_depth_texture = 0;
+            }
+        }
+        self.scene_copy_size = (0, 0);
+    }
+
+    pub fn shadow_cascade_count(&self) -> usize {
+        self.shadow_cascade_count
+    }
+
+    pub fn bind_shadow_fbo(&self, cascade_index: usize) {
         unsafe {
             self.gl.Viewport(
                 0,
@@ -371,6 +957,13 @@ impl Renderer {
                 self.shadow_map_resolution as i32,
             );
             self.gl.BindFramebuffer(gl::FRAMEBUFFER, self.shadow_fbo);
+            self.gl.FramebufferTextureLayer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                self.shadow_map_texture,
+                0,
+                cascade_index as i32,
+            );
             self.gl.Clear(gl::DEPTH_BUFFER_BIT);
             self.gl.CullFace(gl::FRONT);
         }
@@ -387,7 +980,8 @@ impl Renderer {
     pub fn bind_shadow_map_texture(&self, texture_unit: gl::types::GLenum) {
         unsafe {
             self.gl.ActiveTexture(texture_unit);
-            self.gl.BindTexture(gl::TEXTURE_2D, self.shadow_map_texture);
+            self.gl
+                .BindTexture(gl::TEXTURE_2D_ARRAY, self.shadow_map_texture);
         }
     }
 }
@@ -399,6 +993,15 @@ impl Drop for Renderer {
             self.cleanup_mesh_buffers(mesh_id);
         }
         unsafe {
+            if self.chunk_vao != 0 {
+                self.gl.DeleteVertexArrays(1, &self.chunk_vao);
+            }
+            if self.chunk_vbo != 0 {
+                self.gl.DeleteBuffers(1, &self.chunk_vbo);
+            }
+            if self.chunk_ebo != 0 {
+                self.gl.DeleteBuffers(1, &self.chunk_ebo);
+            }
             if self.celestial_vao != 0 {
                 self.gl.DeleteVertexArrays(1, &self.celestial_vao);
             }
@@ -408,6 +1011,22 @@ impl Drop for Renderer {
             if self.celestial_ebo != 0 {
                 self.gl.DeleteBuffers(1, &self.celestial_ebo);
             }
+            if self.empty_vao != 0 {
+                self.gl.DeleteVertexArrays(1, &self.empty_vao);
+            }
+            if self.cloud_vao != 0 {
+                self.gl.DeleteVertexArrays(1, &self.cloud_vao);
+            }
+            if self.cloud_vbo != 0 {
+                self.gl.DeleteBuffers(1, &self.cloud_vbo);
+            }
+            if self.cloud_texture != 0 {
+                self.gl.DeleteTextures(1, &self.cloud_texture);
+            }
+            if self.precipitation_heightmap_texture != 0 {
+                self.gl
+                    .DeleteTextures(1, &self.precipitation_heightmap_texture);
+            }
             if self.star_vao != 0 {
                 self.gl.DeleteVertexArrays(1, &self.star_vao);
             }
@@ -416,5 +1035,6 @@ impl Drop for Renderer {
             }
         }
         self.cleanup_shadow_fbo();
+        self.cleanup_scene_textures();
     }
 }
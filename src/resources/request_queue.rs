@@ -0,0 +1,236 @@
+use fnv::{FnvHashMap, FnvHashSet};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::hash::Hash;
+use std::sync::Mutex;
+
+struct HeapEntry<K> {
+    priority: f32,
+    sequence: u64,
+    key: K,
+}
+
+impl<K> PartialEq for HeapEntry<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<K> Eq for HeapEntry<K> {}
+
+impl<K> PartialOrd for HeapEntry<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Reversed so the max-heap pops the lowest priority value first, oldest first on ties.
+impl<K> Ord for HeapEntry<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+// Main-thread queue of work waiting for a worker, keyed so a request can be replaced or
+// cancelled before it is sent. Lower priority values are popped first. Heap entries left
+// behind by replaced or removed requests are skipped lazily on pop.
+pub struct RequestQueue<K, T> {
+    heap: BinaryHeap<HeapEntry<K>>,
+    entries: FnvHashMap<K, (u64, T)>,
+    next_sequence: u64,
+}
+
+impl<K: Copy + Eq + Hash, T> RequestQueue<K, T> {
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            entries: FnvHashMap::default(),
+            next_sequence: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    // Queues `item`, replacing any request already queued under `key`.
+    pub fn push(&mut self, key: K, priority: f32, item: T) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.entries.insert(key, (sequence, item));
+        self.heap.push(HeapEntry {
+            priority,
+            sequence,
+            key,
+        });
+    }
+
+    pub fn pop(&mut self) -> Option<(K, T)> {
+        while let Some(entry) = self.heap.pop() {
+            if self
+                .entries
+                .get(&entry.key)
+                .is_some_and(|(sequence, _)| *sequence == entry.sequence)
+            {
+                return self
+                    .entries
+                    .remove(&entry.key)
+                    .map(|(_, item)| (entry.key, item));
+            }
+        }
+        None
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<T> {
+        self.entries.remove(key).map(|(_, item)| item)
+    }
+
+    // Recomputes every priority. Requests for which `priority` returns None are cancelled
+    // and their keys returned.
+    pub fn reprioritize<F>(&mut self, mut priority: F) -> Vec<K>
+    where
+        F: FnMut(&K, &T) -> Option<f32>,
+    {
+        let mut cancelled = Vec::new();
+        let mut heap = Vec::with_capacity(self.entries.len());
+        self.entries
+            .retain(|key, (sequence, item)| match priority(key, item) {
+                Some(priority) => {
+                    heap.push(HeapEntry {
+                        priority,
+                        sequence: *sequence,
+                        key: *key,
+                    });
+                    true
+                }
+                None => {
+                    cancelled.push(*key);
+                    false
+                }
+            });
+        self.heap = BinaryHeap::from(heap);
+        cancelled
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.entries.clear();
+    }
+}
+
+impl<K: Copy + Eq + Hash, T> Default for RequestQueue<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Keys of requests already handed to the workers that are no longer wanted. Shared with
+// the worker threads, which drop a request instead of running it if its key is in here.
+pub struct CancellationSet<K> {
+    keys: Mutex<FnvHashSet<K>>,
+}
+
+impl<K: Eq + Hash> CancellationSet<K> {
+    pub fn new() -> Self {
+        Self {
+            keys: Mutex::new(FnvHashSet::default()),
+        }
+    }
+
+    pub fn cancel(&self, key: K) {
+        self.keys.lock().unwrap().insert(key);
+    }
+
+    // Called when a key is requested again so a stale cancellation doesn't drop it.
+    pub fn revoke(&self, key: &K) {
+        self.keys.lock().unwrap().remove(key);
+    }
+
+    // Returns true and forgets the cancellation if `key` was cancelled.
+    pub fn take(&self, key: &K) -> bool {
+        self.keys.lock().unwrap().remove(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Eq + Hash> Default for CancellationSet<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Counts failed jobs per key. A key is retried until it has failed `max_attempts` times, then
+// reported as errored so it isn't requested again until cleared.
+pub struct FailureTracker<K> {
+    max_attempts: u32,
+    failures: FnvHashMap<K, u32>,
+}
+
+impl<K: Copy + Eq + Hash> FailureTracker<K> {
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            failures: FnvHashMap::default(),
+        }
+    }
+
+    pub fn set_max_attempts(&mut self, max_attempts: u32) {
+        self.max_attempts = max_attempts.max(1);
+    }
+
+    // Returns true if the job should be retried.
+    pub fn record_failure(&mut self, key: K) -> bool {
+        let failures = self.failures.entry(key).or_insert(0);
+        *failures += 1;
+        *failures < self.max_attempts
+    }
+
+    pub fn is_errored(&self, key: &K) -> bool {
+        self.failures
+            .get(key)
+            .is_some_and(|failures| *failures >= self.max_attempts)
+    }
+
+    pub fn clear(&mut self, key: &K) {
+        self.failures.remove(key);
+    }
+
+    pub fn clear_all(&mut self) {
+        self.failures.clear();
+    }
+
+    pub fn retain<F: FnMut(&K) -> bool>(&mut self, mut keep: F) {
+        self.failures.retain(|key, _| keep(key));
+    }
+
+    pub fn errored_count(&self) -> usize {
+        self.failures
+            .values()
+            .filter(|failures| **failures >= self.max_attempts)
+            .count()
+    }
+}
+
+impl<K: Copy + Eq + Hash> Default for FailureTracker<K> {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
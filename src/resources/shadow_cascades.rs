@@ -0,0 +1,92 @@
+use crate::resources::{Camera, Config};
+use glam::{Mat4, Vec3};
+
+pub const MAX_SHADOW_CASCADES: usize = 4;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowCascade {
+    pub light_space_matrix: Mat4,
+    pub split_depth: f32,
+    pub world_units_per_texel: f32,
+    pub depth_bias: f32,
+}
+
+// Blends a logarithmic and a uniform split scheme; `lambda` = 1 is fully logarithmic.
+pub fn calculate_cascade_splits(near: f32, far: f32, count: usize, lambda: f32) -> Vec<f32> {
+    (1..=count)
+        .map(|i| {
+            let fraction = i as f32 / count as f32;
+            let logarithmic = near * (far / near).powf(fraction);
+            let uniform = near + (far - near) * fraction;
+            lambda * logarithmic + (1.0 - lambda) * uniform
+        })
+        .collect()
+}
+
+pub fn calculate_shadow_cascades(
+    camera: &mut Camera,
+    light_direction: Vec3,
+    config: &Config,
+) -> Vec<ShadowCascade> {
+    let cascade_count = config.shadow_cascade_count.clamp(1, MAX_SHADOW_CASCADES);
+    let shadow_distance_world = config.shadow_distance as f32 * config.chunk_width as f32;
+    let near = camera.z_near();
+    let far = shadow_distance_world.min(camera.z_far());
+    let splits =
+        calculate_cascade_splits(near, far, cascade_count, config.shadow_cascade_split_lambda);
+
+    let up = if light_direction.abs().y > 0.99 {
+        Vec3::Z
+    } else {
+        Vec3::Y
+    };
+    // `light_direction` points towards the light, so the view looks the opposite way.
+    let light_view_matrix = Mat4::look_at_rh(Vec3::ZERO, -light_direction, up);
+
+    let mut slice_near = near;
+    splits
+        .iter()
+        .map(|&slice_far| {
+            let corners = camera.frustum_corners(slice_near, slice_far);
+            slice_near = slice_far;
+
+            let center = corners.iter().copied().sum::<Vec3>() / corners.len() as f32;
+            let radius = corners
+                .iter()
+                .map(|corner| corner.distance(center))
+                .fold(0.0f32, f32::max);
+            // Rounding the radius keeps the projection size constant while the camera turns.
+            let radius = (radius * 16.0).ceil() / 16.0;
+
+            let world_units_per_texel = radius * 2.0 / config.shadow_map_resolution as f32;
+
+            let center_light_view = light_view_matrix.transform_point3(center);
+            let snapped_center_x =
+                (center_light_view.x / world_units_per_texel).floor() * world_units_per_texel;
+            let snapped_center_y =
+                (center_light_view.y / world_units_per_texel).floor() * world_units_per_texel;
+
+            // Geometry between the light and the slice still casts into it.
+            let center_depth = -center_light_view.z;
+            let near_plane = center_depth - radius - shadow_distance_world;
+            let far_plane = center_depth + radius;
+
+            let light_projection_matrix = Mat4::orthographic_rh(
+                snapped_center_x - radius,
+                snapped_center_x + radius,
+                snapped_center_y - radius,
+                snapped_center_y + radius,
+                near_plane,
+                far_plane,
+            );
+
+            let depth_range = far_plane - near_plane;
+            ShadowCascade {
+                light_space_matrix: light_projection_matrix * light_view_matrix,
+                split_depth: slice_far,
+                world_units_per_texel,
+                depth_bias: 0.5 * world_units_per_texel * config.shadow_bias_texels / depth_range,
+            }
+        })
+        .collect()
+}
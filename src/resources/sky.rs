@@ -0,0 +1,106 @@
+use crate::resources::{far_terrain_radius, Config};
+use glam::Vec3;
+use std::f32::consts::PI;
+
+// Sea-level scattering coefficients (per meter) and scale heights of the Earth's atmosphere.
+// Keep in sync with sky_fragment.glsl.
+const RAYLEIGH_COEFFICIENTS: Vec3 = Vec3::new(5.8e-6, 13.5e-6, 33.1e-6);
+const MIE_COEFFICIENT: f32 = 21e-6;
+const RAYLEIGH_SCALE_HEIGHT: f32 = 8000.0;
+const MIE_SCALE_HEIGHT: f32 = 1200.0;
+// Most scattering happens about one scale height up, where only 1/e of the air is left above.
+const SCATTERING_ALTITUDE_DEPTH: f32 = 0.37;
+
+// Kasten-Young relative air mass; directions below the horizon are treated as horizontal.
+fn air_mass(direction: Vec3) -> f32 {
+    let cos_zenith = direction.y.max(0.0);
+    let zenith_degrees = cos_zenith.acos().to_degrees();
+    1.0 / (cos_zenith + 0.50572 * (96.07995 - zenith_degrees).powf(-1.6364))
+}
+
+fn rayleigh_phase(cos_theta: f32) -> f32 {
+    3.0 / (16.0 * PI) * (1.0 + cos_theta * cos_theta)
+}
+
+fn henyey_greenstein_phase(cos_theta: f32, g: f32) -> f32 {
+    let g2 = g * g;
+    (1.0 - g2) / (4.0 * PI * (1.0 + g2 - 2.0 * g * cos_theta).powf(1.5))
+}
+
+// Single-scattering sky: sunlight attenuated on its way down, scattered once towards the
+// viewer, with the in-scattered amount growing with the optical depth along the view ray.
+pub fn scattering_sky_color(view_dir: Vec3, sun_dir: Vec3, config: &Config) -> Vec3 {
+    let view_dir = view_dir.normalize();
+    let sun_dir = sun_dir.normalize();
+    let cos_theta = view_dir.dot(sun_dir);
+
+    let rayleigh_depth = RAYLEIGH_COEFFICIENTS * RAYLEIGH_SCALE_HEIGHT;
+    let mie_depth = Vec3::splat(MIE_COEFFICIENT * MIE_SCALE_HEIGHT);
+    let optical_depth = rayleigh_depth + mie_depth;
+
+    let sun_transmittance = (-optical_depth * air_mass(sun_dir) * SCATTERING_ALTITUDE_DEPTH).exp();
+    let view_extinction = Vec3::ONE - (-optical_depth * air_mass(view_dir)).exp();
+
+    let scattering = rayleigh_depth * rayleigh_phase(cos_theta)
+        + mie_depth * henyey_greenstein_phase(cos_theta, config.sky_mie_anisotropy);
+    let daylight = smoothstep(-0.1, 0.05, sun_dir.y);
+
+    config.midnight_color
+        + scattering / optical_depth
+            * view_extinction
+            * sun_transmittance
+            * config.sky_sun_intensity
+            * daylight
+}
+
+// Distance band over which terrain fades into the sky. The fog is opaque before the outermost
+// ring of chunks so chunks popping in at the render edge stay hidden.
+pub fn fog_range(config: &Config) -> (f32, f32) {
+    let fog_end = if config.far_terrain_enabled {
+        far_terrain_radius(config) - config.far_terrain_tile_size as f32
+    } else {
+        (config.render_distance - 1).max(1) as f32 * config.chunk_width as f32
+    };
+    (fog_end * config.fog_start_fraction, fog_end)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+pub fn light_level(time: f32, config: &Config) -> f32 {
+    let sunrise_start = config.sunrise_center_time - config.day_night_transition_duration;
+    let sunrise_end = config.sunrise_center_time + config.day_night_transition_duration;
+    let sunset_start = config.sunset_center_time - config.day_night_transition_duration;
+    let sunset_end = config.sunset_center_time + config.day_night_transition_duration;
+
+    if (sunrise_end..sunset_start).contains(&time) {
+        config.max_light_level
+    } else if (sunrise_start..sunrise_end).contains(&time) {
+        let factor = (time - sunrise_start) / (sunrise_end - sunrise_start);
+        config.min_light_level
+            + (config.max_light_level - config.min_light_level) * factor.clamp(0.0, 1.0)
+    } else if (sunset_start..sunset_end).contains(&time) {
+        let factor = (time - sunset_start) / (sunset_end - sunset_start);
+        config.max_light_level
+            - (config.max_light_level - config.min_light_level) * factor.clamp(0.0, 1.0)
+    } else {
+        config.min_light_level
+    }
+}
+
+// Unit vector from the world towards the sun; the moon sits opposite.
+pub fn sun_direction(time_of_day: f32) -> Vec3 {
+    let angle = time_of_day * 2.0 * PI;
+    Vec3::new(angle.sin(), (angle + PI).cos(), 0.0).normalize()
+}
+
+// Direction towards whichever body currently lights the scene, blending across dawn and dusk.
+pub fn light_direction(time_of_day: f32, config: &Config) -> Vec3 {
+    let sun_dir = sun_direction(time_of_day);
+    let sun_blend_factor = ((light_level(time_of_day, config) - config.min_light_level)
+        / (config.max_light_level - config.min_light_level))
+        .clamp(0.0, 1.0);
+    sun_dir.lerp(-sun_dir, 1.0 - sun_blend_factor).normalize()
+}
@@ -28,6 +28,7 @@ impl TextureManager {
     pub fn load_textures_as_array(
         &mut self,
         texture_files: &[(&str, &str)],
+        srgb: bool,
     ) -> Result<(), Box<dyn Error>> {
         self.cleanup_texture();
 
@@ -57,6 +58,9 @@ impl TextureManager {
         self.texture_width = max_width;
         self.texture_height = max_height;
 
+        // Linear-light rendering needs the texels decoded from sRGB when they are sampled.
+        let internal_format = if srgb { gl::SRGB8_ALPHA8 } else { gl::RGBA8 };
+
         unsafe {
             self.gl.GenTextures(1, &mut self.array_texture_id);
             self.gl
@@ -65,7 +69,7 @@ impl TextureManager {
             self.gl.TexImage3D(
                 gl::TEXTURE_2D_ARRAY,
                 0,
-                gl::RGBA8 as i32,
+                internal_format as i32,
                 max_width as i32,
                 max_height as i32,
                 self.layer_count as i32,
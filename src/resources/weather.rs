@@ -0,0 +1,321 @@
+use crate::resources::Config;
+use glam::IVec2;
+use rand::Rng;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherKind {
+    Clear,
+    Rain,
+    Thunderstorm,
+    Snow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precipitation {
+    Rain,
+    Snow,
+}
+
+impl WeatherKind {
+    pub fn has_precipitation(&self) -> bool {
+        !matches!(self, WeatherKind::Clear)
+    }
+
+    pub fn darkening(&self, config: &Config) -> f32 {
+        match self {
+            WeatherKind::Clear => 0.0,
+            WeatherKind::Rain => config.rain_darkening,
+            WeatherKind::Thunderstorm => config.thunderstorm_darkening,
+            WeatherKind::Snow => config.snow_darkening,
+        }
+    }
+
+    pub fn cloud_coverage(&self, config: &Config) -> f32 {
+        match self {
+            WeatherKind::Clear => config.cloud_coverage,
+            _ => config.overcast_cloud_coverage,
+        }
+    }
+
+    // Transition table; `roll` is uniform in [0, 1).
+    pub fn next(&self, roll: f32) -> WeatherKind {
+        let table: &[(f32, WeatherKind)] = match self {
+            WeatherKind::Clear => &[
+                (0.5, WeatherKind::Rain),
+                (0.7, WeatherKind::Snow),
+                (0.8, WeatherKind::Thunderstorm),
+            ],
+            WeatherKind::Rain => &[
+                (0.55, WeatherKind::Clear),
+                (0.85, WeatherKind::Thunderstorm),
+            ],
+            WeatherKind::Thunderstorm => &[(0.7, WeatherKind::Rain)],
+            WeatherKind::Snow => &[(0.75, WeatherKind::Clear), (0.9, WeatherKind::Rain)],
+        };
+        table
+            .iter()
+            .find(|(threshold, _)| roll < *threshold)
+            .map(|(_, kind)| *kind)
+            .unwrap_or(match self {
+                WeatherKind::Clear => WeatherKind::Clear,
+                WeatherKind::Rain => WeatherKind::Rain,
+                WeatherKind::Thunderstorm => WeatherKind::Clear,
+                WeatherKind::Snow => WeatherKind::Snow,
+            })
+    }
+}
+
+// Rain falling on ground at or above the snow line comes down as snow instead.
+pub fn precipitation_at(
+    kind: WeatherKind,
+    ground_height: i32,
+    snow_line: i32,
+) -> Option<Precipitation> {
+    match kind {
+        WeatherKind::Clear => None,
+        WeatherKind::Snow => Some(Precipitation::Snow),
+        WeatherKind::Rain | WeatherKind::Thunderstorm if ground_height >= snow_line => {
+            Some(Precipitation::Snow)
+        }
+        WeatherKind::Rain | WeatherKind::Thunderstorm => Some(Precipitation::Rain),
+    }
+}
+
+pub struct Weather {
+    previous: WeatherKind,
+    current: WeatherKind,
+    transition: f32,
+    time_remaining: f32,
+    lightning_flash: f32,
+}
+
+impl Weather {
+    pub fn new() -> Self {
+        Self {
+            previous: WeatherKind::Clear,
+            current: WeatherKind::Clear,
+            transition: 1.0,
+            time_remaining: 0.0,
+            lightning_flash: 0.0,
+        }
+    }
+
+    pub fn current(&self) -> WeatherKind {
+        self.current
+    }
+
+    pub fn transition(&self) -> f32 {
+        self.transition
+    }
+
+    pub fn time_remaining(&self) -> f32 {
+        self.time_remaining
+    }
+
+    pub fn lightning_flash(&self) -> f32 {
+        self.lightning_flash
+    }
+
+    // While fading out to clear skies the precipitation keeps the type it had.
+    pub fn precipitation_kind(&self) -> WeatherKind {
+        if self.current.has_precipitation() {
+            self.current
+        } else {
+            self.previous
+        }
+    }
+
+    pub fn precipitation_intensity(&self) -> f32 {
+        let amount = |kind: WeatherKind| if kind.has_precipitation() { 1.0 } else { 0.0 };
+        self.blend(amount(self.previous), amount(self.current))
+    }
+
+    pub fn darkening(&self, config: &Config) -> f32 {
+        self.blend(
+            self.previous.darkening(config),
+            self.current.darkening(config),
+        )
+    }
+
+    pub fn cloud_coverage(&self, config: &Config) -> f32 {
+        self.blend(
+            self.previous.cloud_coverage(config),
+            self.current.cloud_coverage(config),
+        )
+    }
+
+    pub fn set_weather(&mut self, kind: WeatherKind, duration: f32) {
+        self.previous = self.current;
+        self.current = kind;
+        self.transition = 0.0;
+        self.time_remaining = duration;
+    }
+
+    pub fn update(&mut self, delta_time: f32, config: &Config, rng: &mut impl Rng) {
+        self.transition =
+            (self.transition + delta_time / config.weather_transition_duration.max(1e-3)).min(1.0);
+        self.lightning_flash *= (-delta_time * 8.0).exp();
+
+        if self.current == WeatherKind::Thunderstorm && self.transition >= 1.0 {
+            let strike_chance = delta_time / config.lightning_interval.max(1e-3);
+            if rng.random::<f32>() < strike_chance {
+                self.lightning_flash = 1.0;
+            }
+        }
+
+        self.time_remaining -= delta_time;
+        if self.time_remaining <= 0.0 {
+            let next = self.current.next(rng.random::<f32>());
+            let duration = rng.random_range(
+                config.weather_min_duration
+                    ..=config.weather_max_duration.max(config.weather_min_duration),
+            );
+            if next == self.current {
+                self.time_remaining = duration;
+            } else {
+                self.set_weather(next, duration);
+            }
+        }
+    }
+
+    fn blend(&self, from: f32, to: f32) -> f32 {
+        from + (to - from) * self.transition
+    }
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Height of the highest non-air block and the snow line for each column in a square around
+// the camera, used to keep rain and snow out of caves and from under roofs and trees, and to
+// pick which of the two falls on each column.
+pub struct PrecipitationHeightmap {
+    origin: IVec2,
+    size: usize,
+    heights: Vec<f32>,
+    snow_lines: Vec<i32>,
+    kind: WeatherKind,
+    dirty: bool,
+}
+
+impl PrecipitationHeightmap {
+    pub const NO_GROUND: f32 = -1.0e6;
+
+    pub fn new(radius: usize) -> Self {
+        let size = radius * 2 + 1;
+        Self {
+            origin: IVec2::ZERO,
+            size,
+            heights: vec![Self::NO_GROUND; size * size],
+            snow_lines: vec![i32::MAX; size * size],
+            kind: WeatherKind::Clear,
+            dirty: true,
+        }
+    }
+
+    pub fn origin(&self) -> IVec2 {
+        self.origin
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // Every column the map covers, in world coordinates.
+    pub fn columns(&self) -> Vec<IVec2> {
+        let size = self.size as i32;
+        (0..size)
+            .flat_map(|z| (0..size).map(move |x| IVec2::new(x, z)))
+            .map(|local| self.origin + local)
+            .collect()
+    }
+
+    // Moves the map to a new center, keeping the columns it still covers. Returns the columns
+    // that came into range, which are empty until set.
+    pub fn recenter(&mut self, center: IVec2) -> Vec<IVec2> {
+        let radius = (self.size / 2) as i32;
+        let previous_origin = self.origin;
+        let previous_heights = std::mem::take(&mut self.heights);
+        let previous_snow_lines = std::mem::take(&mut self.snow_lines);
+        self.origin = center - IVec2::splat(radius);
+        self.heights = vec![Self::NO_GROUND; self.size * self.size];
+        self.snow_lines = vec![i32::MAX; self.size * self.size];
+        self.dirty = true;
+
+        let mut entered = Vec::new();
+        for column in self.columns() {
+            let index = self.index(column).expect("column of the new map");
+            let previous = column - previous_origin;
+            let size = self.size as i32;
+            if previous.x >= 0 && previous.y >= 0 && previous.x < size && previous.y < size {
+                let previous_index = previous.y as usize * self.size + previous.x as usize;
+                self.heights[index] = previous_heights[previous_index];
+                self.snow_lines[index] = previous_snow_lines[previous_index];
+            } else {
+                entered.push(column);
+            }
+        }
+        entered
+    }
+
+    pub fn height_at(&self, world_x: i32, world_z: i32) -> Option<f32> {
+        self.index(IVec2::new(world_x, world_z))
+            .map(|index| self.heights[index])
+    }
+
+    pub fn set_column(&mut self, world_x: i32, world_z: i32, height: f32, snow_line: i32) {
+        if let Some(index) = self.index(IVec2::new(world_x, world_z)) {
+            if self.heights[index] != height || self.snow_lines[index] != snow_line {
+                self.heights[index] = height;
+                self.snow_lines[index] = snow_line;
+                self.dirty = true;
+            }
+        }
+    }
+
+    pub fn set_precipitation_kind(&mut self, kind: WeatherKind) {
+        if self.kind != kind {
+            self.kind = kind;
+            self.dirty = true;
+        }
+    }
+
+    pub fn precipitation_at(&self, world_x: i32, world_z: i32) -> Option<Precipitation> {
+        let index = self.index(IVec2::new(world_x, world_z))?;
+        precipitation_at(
+            self.kind,
+            self.heights[index].floor() as i32,
+            self.snow_lines[index],
+        )
+    }
+
+    // Ground height and a snow flag per column, row by row, for the precipitation shader.
+    pub fn texels(&self) -> Vec<f32> {
+        self.columns()
+            .into_iter()
+            .flat_map(|column| {
+                let height = self
+                    .height_at(column.x, column.y)
+                    .unwrap_or(Self::NO_GROUND);
+                let snows = self.precipitation_at(column.x, column.y) == Some(Precipitation::Snow);
+                [height, if snows { 1.0 } else { 0.0 }]
+            })
+            .collect()
+    }
+
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+
+    fn index(&self, column: IVec2) -> Option<usize> {
+        let local = column - self.origin;
+        let size = self.size as i32;
+        if local.x < 0 || local.y < 0 || local.x >= size || local.y >= size {
+            return None;
+        }
+        Some(local.y as usize * self.size + local.x as usize)
+    }
+}
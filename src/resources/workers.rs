@@ -0,0 +1,278 @@
+use crate::resources::Config;
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Once};
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    Generation,
+    Meshing,
+    FarTerrain,
+}
+
+impl JobKind {
+    pub const ALL: [JobKind; 3] = [JobKind::Generation, JobKind::Meshing, JobKind::FarTerrain];
+
+    fn index(self) -> usize {
+        match self {
+            JobKind::Generation => 0,
+            JobKind::Meshing => 1,
+            JobKind::FarTerrain => 2,
+        }
+    }
+}
+
+// Threads for (generation, meshing). Zero in the config means "pick for me": the cores left
+// after the main thread are split evenly, and each pool always gets at least one thread.
+pub fn worker_thread_budget(config: &Config, available_threads: usize) -> (usize, usize) {
+    let available = available_threads.max(2);
+    let meshing = match config.meshing_threads {
+        0 => (available / 2).max(1),
+        n => n,
+    };
+    let generation = match config.generation_threads {
+        0 => available.saturating_sub(meshing).max(1),
+        n => n,
+    };
+    (generation, meshing)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JobCounts {
+    pub completed: u64,
+    pub busy_nanos: u64,
+}
+
+// Lock-free counters the workers bump after every job.
+#[derive(Default)]
+pub struct WorkerStats {
+    completed: [AtomicU64; 3],
+    busy_nanos: [AtomicU64; 3],
+}
+
+impl WorkerStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, kind: JobKind, duration: Duration) {
+        let index = kind.index();
+        self.completed[index].fetch_add(1, Ordering::Relaxed);
+        self.busy_nanos[index].fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn counts(&self, kind: JobKind) -> JobCounts {
+        let index = kind.index();
+        JobCounts {
+            completed: self.completed[index].load(Ordering::Relaxed),
+            busy_nanos: self.busy_nanos[index].load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct JobStats {
+    pub threads: usize,
+    pub queue_len: usize,
+    pub jobs_completed: u64,
+    pub jobs_per_sec: f32,
+    pub average_job_ms: f32,
+}
+
+impl JobStats {
+    // Rates over the window between two samples of the counters.
+    pub fn between(
+        previous: JobCounts,
+        current: JobCounts,
+        elapsed_secs: f32,
+        threads: usize,
+        queue_len: usize,
+    ) -> Self {
+        let jobs = current.completed.saturating_sub(previous.completed);
+        let busy_nanos = current.busy_nanos.saturating_sub(previous.busy_nanos);
+        Self {
+            threads,
+            queue_len,
+            jobs_completed: current.completed,
+            jobs_per_sec: if elapsed_secs > 0.0 {
+                jobs as f32 / elapsed_secs
+            } else {
+                0.0
+            },
+            average_job_ms: if jobs > 0 {
+                busy_nanos as f32 / jobs as f32 / 1_000_000.0
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+impl fmt::Display for JobStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.0}/s {:.1}ms q{}",
+            self.jobs_per_sec, self.average_job_ms, self.queue_len
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WorkerReport {
+    pub generation: JobStats,
+    pub meshing: JobStats,
+    pub far_terrain: JobStats,
+    pub respawned_workers: usize,
+}
+
+impl WorkerReport {
+    pub fn get_mut(&mut self, kind: JobKind) -> &mut JobStats {
+        match kind {
+            JobKind::Generation => &mut self.generation,
+            JobKind::Meshing => &mut self.meshing,
+            JobKind::FarTerrain => &mut self.far_terrain,
+        }
+    }
+}
+
+impl fmt::Display for WorkerReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "gen {} | mesh {} | far {}",
+            self.generation, self.meshing, self.far_terrain
+        )?;
+        if self.respawned_workers > 0 {
+            write!(f, " | respawned {}", self.respawned_workers)?;
+        }
+        Ok(())
+    }
+}
+
+// Runs one worker job, turning a panic into an error message so the thread survives and the
+// requester hears about the failure instead of waiting forever.
+pub fn run_catching_panics<T>(job: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(job)).map_err(|payload| panic_message(&*payload))
+}
+
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+thread_local! {
+    static IS_WORKER_THREAD: Cell<bool> = const { Cell::new(false) };
+}
+
+// Worker panics are logged by whoever catches them, with the job or thread they came from, so
+// the default hook's report would be a second line for the same panic.
+fn silence_worker_panics() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IS_WORKER_THREAD.with(Cell::get) {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+struct WorkerThread {
+    name: String,
+    body: Arc<dyn Fn() + Send + Sync>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl WorkerThread {
+    fn start(name: String, body: Arc<dyn Fn() + Send + Sync>) -> Self {
+        let thread_body = Arc::clone(&body);
+        let handle = thread::Builder::new()
+            .name(name.clone())
+            .spawn(move || {
+                IS_WORKER_THREAD.with(|is_worker| is_worker.set(true));
+                thread_body();
+            })
+            .expect("Failed to spawn worker thread");
+        Self { name, body, handle }
+    }
+}
+
+// Named threads that each run a body until told to stop, restarted if they die early.
+pub struct WorkerThreads {
+    threads: Vec<WorkerThread>,
+}
+
+impl WorkerThreads {
+    pub fn new() -> Self {
+        silence_worker_panics();
+        Self {
+            threads: Vec::new(),
+        }
+    }
+
+    pub fn spawn(&mut self, name: String, body: impl Fn() + Send + Sync + 'static) {
+        self.threads.push(WorkerThread::start(name, Arc::new(body)));
+    }
+
+    pub fn len(&self) -> usize {
+        self.threads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.threads.is_empty()
+    }
+
+    // Jobs catch their own panics, so a worker only exits early if something outside a job
+    // went wrong. Replaces any such thread with a fresh one and returns how many were replaced.
+    pub fn respawn_dead(&mut self) -> usize {
+        let mut respawned = 0;
+        for worker in self.threads.iter_mut() {
+            if !worker.handle.is_finished() {
+                continue;
+            }
+            let replacement = WorkerThread::start(worker.name.clone(), Arc::clone(&worker.body));
+            let dead = std::mem::replace(worker, replacement);
+            match dead.handle.join() {
+                Err(e) => eprintln!(
+                    "WorkerPool: {} panicked, respawning: {}",
+                    dead.name,
+                    panic_message(&*e)
+                ),
+                Ok(()) => eprintln!("WorkerPool: {} exited unexpectedly, respawning", dead.name),
+            }
+            respawned += 1;
+        }
+        respawned
+    }
+
+    // Waits for every thread to finish; the caller must have told them to stop.
+    pub fn join(self) {
+        for worker in self.threads {
+            if let Err(e) = worker.handle.join() {
+                eprintln!(
+                    "WorkerPool: {} panicked: {}",
+                    worker.name,
+                    panic_message(&*e)
+                );
+            }
+        }
+    }
+}
+
+impl Default for WorkerThreads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
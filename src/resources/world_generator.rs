@@ -1,6 +1,7 @@
 use crate::components::{BlockType, ChunkCoord, ChunkData};
 use crate::resources::Config;
 use noise::{Fbm, MultiFractal, NoiseFn, Perlin, Simplex};
+use rayon::prelude::*;
 
 pub struct WorldGenerator {
     config: Config,
@@ -10,6 +11,8 @@ pub struct WorldGenerator {
     stone_variation_noise: Fbm<Simplex>,
     seabed_gravel_noise: Perlin,
     ice_patch_noise: Perlin,
+    river_noise: Fbm<Perlin>,
+    climate_noise: Perlin,
 }
 
 impl WorldGenerator {
@@ -43,6 +46,16 @@ impl WorldGenerator {
 
         let ice_patch_noise = Perlin::new(seed.wrapping_add(5));
 
+        let river_noise = Fbm::<Perlin>::new(seed.wrapping_add(6))
+            .set_frequency(config.river_freq)
+            .set_octaves(3)
+            .set_lacunarity(2.0)
+            .set_persistence(0.4);
+
+        // Fbm octaves take consecutive seeds, so skip past the river (6..8) and cloud (7..11)
+        // noise instead of sharing one of their octaves.
+        let climate_noise = Perlin::new(seed.wrapping_add(12));
+
         Self {
             config,
             base_height_noise,
@@ -51,128 +64,247 @@ impl WorldGenerator {
             stone_variation_noise,
             seabed_gravel_noise,
             ice_patch_noise,
+            river_noise,
+            climate_noise,
         }
     }
 
     pub fn generate_chunk_data(&self, chunk_coord: ChunkCoord) -> ChunkData {
         let mut chunk_data = ChunkData::new(&self.config);
         let ChunkCoord(cx, cy, cz) = chunk_coord;
+        let width = self.config.chunk_width;
 
-        let chunk_origin_x = cx * self.config.chunk_width as i32;
+        let chunk_origin_x = cx * width as i32;
         let chunk_origin_z = cz * self.config.chunk_depth as i32;
 
-        for local_x in 0..self.config.chunk_width {
-            for local_z in 0..self.config.chunk_depth {
-                let world_x = chunk_origin_x + local_x as i32;
-                let world_z = chunk_origin_z + local_z as i32;
-
-                let terrain_height = self.calculate_terrain_height(world_x, world_z);
-                let roughness_val = self.get_roughness_value(world_x, world_z);
-
-                let height_nx = self.calculate_terrain_height(world_x + 1, world_z);
-                let height_nz = self.calculate_terrain_height(world_x, world_z + 1);
-                let diff_x = (terrain_height - height_nx).abs();
-                let diff_z = (terrain_height - height_nz).abs();
-                let max_height_diff = diff_x.max(diff_z);
-
-                let stone_coords = [
-                    world_x as f64,
-                    world_z as f64,
-                    (cy * self.config.chunk_height as i32) as f64 * 0.1,
-                ];
-                let seabed_coords = [world_x as f64, world_z as f64];
-
-                for local_y in 0..self.config.chunk_height {
-                    let world_y = cy * self.config.chunk_height as i32 + local_y as i32;
-
-                    let block_type = if world_y > terrain_height {
-                        if world_y == terrain_height + 1 && terrain_height >= self.config.snow_level
-                        {
-                            let ice_coords = [
-                                world_x as f64 * self.config.ice_patch_freq,
-                                world_z as f64 * self.config.ice_patch_freq,
-                            ];
-                            let ice_noise_val = self.ice_patch_noise.get(ice_coords);
-                            if ice_noise_val > self.config.ice_patch_threshold {
-                                BlockType::Ice
-                            } else {
-                                BlockType::Air
-                            }
-                        } else if world_y <= self.config.sea_level {
-                            BlockType::Water
-                        } else {
-                            BlockType::Air
-                        }
+        let column_count = width * self.config.chunk_depth;
+        let column_origin = |index: usize| {
+            (
+                chunk_origin_x + (index % width) as i32,
+                chunk_origin_z + (index / width) as i32,
+            )
+        };
+        // Blocks are stored layer by layer, so a column is every `column_count`th entry.
+        let blocks: &mut [BlockType] = &mut chunk_data.blocks;
+        let mut write_column = |index: usize, column: &[BlockType]| {
+            for (local_y, block_type) in column.iter().enumerate() {
+                blocks[local_y * column_count + index] = *block_type;
+            }
+        };
+
+        if self.config.parallel_chunk_generation {
+            let columns: Vec<Vec<BlockType>> = (0..column_count)
+                .into_par_iter()
+                .map(|index| {
+                    let (world_x, world_z) = column_origin(index);
+                    let mut column = vec![BlockType::Air; self.config.chunk_height];
+                    self.generate_column(cy, world_x, world_z, &mut column);
+                    column
+                })
+                .collect();
+            for (index, column) in columns.iter().enumerate() {
+                write_column(index, column);
+            }
+        } else {
+            let mut column = vec![BlockType::Air; self.config.chunk_height];
+            for index in 0..column_count {
+                let (world_x, world_z) = column_origin(index);
+                self.generate_column(cy, world_x, world_z, &mut column);
+                write_column(index, &column);
+            }
+        }
+
+        chunk_data
+    }
+
+    // Fills `column` (one entry per block of the chunk's height) for a single world column.
+    fn generate_column(&self, cy: i32, world_x: i32, world_z: i32, column: &mut [BlockType]) {
+        let uncarved_height = self.calculate_uncarved_height(world_x, world_z);
+        let river_strength = self.get_river_strength(world_x, world_z);
+        let terrain_height = self.carve_river(uncarved_height, river_strength);
+        let is_river_bank = river_strength > 0.0
+            && terrain_height <= self.config.sea_level + self.config.river_bank_height;
+        let snow_line = self.snow_line(world_x, world_z);
+        let is_snow_covered = terrain_height >= snow_line;
+        let is_frozen_river = river_strength > 0.0 && self.rivers_freeze(world_x, world_z);
+        let roughness_val = self.get_roughness_value(world_x, world_z);
+
+        let height_nx = self.calculate_terrain_height(world_x + 1, world_z);
+        let height_nz = self.calculate_terrain_height(world_x, world_z + 1);
+        let diff_x = (terrain_height - height_nx).abs();
+        let diff_z = (terrain_height - height_nz).abs();
+        let max_height_diff = diff_x.max(diff_z);
+
+        let stone_coords = [
+            world_x as f64,
+            world_z as f64,
+            (cy * self.config.chunk_height as i32) as f64 * 0.1,
+        ];
+        let seabed_coords = [world_x as f64, world_z as f64];
+
+        for (local_y, block) in column.iter_mut().enumerate() {
+            let world_y = cy * self.config.chunk_height as i32 + local_y as i32;
+
+            let block_type = if world_y > terrain_height {
+                if world_y == terrain_height + 1 && is_snow_covered {
+                    let ice_coords = [
+                        world_x as f64 * self.config.ice_patch_freq,
+                        world_z as f64 * self.config.ice_patch_freq,
+                    ];
+                    let ice_noise_val = self.ice_patch_noise.get(ice_coords);
+                    if ice_noise_val > self.config.ice_patch_threshold {
+                        BlockType::Ice
                     } else {
-                        let is_surface = world_y == terrain_height;
-                        let is_dirt_layer = world_y > terrain_height - self.config.dirt_depth
-                            && world_y < terrain_height;
-
-                        let is_rough = roughness_val * self.config.roughness_amp
-                            > self.config.roughness_amp * self.config.exposed_stone_threshold;
-                        let is_steep = max_height_diff > self.config.dirt_depth;
-                        let should_expose_stone =
-                            (is_rough || is_steep) && world_y > self.config.sea_level + 1;
-
-                        if is_surface {
-                            if world_y >= self.config.snow_level {
-                                BlockType::Snow
-                            } else if should_expose_stone {
-                                BlockType::Stone
-                            } else if world_y > self.config.sea_level {
-                                BlockType::GrassyDirt
-                            } else {
-                                let scaled_seabed_coords = [
-                                    seabed_coords[0] * self.config.seabed_gravel_freq,
-                                    seabed_coords[1] * self.config.seabed_gravel_freq,
-                                ];
-                                let gravel_noise =
-                                    self.seabed_gravel_noise.get(scaled_seabed_coords);
-                                if gravel_noise > self.config.seabed_gravel_threshold {
-                                    BlockType::Gravel
-                                } else {
-                                    BlockType::Sand
-                                }
-                            }
-                        } else if is_dirt_layer {
-                            if world_y == terrain_height - 1
-                                && terrain_height >= self.config.snow_level
-                            {
-                                BlockType::SnowyDirt
-                            } else if should_expose_stone {
-                                BlockType::Stone
-                            } else {
-                                BlockType::Dirt
-                            }
+                        BlockType::Air
+                    }
+                } else if world_y == self.config.sea_level && is_frozen_river {
+                    BlockType::Ice
+                } else if world_y <= self.config.sea_level {
+                    BlockType::Water
+                } else {
+                    BlockType::Air
+                }
+            } else {
+                let is_surface = world_y == terrain_height;
+                let is_dirt_layer =
+                    world_y > terrain_height - self.config.dirt_depth && world_y < terrain_height;
+
+                let is_rough = roughness_val * self.config.roughness_amp
+                    > self.config.roughness_amp * self.config.exposed_stone_threshold;
+                let is_steep = max_height_diff > self.config.dirt_depth;
+                let should_expose_stone =
+                    (is_rough || is_steep) && world_y > self.config.sea_level + 1;
+
+                if is_surface {
+                    if is_snow_covered {
+                        BlockType::Snow
+                    } else if should_expose_stone && !is_river_bank {
+                        BlockType::Stone
+                    } else if world_y > self.config.sea_level && !is_river_bank {
+                        BlockType::GrassyDirt
+                    } else {
+                        let scaled_seabed_coords = [
+                            seabed_coords[0] * self.config.seabed_gravel_freq,
+                            seabed_coords[1] * self.config.seabed_gravel_freq,
+                        ];
+                        let gravel_noise = self.seabed_gravel_noise.get(scaled_seabed_coords);
+                        if gravel_noise > self.config.seabed_gravel_threshold {
+                            BlockType::Gravel
                         } else {
-                            let scaled_stone_coords = [
-                                stone_coords[0] * self.config.stone_variation_freq,
-                                stone_coords[1] * self.config.stone_variation_freq,
-                                stone_coords[2] * self.config.stone_variation_freq,
-                            ];
-                            let stone_noise_val =
-                                self.stone_variation_noise.get(scaled_stone_coords);
-                            if stone_noise_val > 0.3 {
-                                BlockType::Andesite
-                            } else if stone_noise_val > -0.1 {
-                                BlockType::Stone
-                            } else if stone_noise_val > -0.5 {
-                                BlockType::Granite
-                            } else {
-                                BlockType::Diorite
-                            }
+                            BlockType::Sand
                         }
-                    };
-
-                    chunk_data.set_block(&self.config, local_x, local_y, local_z, block_type);
+                    }
+                } else if is_dirt_layer {
+                    if world_y == terrain_height - 1 && is_snow_covered {
+                        BlockType::SnowyDirt
+                    } else if should_expose_stone {
+                        BlockType::Stone
+                    } else {
+                        BlockType::Dirt
+                    }
+                } else {
+                    let scaled_stone_coords = [
+                        stone_coords[0] * self.config.stone_variation_freq,
+                        stone_coords[1] * self.config.stone_variation_freq,
+                        stone_coords[2] * self.config.stone_variation_freq,
+                    ];
+                    let stone_noise_val = self.stone_variation_noise.get(scaled_stone_coords);
+                    if stone_noise_val > 0.3 {
+                        BlockType::Andesite
+                    } else if stone_noise_val > -0.1 {
+                        BlockType::Stone
+                    } else if stone_noise_val > -0.5 {
+                        BlockType::Granite
+                    } else {
+                        BlockType::Diorite
+                    }
                 }
-            }
+            };
+
+            *block = block_type;
         }
+    }
 
-        chunk_data
+    // Where snow settles and water freezes, by the same rules generation uses: anything above
+    // the snow line, and the surface of rivers in regions cold enough to freeze them. Oceans
+    // and lakes at sea level stay liquid.
+    pub fn is_freezing(&self, world_x: i32, world_y: i32, world_z: i32) -> bool {
+        world_y >= self.snow_line(world_x, world_z)
+            || (world_y == self.config.sea_level
+                && self.get_river_strength(world_x, world_z) > 0.0
+                && self.rivers_freeze(world_x, world_z))
     }
 
-    fn calculate_terrain_height(&self, world_x: i32, world_z: i32) -> i32 {
+    // Height from which ground is snow-covered. Cold regions pull it down from `snow_level`,
+    // but never onto shores and river banks.
+    pub fn snow_line(&self, world_x: i32, world_z: i32) -> i32 {
+        let lowest = self.config.sea_level + self.config.river_bank_height + 1;
+        self.unclamped_snow_line(world_x, world_z).max(lowest)
+    }
+
+    // Only in the coldest regions, where the snow line would reach the sea, do rivers freeze.
+    pub fn rivers_freeze(&self, world_x: i32, world_z: i32) -> bool {
+        self.unclamped_snow_line(world_x, world_z) <= self.config.sea_level
+    }
+
+    fn unclamped_snow_line(&self, world_x: i32, world_z: i32) -> i32 {
+        let climate = self.climate_noise.get([
+            world_x as f64 * self.config.climate_freq,
+            world_z as f64 * self.config.climate_freq,
+        ]);
+        let drop = (-climate).max(0.0) * self.config.cold_snow_line_drop;
+        (self.config.snow_level as f64 - drop).round() as i32
+    }
+
+    // The topmost visible block of a column and its height, without generating chunk data.
+    // Mirrors the surface rules in generate_chunk_data, minus ice patches and gravel.
+    pub fn surface_block(&self, world_x: i32, world_z: i32) -> (i32, BlockType) {
+        let uncarved_height = self.calculate_uncarved_height(world_x, world_z);
+        let river_strength = self.get_river_strength(world_x, world_z);
+        let terrain_height = self.carve_river(uncarved_height, river_strength);
+        let sea_level = self.config.sea_level;
+        let snow_line = self.snow_line(world_x, world_z);
+
+        if terrain_height < sea_level {
+            let is_frozen_river = river_strength > 0.0 && self.rivers_freeze(world_x, world_z);
+            let block = if is_frozen_river {
+                BlockType::Ice
+            } else {
+                BlockType::Water
+            };
+            return (sea_level, block);
+        }
+
+        let is_river_bank =
+            river_strength > 0.0 && terrain_height <= sea_level + self.config.river_bank_height;
+        let max_height_diff = (terrain_height
+            - self.calculate_terrain_height(world_x + 1, world_z))
+        .abs()
+        .max((terrain_height - self.calculate_terrain_height(world_x, world_z + 1)).abs());
+        let is_rough =
+            self.get_roughness_value(world_x, world_z) > self.config.exposed_stone_threshold;
+        let is_steep = max_height_diff > self.config.dirt_depth;
+        let should_expose_stone = (is_rough || is_steep) && terrain_height > sea_level + 1;
+
+        let block = if terrain_height >= snow_line {
+            BlockType::Snow
+        } else if should_expose_stone && !is_river_bank {
+            BlockType::Stone
+        } else if terrain_height > sea_level && !is_river_bank {
+            BlockType::GrassyDirt
+        } else {
+            BlockType::Sand
+        };
+        (terrain_height, block)
+    }
+
+    pub fn calculate_terrain_height(&self, world_x: i32, world_z: i32) -> i32 {
+        let uncarved_height = self.calculate_uncarved_height(world_x, world_z);
+        let river_strength = self.get_river_strength(world_x, world_z);
+        self.carve_river(uncarved_height, river_strength)
+    }
+
+    fn calculate_uncarved_height(&self, world_x: i32, world_z: i32) -> f64 {
         let coords = [world_x as f64, world_z as f64];
 
         let base_noise_val = self.base_height_noise.get(coords);
@@ -187,9 +319,31 @@ impl WorldGenerator {
         let roughness_noise_val = self.roughness_noise.get(coords);
         let roughness_h = roughness_noise_val * self.config.roughness_amp;
 
-        let final_height = base_h + mountain_h + roughness_h;
+        base_h + mountain_h + roughness_h
+    }
+
+    fn get_river_strength(&self, world_x: i32, world_z: i32) -> f64 {
+        let river_noise_val = self.river_noise.get([world_x as f64, world_z as f64]);
+        let ridge = 1.0 - river_noise_val.abs();
+        let t =
+            ((ridge - (1.0 - self.config.river_width)) / self.config.river_width).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    fn carve_river(&self, uncarved_height: f64, river_strength: f64) -> i32 {
+        let river_bed = (self.config.sea_level - self.config.river_depth) as f64;
+        let mut height = uncarved_height;
+
+        if river_strength > 0.0 && height > river_bed {
+            let elevation = height - self.config.sea_level as f64;
+            let carve_cutoff = (elevation / self.config.river_max_carve).clamp(0.0, 1.0);
+            if river_strength > carve_cutoff && carve_cutoff < 1.0 {
+                let carve_factor = (river_strength - carve_cutoff) / (1.0 - carve_cutoff);
+                height += (river_bed - height) * carve_factor;
+            }
+        }
 
-        final_height.round().clamp(1.0, 255.0) as i32
+        height.round().clamp(1.0, 255.0) as i32
     }
 
     fn get_roughness_value(&self, world_x: i32, world_z: i32) -> f64 {
@@ -1,11 +1,21 @@
 use crate::input::InputManager;
+use crate::resources::FrameBudget;
 use crate::state::GameState;
-use crate::systems::{ChunkLoadingSystem, ChunkMeshingSystem, InputSystem, RenderSystem};
+use crate::systems::{
+    ChunkLoadingSystem, ChunkMeshingSystem, FallingBlockSystem, FarTerrainSystem, FluidSystem,
+    InputSystem, RandomTickSystem, RenderSystem, WeatherSystem, WorkerStatsSystem,
+};
 
 pub struct SystemScheduler {
     input_system: InputSystem,
     chunk_loading_system: ChunkLoadingSystem,
     chunk_meshing_system: ChunkMeshingSystem,
+    fluid_system: FluidSystem,
+    falling_block_system: FallingBlockSystem,
+    random_tick_system: RandomTickSystem,
+    weather_system: WeatherSystem,
+    far_terrain_system: FarTerrainSystem,
+    worker_stats_system: WorkerStatsSystem,
     render_system: RenderSystem,
 }
 
@@ -15,6 +25,12 @@ impl SystemScheduler {
             input_system: InputSystem::new(),
             chunk_loading_system: ChunkLoadingSystem::new(),
             chunk_meshing_system: ChunkMeshingSystem::new(),
+            fluid_system: FluidSystem::new(),
+            falling_block_system: FallingBlockSystem::new(),
+            random_tick_system: RandomTickSystem::new(),
+            weather_system: WeatherSystem::new(),
+            far_terrain_system: FarTerrainSystem::new(),
+            worker_stats_system: WorkerStatsSystem::new(),
             render_system: RenderSystem::new(),
         }
     }
@@ -32,10 +48,29 @@ impl SystemScheduler {
     pub fn process_updates_and_requests(&mut self, game_state: &mut GameState) {
         self.chunk_loading_system.update(game_state);
 
+        self.random_tick_system.update(game_state);
+
+        self.fluid_system.update(game_state);
+
+        self.falling_block_system.update(game_state);
+
+        self.weather_system.update(game_state);
+
+        // Chunk meshes and far terrain tiles share one frame's GPU uploads, chunks first.
+        let mut upload_budget = FrameBudget::new(
+            game_state.config.upload_budget_bytes_per_frame,
+            game_state.config.upload_budget_ms_per_frame,
+        );
+
         self.chunk_meshing_system.update_lod_parameters(game_state);
 
         self.chunk_meshing_system
-            .process_mesh_results_and_requests(game_state);
+            .process_mesh_results_and_requests(game_state, &mut upload_budget);
+
+        self.far_terrain_system
+            .update(game_state, &mut upload_budget);
+
+        self.worker_stats_system.update(game_state);
     }
 
     pub fn render(&self, game_state: &mut GameState) {
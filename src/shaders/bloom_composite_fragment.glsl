@@ -0,0 +1,15 @@
+#version 410 core
+
+in vec2 TexCoord;
+
+out vec4 FragColor;
+
+uniform sampler2D sceneTexture;
+uniform sampler2D bloomTexture;
+uniform float bloomIntensity;
+
+void main() {
+    vec3 scene = texture(sceneTexture, TexCoord).rgb;
+    vec3 bloom = texture(bloomTexture, TexCoord).rgb;
+    FragColor = vec4(scene + bloom * bloomIntensity, 1.0);
+}
@@ -0,0 +1,31 @@
+#version 410 core
+
+in vec2 TexCoord;
+
+out vec4 FragColor;
+
+uniform sampler2D sourceTexture;
+
+// 13-tap downsample: a wide box filter that avoids the flicker of a plain 2x2 average.
+void main() {
+    vec2 texelSize = 1.0 / vec2(textureSize(sourceTexture, 0));
+    vec3 a = texture(sourceTexture, TexCoord + texelSize * vec2(-2.0, 2.0)).rgb;
+    vec3 b = texture(sourceTexture, TexCoord + texelSize * vec2(0.0, 2.0)).rgb;
+    vec3 c = texture(sourceTexture, TexCoord + texelSize * vec2(2.0, 2.0)).rgb;
+    vec3 d = texture(sourceTexture, TexCoord + texelSize * vec2(-2.0, 0.0)).rgb;
+    vec3 e = texture(sourceTexture, TexCoord).rgb;
+    vec3 f = texture(sourceTexture, TexCoord + texelSize * vec2(2.0, 0.0)).rgb;
+    vec3 g = texture(sourceTexture, TexCoord + texelSize * vec2(-2.0, -2.0)).rgb;
+    vec3 h = texture(sourceTexture, TexCoord + texelSize * vec2(0.0, -2.0)).rgb;
+    vec3 i = texture(sourceTexture, TexCoord + texelSize * vec2(2.0, -2.0)).rgb;
+    vec3 j = texture(sourceTexture, TexCoord + texelSize * vec2(-1.0, 1.0)).rgb;
+    vec3 k = texture(sourceTexture, TexCoord + texelSize * vec2(1.0, 1.0)).rgb;
+    vec3 l = texture(sourceTexture, TexCoord + texelSize * vec2(-1.0, -1.0)).rgb;
+    vec3 m = texture(sourceTexture, TexCoord + texelSize * vec2(1.0, -1.0)).rgb;
+
+    vec3 color = e * 0.125;
+    color += (a + c + g + i) * 0.03125;
+    color += (b + d + f + h) * 0.0625;
+    color += (j + k + l + m) * 0.125;
+    FragColor = vec4(color, 1.0);
+}
@@ -0,0 +1,28 @@
+#version 410 core
+
+in vec2 TexCoord;
+
+out vec4 FragColor;
+
+uniform sampler2D sourceTexture;
+uniform float threshold;
+uniform float softKnee;
+
+// Keeps only the part of each pixel brighter than the threshold, with a soft knee so the
+// cutoff does not band.
+void main() {
+    vec2 texelSize = 1.0 / vec2(textureSize(sourceTexture, 0));
+    vec3 color = texture(sourceTexture, TexCoord + texelSize * vec2(-0.5, -0.5)).rgb;
+    color += texture(sourceTexture, TexCoord + texelSize * vec2(0.5, -0.5)).rgb;
+    color += texture(sourceTexture, TexCoord + texelSize * vec2(-0.5, 0.5)).rgb;
+    color += texture(sourceTexture, TexCoord + texelSize * vec2(0.5, 0.5)).rgb;
+    color *= 0.25;
+
+    float brightness = max(color.r, max(color.g, color.b));
+    float knee = threshold * softKnee + 1e-5;
+    float soft = clamp(brightness - threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee);
+    float contribution = max(soft, brightness - threshold) / max(brightness, 1e-5);
+
+    FragColor = vec4(color * contribution, 1.0);
+}
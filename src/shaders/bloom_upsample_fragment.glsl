@@ -0,0 +1,23 @@
+#version 410 core
+
+in vec2 TexCoord;
+
+out vec4 FragColor;
+
+uniform sampler2D sourceTexture;
+uniform float filterRadius;
+
+// 3x3 tent filter; the result is added onto the next larger mip with additive blending.
+void main() {
+    vec2 offset = filterRadius / vec2(textureSize(sourceTexture, 0));
+    vec3 color = texture(sourceTexture, TexCoord).rgb * 4.0;
+    color += texture(sourceTexture, TexCoord + vec2(-offset.x, 0.0)).rgb * 2.0;
+    color += texture(sourceTexture, TexCoord + vec2(offset.x, 0.0)).rgb * 2.0;
+    color += texture(sourceTexture, TexCoord + vec2(0.0, -offset.y)).rgb * 2.0;
+    color += texture(sourceTexture, TexCoord + vec2(0.0, offset.y)).rgb * 2.0;
+    color += texture(sourceTexture, TexCoord + vec2(-offset.x, -offset.y)).rgb;
+    color += texture(sourceTexture, TexCoord + vec2(offset.x, -offset.y)).rgb;
+    color += texture(sourceTexture, TexCoord + vec2(-offset.x, offset.y)).rgb;
+    color += texture(sourceTexture, TexCoord + vec2(offset.x, offset.y)).rgb;
+    FragColor = vec4(color / 16.0, 1.0);
+}
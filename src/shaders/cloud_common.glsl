@@ -0,0 +1,13 @@
+#version 410 core
+
+uniform sampler2D cloudTexture;
+uniform vec2 cloudOffset;
+uniform float cloudScale;
+uniform float cloudCoverage;
+
+// Coverage moves the threshold on the tileable noise map; higher coverage, more sky covered.
+float cloudDensity(vec2 worldXZ) {
+    float noise = texture(cloudTexture, (worldXZ + cloudOffset) / cloudScale).r;
+    float threshold = 1.0 - cloudCoverage;
+    return smoothstep(threshold, threshold + 0.2, noise);
+}
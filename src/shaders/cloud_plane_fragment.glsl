@@ -0,0 +1,28 @@
+// Appended to cloud_common.glsl.
+
+in vec3 WorldPos;
+
+out vec4 FragColor;
+
+uniform vec2 cloudCenter;
+uniform float cloudExtent;
+uniform vec3 lightDirection;
+uniform vec3 lightColor;
+uniform vec3 ambientColor;
+
+void main() {
+    float density = cloudDensity(WorldPos.xz);
+
+    // Denser parts of the layer are darker underneath, as if thicker.
+    float sunHeight = max(normalize(lightDirection).y, 0.0);
+    float selfShadow = mix(1.0, 0.55, density);
+    vec3 color = ambientColor + lightColor * selfShadow * (0.4 + 0.6 * sunHeight);
+
+    float edgeDistance = length(WorldPos.xz - cloudCenter);
+    float edgeFade = 1.0 - smoothstep(cloudExtent * 0.6, cloudExtent * 0.95, edgeDistance);
+    float alpha = density * 0.9 * edgeFade;
+    if (alpha < 0.01)
+        discard;
+
+    FragColor = vec4(color, alpha);
+}
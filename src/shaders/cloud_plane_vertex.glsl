@@ -0,0 +1,17 @@
+#version 410 core
+
+layout(location = 0) in vec2 planePosition;
+
+out vec3 WorldPos;
+
+uniform mat4 viewMatrix;
+uniform mat4 projectionMatrix;
+uniform vec2 cloudCenter;
+uniform float cloudExtent;
+uniform float cloudAltitude;
+
+void main() {
+    WorldPos = vec3(cloudCenter.x + planePosition.x * cloudExtent, cloudAltitude,
+                    cloudCenter.y + planePosition.y * cloudExtent);
+    gl_Position = projectionMatrix * viewMatrix * vec4(WorldPos, 1.0);
+}
@@ -0,0 +1,96 @@
+// Appended to cloud_common.glsl. Uses sky_vertex.glsl for the full-screen ray.
+
+in vec3 ViewRay;
+
+out vec4 FragColor;
+
+uniform sampler2D sceneDepth;
+uniform vec3 cameraPosition;
+uniform vec3 cameraForward;
+uniform float zNear;
+uniform float zFar;
+uniform float cloudAltitude;
+uniform float cloudThickness;
+uniform int raymarchSteps;
+uniform vec3 lightDirection;
+uniform vec3 lightColor;
+uniform vec3 ambientColor;
+
+const float EXTINCTION = 0.08;
+const int LIGHT_STEPS = 4;
+
+float linearizeDepth(float depth) {
+    float ndcDepth = depth * 2.0 - 1.0;
+    return zNear * zFar / (zFar - ndcDepth * (zFar - zNear));
+}
+
+float layerDensity(vec3 position) {
+    float height = (position.y - cloudAltitude) / cloudThickness;
+    if (height <= 0.0 || height >= 1.0)
+        return 0.0;
+    float profile = 4.0 * height * (1.0 - height);
+    return cloudDensity(position.xz) * profile;
+}
+
+void main() {
+    vec3 rayDir = normalize(ViewRay);
+    float cloudTop = cloudAltitude + cloudThickness;
+
+    float tEnter;
+    float tExit;
+    if (abs(rayDir.y) < 1e-4) {
+        if (cameraPosition.y < cloudAltitude || cameraPosition.y > cloudTop)
+            discard;
+        tEnter = 0.0;
+        tExit = zFar;
+    } else {
+        float t0 = (cloudAltitude - cameraPosition.y) / rayDir.y;
+        float t1 = (cloudTop - cameraPosition.y) / rayDir.y;
+        tEnter = max(min(t0, t1), 0.0);
+        tExit = max(t0, t1);
+    }
+
+    // Stop at opaque geometry; the depth buffer stores distance along the view axis.
+    vec2 screenUV = gl_FragCoord.xy / vec2(textureSize(sceneDepth, 0));
+    float depth = texture(sceneDepth, screenUV).r;
+    if (depth < 1.0) {
+        float sceneDistance = linearizeDepth(depth) / max(dot(rayDir, cameraForward), 1e-4);
+        tExit = min(tExit, sceneDistance);
+    }
+    tExit = min(tExit, zFar);
+    if (tExit <= tEnter)
+        discard;
+
+    vec3 lightDir = normalize(lightDirection);
+    float lightStep = cloudThickness / float(LIGHT_STEPS) / max(lightDir.y, 0.2);
+    float stepLength = (tExit - tEnter) / float(raymarchSteps);
+
+    float transmittance = 1.0;
+    vec3 scattered = vec3(0.0);
+    for (int i = 0; i < raymarchSteps; ++i) {
+        vec3 position = cameraPosition + rayDir * (tEnter + (float(i) + 0.5) * stepLength);
+        float density = layerDensity(position);
+        if (density <= 0.01)
+            continue;
+
+        float lightDepth = 0.0;
+        for (int j = 0; j < LIGHT_STEPS; ++j) {
+            lightDepth += layerDensity(position + lightDir * (float(j) + 0.5) * lightStep);
+        }
+        float lightTransmittance = exp(-lightDepth * lightStep * EXTINCTION);
+
+        float sampleTransmittance = exp(-density * stepLength * EXTINCTION);
+        vec3 sampleLight = ambientColor + lightColor * lightTransmittance;
+        scattered += transmittance * (1.0 - sampleTransmittance) * sampleLight;
+        transmittance *= sampleTransmittance;
+        if (transmittance < 0.01)
+            break;
+    }
+
+    float distanceFade = 1.0 - smoothstep(zFar * 0.6, zFar, tEnter);
+    float alpha = (1.0 - transmittance) * distanceFade;
+    if (alpha < 0.01)
+        discard;
+
+    FragColor = vec4(scattered / max(1.0 - transmittance, 1e-4), alpha);
+}
@@ -0,0 +1,8 @@
+// Appended to cloud_common.glsl.
+
+in vec2 WorldXZ;
+
+void main() {
+    if (cloudDensity(WorldXZ) < 0.5)
+        discard;
+}
@@ -0,0 +1,17 @@
+#version 410 core
+
+layout(location = 0) in vec2 planePosition;
+
+out vec2 WorldXZ;
+
+uniform mat4 lightSpaceMatrix;
+uniform vec2 cloudCenter;
+uniform float cloudExtent;
+uniform float cloudAltitude;
+
+void main() {
+    vec3 worldPos = vec3(cloudCenter.x + planePosition.x * cloudExtent, cloudAltitude,
+                         cloudCenter.y + planePosition.y * cloudExtent);
+    WorldXZ = worldPos.xz;
+    gl_Position = lightSpaceMatrix * vec4(worldPos, 1.0);
+}
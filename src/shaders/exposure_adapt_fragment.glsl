@@ -0,0 +1,22 @@
+#version 410 core
+
+out vec4 FragColor;
+
+uniform sampler2D luminanceTexture;
+uniform sampler2D previousExposure;
+uniform float deltaTime;
+uniform float adaptationSpeed;
+uniform float exposureKey;
+uniform float minExposure;
+uniform float maxExposure;
+
+void main() {
+    float topLevel = floor(log2(float(textureSize(luminanceTexture, 0).x)));
+    float averageLuminance = exp(textureLod(luminanceTexture, vec2(0.5), topLevel).r);
+    float target = clamp(exposureKey / max(averageLuminance, 1e-4), minExposure, maxExposure);
+
+    float current = texelFetch(previousExposure, ivec2(0), 0).r;
+    // Exponential approach, so the adaptation speed does not depend on the frame rate.
+    float blend = 1.0 - exp(-deltaTime * adaptationSpeed);
+    FragColor = vec4(current + (target - current) * blend, 0.0, 0.0, 1.0);
+}
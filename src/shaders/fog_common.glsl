@@ -0,0 +1,40 @@
+#version 410 core
+
+uniform vec3 cameraPosition;
+uniform bool fogEnabled;
+uniform float fogStart;
+uniform float fogEnd;
+uniform vec3 fogColor;
+uniform vec3 fogSunColor;
+uniform vec3 sunDirection;
+uniform float heightFogDensity;
+uniform float heightFogFalloff;
+uniform float fogBaseHeight;
+
+// Distance fog fading into the horizon sky, plus exponential height fog integrated along
+// the view ray.
+vec3 applyFog(vec3 color, vec3 worldPos) {
+    if (!fogEnabled)
+        return color;
+
+    vec3 toFragment = worldPos - cameraPosition;
+    float fragmentDistance = length(toFragment);
+    float distanceFog = smoothstep(fogStart, fogEnd, fragmentDistance);
+
+    float heightDelta = toFragment.y;
+    float heightFalloff = heightFogFalloff * heightDelta;
+    float heightIntegral = abs(heightFalloff) > 1e-3
+        ? (1.0 - exp(-heightFalloff)) / heightFalloff
+        : 1.0;
+    float heightFog = heightFogDensity * fragmentDistance
+        * exp(-heightFogFalloff * (cameraPosition.y - fogBaseHeight)) * heightIntegral;
+
+    float fogAmount = 1.0 - (1.0 - distanceFog) * exp(-max(heightFog, 0.0));
+
+    vec2 horizontalDir = normalize(toFragment.xz + vec2(1e-5));
+    vec2 sunHorizontal = normalize(sunDirection.xz + vec2(1e-5));
+    float sunAlignment = pow(max(dot(horizontalDir, sunHorizontal), 0.0), 4.0);
+    vec3 horizonColor = mix(fogColor, fogSunColor, sunAlignment);
+
+    return mix(color, horizonColor, clamp(fogAmount, 0.0, 1.0));
+}
@@ -1,10 +1,10 @@
-#version 410 core
+// Appended to fog_common.glsl.
 
 in vec2 TexCoord;
 in float LayerIndex;
 in vec3 WorldNormal;
 in vec3 WorldPos;
-in vec4 FragPosLightSpace;
+in float ViewDepth;
 
 out vec4 FragColor;
 
@@ -15,38 +15,93 @@ uniform vec3 lightColor;
 uniform float minAmbientContribution;
 uniform bool isCelestial;
 uniform float celestialLayerIndex;
-uniform vec3 cameraPosition;
+uniform float celestialIntensity;
+uniform bool isFarTerrain;
+uniform vec3 cameraChunk;
+uniform vec3 chunkSize;
+uniform float chunkRenderDistance;
+uniform float lodVerticalWeight;
 uniform float shininess;
-uniform sampler2D shadowMap;
-
-float calculateShadow(vec3 norm, vec3 lightDir, vec4 fragPosLightSpace) {
+uniform sampler2DArray shadowMap;
+
+#define MAX_CASCADES 4
+uniform mat4 lightSpaceMatrices[MAX_CASCADES];
+uniform float cascadeSplits[MAX_CASCADES];
+uniform float cascadeBias[MAX_CASCADES];
+uniform int cascadePcfRadius[MAX_CASCADES];
+uniform int cascadeCount;
+uniform float cascadeBlendFraction;
+
+float sampleCascade(int cascade, vec3 worldPos) {
+    vec4 fragPosLightSpace = lightSpaceMatrices[cascade] * vec4(worldPos, 1.0);
     vec3 projCoords = fragPosLightSpace.xyz / fragPosLightSpace.w;
 
     projCoords = projCoords * 0.5 + 0.5;
 
-    float closestDepth = texture(shadowMap, projCoords.xy).r;
+    if (projCoords.z > 1.0)
+        return 1.0;
 
     float currentDepth = projCoords.z;
 
-    float bias = 0.009;
+    float bias = cascadeBias[cascade];
 
+    int radius = cascadePcfRadius[cascade];
     float shadow = 0.0;
-    vec2 texelSize = 1.0 / textureSize(shadowMap, 0);
-    for (int x = -1; x <= 1; ++x) {
-        for (int y = -1; y <= 1; ++y) {
-            float pcfDepth = texture(shadowMap, projCoords.xy + vec2(x, y) * texelSize).r;
+    vec2 texelSize = 1.0 / vec2(textureSize(shadowMap, 0).xy);
+    for (int x = -radius; x <= radius; ++x) {
+        for (int y = -radius; y <= radius; ++y) {
+            vec2 offset = vec2(x, y) * texelSize;
+            float pcfDepth = texture(shadowMap, vec3(projCoords.xy + offset, cascade)).r;
             shadow += currentDepth - bias > pcfDepth ? 1.0 : 0.0;
         }
     }
-    shadow /= 9.0;
-
-    if (projCoords.z > 1.0)
-        shadow = 0.0;
+    float kernelWidth = float(radius * 2 + 1);
+    shadow /= kernelWidth * kernelWidth;
 
     return 1.0 - shadow;
 }
 
+float calculateShadow(vec3 worldPos, float viewDepth) {
+    int cascade = cascadeCount;
+    for (int i = 0; i < cascadeCount; ++i) {
+        if (viewDepth < cascadeSplits[i]) {
+            cascade = i;
+            break;
+        }
+    }
+    if (cascade >= cascadeCount)
+        return 1.0;
+
+    float shadow = sampleCascade(cascade, worldPos);
+
+    // Fade into the next cascade near the split so the seam does not show.
+    if (cascade + 1 < cascadeCount) {
+        float sliceStart = cascade == 0 ? 0.0 : cascadeSplits[cascade - 1];
+        float sliceEnd = cascadeSplits[cascade];
+        float blendStart = sliceEnd - (sliceEnd - sliceStart) * cascadeBlendFraction;
+        if (viewDepth > blendStart) {
+            float t = (viewDepth - blendStart) / (sliceEnd - blendStart);
+            shadow = mix(shadow, sampleCascade(cascade + 1, worldPos), t);
+        }
+    }
+
+    return shadow;
+}
+
+// Whether the ground block under a far terrain fragment lies in a chunk within loading range,
+// using the same weighted distance as the chunk loader.
+bool inLoadedChunk(vec3 worldPos) {
+    vec3 block = floor(worldPos + vec3(0.5, 0.0, 0.5));
+    vec3 offset = floor(block / chunkSize) - cameraChunk;
+    offset.y *= lodVerticalWeight;
+    return dot(offset, offset) <= chunkRenderDistance * chunkRenderDistance;
+}
+
 void main() {
+    if (isFarTerrain && inLoadedChunk(WorldPos)) {
+        discard;
+    }
+
     vec4 texColor;
     vec3 finalColor;
 
@@ -60,7 +115,7 @@ void main() {
 
     if (isCelestial) {
         texColor = texture(blockTexture, vec3(TexCoord, celestialLayerIndex));
-        finalColor = texColor.rgb + specular;
+        finalColor = texColor.rgb * celestialIntensity + specular;
     } else {
         texColor = texture(blockTexture, vec3(TexCoord, LayerIndex));
 
@@ -69,11 +124,11 @@ void main() {
 
         vec3 finalAmbient = max(ambientColor, vec3(minAmbientContribution));
 
-        float shadow = calculateShadow(norm, lightDir, FragPosLightSpace); // Re-enabled call
+        float shadow = calculateShadow(WorldPos, ViewDepth);
 
         vec3 lighting = finalAmbient + (diffuse + specular) * shadow;
 
-        finalColor = texColor.rgb * lighting;
+        finalColor = applyFog(texColor.rgb * lighting, WorldPos);
     }
 
     FragColor = vec4(finalColor, texColor.a);
@@ -0,0 +1,14 @@
+#version 410 core
+
+in vec2 TexCoord;
+
+out vec4 FragColor;
+
+uniform sampler2D sourceTexture;
+
+// Log luminance, so the mip chain's average is a geometric mean of the scene brightness.
+void main() {
+    vec3 color = texture(sourceTexture, TexCoord).rgb;
+    float luminance = dot(color, vec3(0.2126, 0.7152, 0.0722));
+    FragColor = vec4(log(max(luminance, 1e-4)), 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,10 @@
+#version 410 core
+
+out vec2 TexCoord;
+
+// Full-screen triangle generated from the vertex index; no vertex buffer is bound.
+void main() {
+    vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2)) * 2.0 - 1.0;
+    TexCoord = position * 0.5 + 0.5;
+    gl_Position = vec4(position, 0.0, 1.0);
+}
@@ -0,0 +1,25 @@
+#version 410 core
+
+in float Alpha;
+
+out vec4 FragColor;
+
+uniform bool isSnow;
+uniform vec3 ambientColor;
+uniform vec3 lightColor;
+
+void main() {
+    float alpha = Alpha;
+    vec3 color;
+    if (isSnow) {
+        vec2 fromCenter = gl_PointCoord - vec2(0.5);
+        if (dot(fromCenter, fromCenter) > 0.25)
+            discard;
+        color = vec3(1.0);
+        alpha *= 0.9;
+    } else {
+        color = vec3(0.7, 0.75, 0.85);
+        alpha *= 0.35;
+    }
+    FragColor = vec4(color * (ambientColor + lightColor * 0.5), alpha);
+}
@@ -0,0 +1,76 @@
+#version 410 core
+
+out float Alpha;
+
+uniform mat4 viewMatrix;
+uniform mat4 projectionMatrix;
+uniform vec3 cameraPosition;
+uniform float time;
+uniform bool isSnow;
+uniform float intensity;
+uniform float areaRadius;
+uniform float areaHeight;
+uniform float fallSpeed;
+uniform float streakLength;
+uniform sampler2D heightmap;
+uniform vec2 heightmapOrigin;
+
+vec4 hash4(uint n) {
+    uvec4 v = uvec4(n) * uvec4(1597334677u, 3812015801u, 2798796415u, 1979697957u);
+    v = (v.yzwx ^ (v >> 16u)) * uvec4(2654435769u);
+    v = (v ^ (v >> 13u)) * uvec4(1274126177u);
+    return vec4(v ^ (v >> 16u)) / 4294967295.0;
+}
+
+void hide() {
+    gl_Position = vec4(2.0, 2.0, 2.0, 1.0);
+    Alpha = 0.0;
+}
+
+void main() {
+    int verticesPerParticle = isSnow ? 1 : 2;
+    int particle = gl_VertexID / verticesPerParticle;
+    int end = gl_VertexID - particle * verticesPerParticle;
+    vec4 seed = hash4(uint(particle));
+
+    if (seed.w > intensity) {
+        hide();
+        return;
+    }
+
+    // Particles live in a world-anchored box that wraps around the camera, so walking
+    // through the weather does not drag it along.
+    vec3 boxSize = vec3(areaRadius * 2.0, areaHeight, areaRadius * 2.0);
+    vec3 boxMin = cameraPosition - vec3(areaRadius, areaHeight * 0.4, areaRadius);
+    vec3 velocity = vec3(0.0, -fallSpeed, 0.0);
+    if (isSnow) {
+        velocity.xz = vec2(sin(time * 0.7 + seed.x * 40.0), cos(time * 0.5 + seed.z * 40.0)) * 0.6;
+    }
+    vec3 position = seed.xyz * boxSize + velocity * time;
+    position = boxMin + mod(position - boxMin, boxSize);
+
+    vec2 texel = floor(position.xz) - heightmapOrigin;
+    ivec2 heightmapSize = textureSize(heightmap, 0);
+    if (any(lessThan(texel, vec2(0.0))) || any(greaterThanEqual(texel, vec2(heightmapSize)))) {
+        hide();
+        return;
+    }
+    // Ground height and whether the column is under snow rather than rain.
+    vec2 column = texelFetch(heightmap, ivec2(texel), 0).rg;
+    float ground = column.r;
+    bool columnSnows = column.g > 0.5;
+    if (position.y < ground + 1.0 || columnSnows != isSnow) {
+        hide();
+        return;
+    }
+
+    if (end == 1) {
+        position -= normalize(velocity) * streakLength;
+    }
+
+    gl_Position = projectionMatrix * viewMatrix * vec4(position, 1.0);
+    gl_PointSize = 3.0;
+
+    float distanceFade = 1.0 - smoothstep(areaRadius * 0.6, areaRadius, length(position.xz - cameraPosition.xz));
+    Alpha = distanceFade;
+}
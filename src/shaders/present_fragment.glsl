@@ -0,0 +1,11 @@
+#version 410 core
+
+in vec2 TexCoord;
+
+out vec4 FragColor;
+
+uniform sampler2D sourceTexture;
+
+void main() {
+    FragColor = vec4(texture(sourceTexture, TexCoord).rgb, 1.0);
+}
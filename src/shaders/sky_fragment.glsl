@@ -0,0 +1,58 @@
+#version 410 core
+
+in vec3 ViewRay;
+
+out vec4 FragColor;
+
+uniform vec3 sunDirection;
+uniform float sunIntensity;
+uniform float mieAnisotropy;
+uniform vec3 nightColor;
+uniform float weatherDarkening;
+uniform float lightningFlash;
+
+#define PI 3.14159265
+// Keep in sync with resources/sky.rs.
+const vec3 RAYLEIGH_COEFFICIENTS = vec3(5.8e-6, 13.5e-6, 33.1e-6);
+const float MIE_COEFFICIENT = 21e-6;
+const float RAYLEIGH_SCALE_HEIGHT = 8000.0;
+const float MIE_SCALE_HEIGHT = 1200.0;
+const float SCATTERING_ALTITUDE_DEPTH = 0.37;
+
+float airMass(vec3 direction) {
+    float cosZenith = max(direction.y, 0.0);
+    float zenithDegrees = degrees(acos(cosZenith));
+    return 1.0 / (cosZenith + 0.50572 * pow(96.07995 - zenithDegrees, -1.6364));
+}
+
+float rayleighPhase(float cosTheta) {
+    return 3.0 / (16.0 * PI) * (1.0 + cosTheta * cosTheta);
+}
+
+float henyeyGreensteinPhase(float cosTheta, float g) {
+    float g2 = g * g;
+    return (1.0 - g2) / (4.0 * PI * pow(1.0 + g2 - 2.0 * g * cosTheta, 1.5));
+}
+
+void main() {
+    vec3 viewDir = normalize(ViewRay);
+    vec3 sunDir = normalize(sunDirection);
+    float cosTheta = dot(viewDir, sunDir);
+
+    vec3 rayleighDepth = RAYLEIGH_COEFFICIENTS * RAYLEIGH_SCALE_HEIGHT;
+    vec3 mieDepth = vec3(MIE_COEFFICIENT * MIE_SCALE_HEIGHT);
+    vec3 opticalDepth = rayleighDepth + mieDepth;
+
+    vec3 sunTransmittance = exp(-opticalDepth * airMass(sunDir) * SCATTERING_ALTITUDE_DEPTH);
+    vec3 viewExtinction = vec3(1.0) - exp(-opticalDepth * airMass(viewDir));
+
+    vec3 scattering = rayleighDepth * rayleighPhase(cosTheta)
+        + mieDepth * henyeyGreensteinPhase(cosTheta, mieAnisotropy);
+    float daylight = smoothstep(-0.1, 0.05, sunDir.y);
+
+    vec3 color = nightColor
+        + scattering / opticalDepth * viewExtinction * sunTransmittance * sunIntensity * daylight;
+    vec3 grey = vec3((color.r + color.g + color.b) / 3.0);
+    color = mix(color, grey, weatherDarkening) * (1.0 - weatherDarkening) + vec3(lightningFlash);
+    FragColor = vec4(color, 1.0);
+}
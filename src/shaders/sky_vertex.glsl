@@ -0,0 +1,14 @@
+#version 410 core
+
+out vec3 ViewRay;
+
+uniform mat4 inverseViewProjection;
+
+// Full-screen triangle generated from the vertex index; no vertex buffer is bound.
+void main() {
+    vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2)) * 2.0 - 1.0;
+    gl_Position = vec4(position, 1.0, 1.0);
+
+    vec4 farPoint = inverseViewProjection * vec4(position, 1.0, 1.0);
+    ViewRay = farPoint.xyz / farPoint.w;
+}
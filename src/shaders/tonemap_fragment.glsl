@@ -0,0 +1,59 @@
+#version 410 core
+
+in vec2 TexCoord;
+
+out vec4 FragColor;
+
+uniform sampler2D sourceTexture;
+uniform sampler2D exposureTexture;
+uniform bool useAdaptedExposure;
+uniform float manualExposure;
+uniform int toneMapOperator;
+uniform float gamma;
+
+vec3 reinhard(vec3 color) {
+    return color / (vec3(1.0) + color);
+}
+
+vec3 aces(vec3 color) {
+    const float a = 2.51;
+    const float b = 0.03;
+    const float c = 2.43;
+    const float d = 0.59;
+    const float e = 0.14;
+    return clamp((color * (a * color + b)) / (color * (c * color + d) + e), 0.0, 1.0);
+}
+
+vec3 uncharted2Curve(vec3 x) {
+    const float A = 0.15;
+    const float B = 0.50;
+    const float C = 0.10;
+    const float D = 0.20;
+    const float E = 0.02;
+    const float F = 0.30;
+    return ((x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F)) - E / F;
+}
+
+vec3 uncharted2(vec3 color) {
+    const float whitePoint = 11.2;
+    return uncharted2Curve(color * 2.0) / uncharted2Curve(vec3(whitePoint));
+}
+
+void main() {
+    vec3 color = texture(sourceTexture, TexCoord).rgb;
+    float exposure = useAdaptedExposure
+        ? texelFetch(exposureTexture, ivec2(0), 0).r
+        : manualExposure;
+    color *= exposure;
+
+    vec3 mapped;
+    if (toneMapOperator == 0) {
+        mapped = reinhard(color);
+    } else if (toneMapOperator == 1) {
+        mapped = aces(color);
+    } else {
+        mapped = uncharted2(color);
+    }
+
+    FragColor = vec4(pow(clamp(mapped, 0.0, 1.0), vec3(1.0 / gamma)), 1.0);
+}
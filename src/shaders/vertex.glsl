@@ -9,22 +9,22 @@ out vec2 TexCoord;
 out float LayerIndex;
 out vec3 WorldNormal;
 out vec3 WorldPos;
-out vec4 FragPosLightSpace;
+out float ViewDepth;
 
 uniform mat4 modelMatrix;
 uniform mat4 viewMatrix;
 uniform mat4 projectionMatrix;
-uniform mat4 lightSpaceMatrix;
 uniform bool isCelestial;
 
 void main() {
     vec4 worldPosition4 = modelMatrix * vec4(vertexPosition, 1.0);
-    gl_Position = projectionMatrix * viewMatrix * worldPosition4;
+    vec4 viewPosition4 = viewMatrix * worldPosition4;
+    gl_Position = projectionMatrix * viewPosition4;
 
     TexCoord = vertexTexCoord;
     LayerIndex = vertexLayerIndex;
 
     WorldNormal = normalize(mat3(transpose(inverse(modelMatrix))) * vertexNormal);
     WorldPos = worldPosition4.xyz;
-    FragPosLightSpace = lightSpaceMatrix * worldPosition4;
+    ViewDepth = -viewPosition4.z;
 }
@@ -0,0 +1,108 @@
+// Appended to fog_common.glsl.
+
+in vec2 TexCoord;
+in float LayerIndex;
+in vec3 WorldNormal;
+in vec3 WorldPos;
+
+out vec4 FragColor;
+
+uniform sampler2DArray blockTexture;
+uniform sampler2D sceneColor;
+uniform sampler2D sceneDepth;
+uniform mat4 viewMatrix;
+uniform mat4 projectionMatrix;
+uniform vec3 lightDirection;
+uniform vec3 lightColor;
+uniform vec3 ambientColor;
+uniform vec3 skyColor;
+uniform float shininess;
+uniform float time;
+uniform float zNear;
+uniform float zFar;
+uniform vec3 waterColor;
+uniform float waterAbsorption;
+uniform float waveStrength;
+uniform float waveSpeed;
+uniform float refractionStrength;
+
+// Inverse of the camera projection: window depth back to view-space distance.
+float linearizeDepth(float depth) {
+    float ndcDepth = depth * 2.0 - 1.0;
+    return zNear * zFar / (zFar - ndcDepth * (zFar - zNear));
+}
+
+vec3 waveNormal(vec2 position) {
+    float t = time * waveSpeed;
+    vec2 slope = vec2(0.0);
+    slope += vec2(0.8, 0.6) * cos(dot(position, vec2(0.8, 0.6)) * 1.3 + t * 1.1) * 0.5;
+    slope += vec2(-0.4, 0.9) * cos(dot(position, vec2(-0.4, 0.9)) * 2.1 + t * 1.7) * 0.3;
+    slope += vec2(0.7, -0.7) * cos(dot(position, vec2(0.7, -0.7)) * 3.7 + t * 2.3) * 0.2;
+    return normalize(vec3(-slope.x * waveStrength, 1.0, -slope.y * waveStrength));
+}
+
+// Screen-space reflection: march the reflected ray in world space and look for the
+// first step that ends up behind the captured scene depth.
+vec3 traceReflection(vec3 origin, vec3 direction, vec3 fallback) {
+    float stepLength = 0.5;
+    vec3 position = origin;
+    for (int i = 0; i < 32; ++i) {
+        position += direction * stepLength;
+        stepLength *= 1.15;
+
+        vec4 clip = projectionMatrix * viewMatrix * vec4(position, 1.0);
+        if (clip.w <= 0.0)
+            break;
+        vec2 uv = clip.xy / clip.w * 0.5 + 0.5;
+        if (any(lessThan(uv, vec2(0.0))) || any(greaterThan(uv, vec2(1.0))))
+            break;
+
+        float sceneDistance = linearizeDepth(texture(sceneDepth, uv).r);
+        float rayDistance = clip.w;
+        if (rayDistance > sceneDistance && rayDistance - sceneDistance < stepLength * 2.0) {
+            vec2 edge = min(uv, 1.0 - uv);
+            float edgeFade = clamp(min(edge.x, edge.y) * 10.0, 0.0, 1.0);
+            return mix(fallback, texture(sceneColor, uv).rgb, edgeFade);
+        }
+    }
+    return fallback;
+}
+
+void main() {
+    vec3 norm = normalize(WorldNormal);
+    if (norm.y > 0.5) {
+        norm = waveNormal(WorldPos.xz);
+    }
+    vec3 viewDir = normalize(cameraPosition - WorldPos);
+    vec3 lightDir = normalize(lightDirection);
+
+    vec2 screenUV = gl_FragCoord.xy / vec2(textureSize(sceneColor, 0));
+    float surfaceDistance = linearizeDepth(gl_FragCoord.z);
+
+    vec2 refractedUV = clamp(screenUV + norm.xz * refractionStrength, 0.0, 1.0);
+    if (linearizeDepth(texture(sceneDepth, refractedUV).r) < surfaceDistance) {
+        refractedUV = screenUV;
+    }
+    float floorDistance = linearizeDepth(texture(sceneDepth, refractedUV).r);
+    float thickness = max(floorDistance - surfaceDistance, 0.0);
+
+    vec3 surfaceTint = texture(blockTexture, vec3(TexCoord, LayerIndex)).rgb;
+    vec3 scatterColor = waterColor * surfaceTint * 2.0;
+    vec3 litScatter = scatterColor * (max(ambientColor, vec3(0.05)) + lightColor * max(dot(norm, lightDir), 0.0));
+
+    vec3 transmittance = exp(-thickness * waterAbsorption * (vec3(1.0) - waterColor));
+    vec3 refraction = texture(sceneColor, refractedUV).rgb * transmittance
+        + litScatter * (vec3(1.0) - transmittance);
+
+    vec3 reflectDir = reflect(-viewDir, norm);
+    vec3 reflection = traceReflection(WorldPos, reflectDir, skyColor);
+
+    float cosTheta = max(dot(norm, viewDir), 0.0);
+    float fresnel = 0.02 + 0.98 * pow(1.0 - cosTheta, 5.0);
+
+    float spec = pow(max(dot(reflect(-lightDir, norm), viewDir), 0.0), shininess * 4.0);
+    vec3 specular = lightColor * spec;
+
+    vec3 color = mix(refraction, reflection, fresnel) + specular;
+    FragColor = vec4(applyFog(color, WorldPos), 1.0);
+}
@@ -1,22 +1,55 @@
 use crate::components::{
-    world_to_chunk_coords, world_to_local_coords, BlockType, ChunkCoord, ChunkData, ChunkModified,
-    LOD,
+    world_to_chunk_coords, world_to_local_coords, BlockType, ChunkCoord, ChunkData, ChunkDirty,
+    ChunkModified, ChunkVisibility, FLUID_SOURCE_LEVEL, LOD,
 };
 use crate::persistence::{
-    ChunkCache, LoadRequest, LoadResult, NeighborData, WorkerChannels, WorkerPool, WorkerResources,
+    ChunkCache, FarTileResult, LoadRequest, LoadResult, NeighborData, WorkerChannels, WorkerPool,
+    WorkerResources,
 };
 use crate::resources::{
-    Camera, ChunkMeshData, Config, InputState, MeshGenerator, MeshRegistry, Renderer,
-    ShaderProgram, TextureManager, WorldGenerator,
+    generate_cloud_density, BlockAccess, Camera, CancellationSet, ChunkMeshData, Config,
+    FarTerrain, FarTileCoord, InputState, LruCache, MeshGenerator, MeshRegistry, PostProcessChain,
+    PrecipitationHeightmap, Renderer, ShaderProgram, ShadowCascade, TextureManager, Weather,
+    WorkerReport, WorkerStats, WorldGenerator, MAX_SHADOW_CASCADES,
 };
 use crossbeam_channel::{Receiver, Sender};
-use fnv::FnvHashMap;
-use glam::Vec3;
+use fnv::{FnvHashMap, FnvHashSet};
+use glam::{IVec3, Vec3};
 use hecs::{Entity, World};
 use std::sync::Arc;
 
-pub type MeshRequestData = (Entity, ChunkCoord, ChunkData, NeighborData, LOD);
-pub type MeshResultData = (Entity, ChunkCoord, Option<ChunkMeshData>, LOD);
+pub type MeshRequestData = (Entity, ChunkCoord, ChunkData, NeighborData, LOD, [LOD; 6]);
+pub type MeshResultData = (
+    Entity,
+    ChunkCoord,
+    Result<Option<ChunkMeshData>, String>,
+    ChunkVisibility,
+    LOD,
+);
+
+const CLOUD_UNIFORMS: [&str; 4] = ["cloudTexture", "cloudOffset", "cloudScale", "cloudCoverage"];
+
+// Cloud fragment shaders share the density lookup in cloud_common.glsl.
+fn cloud_fragment_source(body: &str) -> String {
+    format!("{}\n{}", include_str!("./shaders/cloud_common.glsl"), body)
+}
+
+// The terrain and water fragment shaders share the fog in fog_common.glsl.
+fn fog_fragment_source(body: &str) -> String {
+    format!("{}\n{}", include_str!("./shaders/fog_common.glsl"), body)
+}
+
+const FOG_UNIFORMS: [&str; 9] = [
+    "fogEnabled",
+    "fogStart",
+    "fogEnd",
+    "fogColor",
+    "fogSunColor",
+    "sunDirection",
+    "heightFogDensity",
+    "heightFogFalloff",
+    "fogBaseHeight",
+];
 
 pub struct GameState {
     pub config: Config,
@@ -24,8 +57,15 @@ pub struct GameState {
     pub camera: Camera,
     pub renderer: Renderer,
     pub shader_program: ShaderProgram,
+    pub sky_shader_program: ShaderProgram,
     pub star_shader_program: ShaderProgram,
     pub shadow_shader_program: ShaderProgram,
+    pub water_shader_program: ShaderProgram,
+    pub cloud_shader_program: ShaderProgram,
+    pub cloud_raymarch_shader_program: ShaderProgram,
+    pub cloud_shadow_shader_program: ShaderProgram,
+    pub precipitation_shader_program: ShaderProgram,
+    pub post_processing: PostProcessChain,
     pub input_state: InputState,
     pub texture_manager: Arc<TextureManager>,
     pub mesh_registry: MeshRegistry,
@@ -33,28 +73,43 @@ pub struct GameState {
     pub chunk_entity_map: FnvHashMap<ChunkCoord, Entity>,
     pub world_generator: Arc<WorldGenerator>,
     pub chunk_cache: ChunkCache,
+    // Recently unloaded chunks, checked before asking the workers to read `chunk_cache`.
+    pub chunk_memory_cache: LruCache<ChunkCoord, ChunkData>,
+    pub scheduled_fluid_updates: FnvHashSet<IVec3>,
+    pub scheduled_gravity_checks: FnvHashSet<IVec3>,
     pub gen_request_tx: Sender<LoadRequest>,
     pub gen_result_rx: Receiver<LoadResult>,
     pub mesh_request_tx: Sender<MeshRequestData>,
     pub mesh_result_rx: Receiver<MeshResultData>,
+    pub far_tile_request_tx: Sender<FarTileCoord>,
+    pub far_tile_result_rx: Receiver<FarTileResult>,
+    pub gen_cancellations: Arc<CancellationSet<ChunkCoord>>,
+    pub mesh_cancellations: Arc<CancellationSet<ChunkCoord>>,
+    pub worker_stats: Arc<WorkerStats>,
+    pub worker_report: WorkerReport,
     gen_request_rx_worker: Option<Receiver<LoadRequest>>,
     gen_result_tx_worker: Option<Sender<LoadResult>>,
     mesh_request_rx_worker: Option<Receiver<MeshRequestData>>,
     mesh_result_tx_worker: Option<Sender<MeshResultData>>,
+    far_tile_request_rx_worker: Option<Receiver<FarTileCoord>>,
+    far_tile_result_tx_worker: Option<Sender<FarTileResult>>,
     worker_pool: Option<WorkerPool>,
     pub time_of_day: f32,
     pub total_time: f32,
-    pub light_space_matrix: glam::Mat4,
+    pub shadow_cascades: Vec<ShadowCascade>,
+    pub weather: Weather,
+    pub precipitation_heightmap: PrecipitationHeightmap,
+    pub far_terrain: FarTerrain,
 }
 
 impl GameState {
     pub fn new(gl: crate::gl::Gl, width: u32, height: u32) -> Self {
         let config = Config::new();
-        let renderer = Renderer::new(gl.clone(), &config);
+        let mut renderer = Renderer::new(gl.clone(), &config);
         let mut shader_program = ShaderProgram::from_sources(
             &renderer.gl,
             include_str!("./shaders/vertex.glsl"),
-            include_str!("./shaders/fragment.glsl"),
+            &fog_fragment_source(include_str!("./shaders/fragment.glsl")),
         )
         .expect("Failed to create main shader program");
 
@@ -68,10 +123,41 @@ impl GameState {
         shader_program.register_uniform("minAmbientContribution");
         shader_program.register_uniform("isCelestial");
         shader_program.register_uniform("celestialLayerIndex");
+        shader_program.register_uniform("celestialIntensity");
+        shader_program.register_uniform("isFarTerrain");
+        shader_program.register_uniform("cameraChunk");
+        shader_program.register_uniform("chunkSize");
+        shader_program.register_uniform("chunkRenderDistance");
+        shader_program.register_uniform("lodVerticalWeight");
         shader_program.register_uniform("cameraPosition");
         shader_program.register_uniform("shininess");
-        shader_program.register_uniform("lightSpaceMatrix");
         shader_program.register_uniform("shadowMap");
+        shader_program.register_uniform("cascadeCount");
+        shader_program.register_uniform("cascadeBlendFraction");
+        for uniform in FOG_UNIFORMS {
+            shader_program.register_uniform(uniform);
+        }
+        for i in 0..MAX_SHADOW_CASCADES {
+            shader_program.register_uniform(&format!("lightSpaceMatrices[{}]", i));
+            shader_program.register_uniform(&format!("cascadeSplits[{}]", i));
+            shader_program.register_uniform(&format!("cascadeBias[{}]", i));
+            shader_program.register_uniform(&format!("cascadePcfRadius[{}]", i));
+        }
+
+        let mut sky_shader_program = ShaderProgram::from_sources(
+            &renderer.gl,
+            include_str!("./shaders/sky_vertex.glsl"),
+            include_str!("./shaders/sky_fragment.glsl"),
+        )
+        .expect("Failed to create sky shader program");
+
+        sky_shader_program.register_uniform("inverseViewProjection");
+        sky_shader_program.register_uniform("sunDirection");
+        sky_shader_program.register_uniform("sunIntensity");
+        sky_shader_program.register_uniform("mieAnisotropy");
+        sky_shader_program.register_uniform("nightColor");
+        sky_shader_program.register_uniform("weatherDarkening");
+        sky_shader_program.register_uniform("lightningFlash");
 
         let mut star_shader_program = ShaderProgram::from_sources(
             &renderer.gl,
@@ -96,6 +182,140 @@ impl GameState {
         shadow_shader_program.register_uniform("lightSpaceMatrix");
         shadow_shader_program.register_uniform("modelMatrix");
 
+        let mut water_shader_program = ShaderProgram::from_sources(
+            &renderer.gl,
+            include_str!("./shaders/water_vertex.glsl"),
+            &fog_fragment_source(include_str!("./shaders/water_fragment.glsl")),
+        )
+        .expect("Failed to create water shader program");
+
+        for uniform in [
+            "viewMatrix",
+            "projectionMatrix",
+            "blockTexture",
+            "sceneColor",
+            "sceneDepth",
+            "cameraPosition",
+            "lightDirection",
+            "lightColor",
+            "ambientColor",
+            "skyColor",
+            "shininess",
+            "time",
+            "zNear",
+            "zFar",
+            "waterColor",
+            "waterAbsorption",
+            "waveStrength",
+            "waveSpeed",
+            "refractionStrength",
+        ]
+        .into_iter()
+        .chain(FOG_UNIFORMS)
+        {
+            water_shader_program.register_uniform(uniform);
+        }
+
+        let mut cloud_shader_program = ShaderProgram::from_sources(
+            &renderer.gl,
+            include_str!("./shaders/cloud_plane_vertex.glsl"),
+            &cloud_fragment_source(include_str!("./shaders/cloud_plane_fragment.glsl")),
+        )
+        .expect("Failed to create cloud shader program");
+
+        for uniform in [
+            "viewMatrix",
+            "projectionMatrix",
+            "cloudCenter",
+            "cloudExtent",
+            "cloudAltitude",
+            "lightDirection",
+            "lightColor",
+            "ambientColor",
+        ]
+        .into_iter()
+        .chain(CLOUD_UNIFORMS)
+        {
+            cloud_shader_program.register_uniform(uniform);
+        }
+
+        let mut cloud_raymarch_shader_program = ShaderProgram::from_sources(
+            &renderer.gl,
+            include_str!("./shaders/sky_vertex.glsl"),
+            &cloud_fragment_source(include_str!("./shaders/cloud_raymarch_fragment.glsl")),
+        )
+        .expect("Failed to create raymarched cloud shader program");
+
+        for uniform in [
+            "inverseViewProjection",
+            "sceneDepth",
+            "cameraPosition",
+            "cameraForward",
+            "zNear",
+            "zFar",
+            "cloudAltitude",
+            "cloudThickness",
+            "raymarchSteps",
+            "lightDirection",
+            "lightColor",
+            "ambientColor",
+        ]
+        .into_iter()
+        .chain(CLOUD_UNIFORMS)
+        {
+            cloud_raymarch_shader_program.register_uniform(uniform);
+        }
+
+        let mut cloud_shadow_shader_program = ShaderProgram::from_sources(
+            &renderer.gl,
+            include_str!("./shaders/cloud_shadow_vertex.glsl"),
+            &cloud_fragment_source(include_str!("./shaders/cloud_shadow_fragment.glsl")),
+        )
+        .expect("Failed to create cloud shadow shader program");
+
+        for uniform in [
+            "lightSpaceMatrix",
+            "cloudCenter",
+            "cloudExtent",
+            "cloudAltitude",
+        ]
+        .into_iter()
+        .chain(CLOUD_UNIFORMS)
+        {
+            cloud_shadow_shader_program.register_uniform(uniform);
+        }
+
+        let mut precipitation_shader_program = ShaderProgram::from_sources(
+            &renderer.gl,
+            include_str!("./shaders/precipitation_vertex.glsl"),
+            include_str!("./shaders/precipitation_fragment.glsl"),
+        )
+        .expect("Failed to create precipitation shader program");
+
+        for uniform in [
+            "viewMatrix",
+            "projectionMatrix",
+            "cameraPosition",
+            "time",
+            "isSnow",
+            "intensity",
+            "areaRadius",
+            "areaHeight",
+            "fallSpeed",
+            "streakLength",
+            "heightmap",
+            "heightmapOrigin",
+            "ambientColor",
+            "lightColor",
+        ] {
+            precipitation_shader_program.register_uniform(uniform);
+        }
+
+        renderer.upload_cloud_texture(
+            config.cloud_texture_resolution,
+            &generate_cloud_density(config.world_seed, config.cloud_texture_resolution),
+        );
+
         let camera = Camera::new(
             Vec3::new(0.0, 20.0, 0.0),
             Vec3::new(0.0, 0.0, 0.0),
@@ -128,7 +348,7 @@ impl GameState {
             ("sun", "assets/textures/sun.png"),
             ("moon", "assets/textures/moon.png"),
         ];
-        if let Err(e) = texture_manager.load_textures_as_array(&texture_files) {
+        if let Err(e) = texture_manager.load_textures_as_array(&texture_files, config.hdr_enabled) {
             panic!("Failed to load textures into array: {}", e);
         }
         #[allow(clippy::arc_with_non_send_sync)]
@@ -140,14 +360,30 @@ impl GameState {
         let world = World::new();
         let chunk_entity_map = FnvHashMap::default();
 
-        let (gen_request_tx, gen_request_rx_worker) = crossbeam_channel::unbounded::<LoadRequest>();
-        let (gen_result_tx_worker, gen_result_rx) = crossbeam_channel::unbounded::<LoadResult>();
+        // Bounded both ways: systems hold requests back in their own queues when the request
+        // side is full, and workers block on a full result side until the main thread catches up.
+        let capacity = config.worker_channel_capacity.max(1);
+        let (gen_request_tx, gen_request_rx_worker) =
+            crossbeam_channel::bounded::<LoadRequest>(capacity);
+        let (gen_result_tx_worker, gen_result_rx) =
+            crossbeam_channel::bounded::<LoadResult>(capacity);
         let (mesh_request_tx, mesh_request_rx_worker) =
-            crossbeam_channel::unbounded::<MeshRequestData>();
+            crossbeam_channel::bounded::<MeshRequestData>(capacity);
         let (mesh_result_tx_worker, mesh_result_rx) =
-            crossbeam_channel::unbounded::<MeshResultData>();
+            crossbeam_channel::bounded::<MeshResultData>(capacity);
+        let (far_tile_request_tx, far_tile_request_rx_worker) =
+            crossbeam_channel::bounded::<FarTileCoord>(capacity);
+        let (far_tile_result_tx_worker, far_tile_result_rx) =
+            crossbeam_channel::bounded::<FarTileResult>(capacity);
+
+        let precipitation_heightmap = PrecipitationHeightmap::new(config.precipitation_radius);
+
+        let post_processing =
+            PostProcessChain::new(&renderer.gl, &config, width as i32, height as i32)
+                .expect("Failed to create post-processing chain");
 
         let chunk_cache = ChunkCache::new("world").expect("Failed to initialize chunk cache");
+        let chunk_memory_cache = LruCache::new(config.chunk_memory_budget_bytes);
 
         Self {
             config,
@@ -156,26 +392,47 @@ impl GameState {
             camera,
             renderer,
             shader_program,
+            sky_shader_program,
             star_shader_program,
             shadow_shader_program,
+            water_shader_program,
+            cloud_shader_program,
+            cloud_raymarch_shader_program,
+            cloud_shadow_shader_program,
+            precipitation_shader_program,
+            post_processing,
             texture_manager,
             mesh_registry,
             mesh_generator,
             chunk_entity_map,
             world_generator,
             chunk_cache,
+            chunk_memory_cache,
+            scheduled_fluid_updates: FnvHashSet::default(),
+            scheduled_gravity_checks: FnvHashSet::default(),
             gen_request_tx,
             gen_result_rx,
             mesh_request_tx,
             mesh_result_rx,
+            far_tile_request_tx,
+            far_tile_result_rx,
+            gen_cancellations: Arc::new(CancellationSet::new()),
+            mesh_cancellations: Arc::new(CancellationSet::new()),
+            worker_stats: Arc::new(WorkerStats::new()),
+            worker_report: WorkerReport::default(),
             gen_request_rx_worker: Some(gen_request_rx_worker),
             gen_result_tx_worker: Some(gen_result_tx_worker),
             mesh_request_rx_worker: Some(mesh_request_rx_worker),
             mesh_result_tx_worker: Some(mesh_result_tx_worker),
+            far_tile_request_rx_worker: Some(far_tile_request_rx_worker),
+            far_tile_result_tx_worker: Some(far_tile_result_tx_worker),
             worker_pool: None,
             time_of_day: 0.5,
             total_time: 0.0,
-            light_space_matrix: glam::Mat4::IDENTITY,
+            shadow_cascades: Vec::new(),
+            weather: Weather::new(),
+            precipitation_heightmap,
+            far_terrain: FarTerrain::new(),
         }
     }
 
@@ -190,6 +447,9 @@ impl GameState {
             texture_manager_layers: Arc::new(self.texture_manager.get_all_layers()),
             chunk_cache: self.chunk_cache.clone(),
             config: self.config.clone(),
+            gen_cancellations: Arc::clone(&self.gen_cancellations),
+            mesh_cancellations: Arc::clone(&self.mesh_cancellations),
+            stats: Arc::clone(&self.worker_stats),
         };
 
         let channels = WorkerChannels {
@@ -209,11 +469,33 @@ impl GameState {
                 .mesh_result_tx_worker
                 .take()
                 .expect("WorkerPool Init: Mesh Result Tx channel missing"),
+            far_tile_request_rx: self
+                .far_tile_request_rx_worker
+                .take()
+                .expect("WorkerPool Init: Far Tile Request Rx channel missing"),
+            far_tile_result_tx: self
+                .far_tile_result_tx_worker
+                .take()
+                .expect("WorkerPool Init: Far Tile Result Tx channel missing"),
         };
 
         self.worker_pool = Some(WorkerPool::new(resources, channels));
     }
 
+    // (generation, meshing) threads, or zeros before the pool is started.
+    pub fn worker_thread_counts(&self) -> (usize, usize) {
+        self.worker_pool
+            .as_ref()
+            .map_or((0, 0), WorkerPool::thread_counts)
+    }
+
+    // Replaces worker threads that died; returns how many were respawned.
+    pub fn respawn_dead_workers(&mut self) -> usize {
+        self.worker_pool
+            .as_mut()
+            .map_or(0, WorkerPool::respawn_dead_workers)
+    }
+
     pub fn shutdown_workers(&mut self) {
         if let Some(pool) = self.worker_pool.take() {
             pool.shutdown();
@@ -292,17 +574,161 @@ impl GameState {
 
     pub fn handle_resize(&mut self, width: u32, height: u32) {
         self.renderer.resize(width as i32, height as i32);
+        self.post_processing.resize(width as i32, height as i32);
         self.camera.update_aspect_ratio(width as f32, height as f32);
     }
 
-    pub fn get_block_world(&self, world_x: i32, world_y: i32, world_z: i32) -> BlockType {
+    // Player edits mark the chunk modified so it is saved on unload; natural changes only
+    // get it remeshed.
+    fn write_block_world(
+        &mut self,
+        pos: IVec3,
+        block_type: BlockType,
+        level: u8,
+        player_edit: bool,
+    ) -> bool {
+        let chunk_coord = world_to_chunk_coords(&self.config, pos.x, pos.y, pos.z);
+        let entity = match self.chunk_entity_map.get(&chunk_coord) {
+            Some(entity) => *entity,
+            None => return false,
+        };
+        let (lx, ly, lz) = world_to_local_coords(&self.config, pos.x, pos.y, pos.z);
+
+        match self.world.get::<&mut ChunkData>(entity) {
+            Ok(mut data_ref) => {
+                if data_ref.get_block(&self.config, lx, ly, lz) == block_type
+                    && data_ref.get_fluid_level(&self.config, lx, ly, lz) == level
+                {
+                    return true;
+                }
+                data_ref.set_fluid(&self.config, lx, ly, lz, block_type, level);
+            }
+            Err(_) => return false,
+        }
+
+        let marked = if player_edit {
+            self.world.insert(entity, (ChunkModified, ChunkDirty))
+        } else {
+            self.world.insert_one(entity, ChunkDirty)
+        };
+        if let Err(e) = marked {
+            eprintln!(
+                "Failed to mark chunk {:?} dirty after block change: {}",
+                chunk_coord, e
+            );
+        }
+        self.mark_border_neighbors_dirty(chunk_coord, lx, ly, lz);
+        self.schedule_fluid_updates_around(pos);
+        self.scheduled_gravity_checks.insert(pos);
+        self.scheduled_gravity_checks.insert(pos + IVec3::Y);
+        true
+    }
+
+    pub fn schedule_fluid_updates_around(&mut self, pos: IVec3) {
+        self.scheduled_fluid_updates.insert(pos);
+        for offset in [
+            IVec3::X,
+            IVec3::NEG_X,
+            IVec3::Y,
+            IVec3::NEG_Y,
+            IVec3::Z,
+            IVec3::NEG_Z,
+        ] {
+            self.scheduled_fluid_updates.insert(pos + offset);
+        }
+    }
+
+    fn mark_border_neighbors_dirty(&mut self, coord: ChunkCoord, lx: usize, ly: usize, lz: usize) {
+        let mut neighbor_coords = Vec::new();
+        if lx == 0 {
+            neighbor_coords.push(ChunkCoord(coord.0 - 1, coord.1, coord.2));
+        }
+        if lx == self.config.chunk_width - 1 {
+            neighbor_coords.push(ChunkCoord(coord.0 + 1, coord.1, coord.2));
+        }
+        if ly == 0 {
+            neighbor_coords.push(ChunkCoord(coord.0, coord.1 - 1, coord.2));
+        }
+        if ly == self.config.chunk_height - 1 {
+            neighbor_coords.push(ChunkCoord(coord.0, coord.1 + 1, coord.2));
+        }
+        if lz == 0 {
+            neighbor_coords.push(ChunkCoord(coord.0, coord.1, coord.2 - 1));
+        }
+        if lz == self.config.chunk_depth - 1 {
+            neighbor_coords.push(ChunkCoord(coord.0, coord.1, coord.2 + 1));
+        }
+
+        for neighbor_coord in neighbor_coords {
+            if let Some(neighbor_entity) = self.chunk_entity_map.get(&neighbor_coord) {
+                if let Err(e) = self.world.insert_one(*neighbor_entity, ChunkDirty) {
+                    eprintln!(
+                        "Failed to insert ChunkDirty for neighbor {:?} of {:?}: {}",
+                        neighbor_coord, coord, e
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl BlockAccess for GameState {
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    fn get_loaded_block_world(
+        &self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+    ) -> Option<BlockType> {
+        let chunk_coord = world_to_chunk_coords(&self.config, world_x, world_y, world_z);
+        let entity = self.chunk_entity_map.get(&chunk_coord)?;
+        let data_ref = self.world.get::<&ChunkData>(*entity).ok()?;
+        let (lx, ly, lz) = world_to_local_coords(&self.config, world_x, world_y, world_z);
+        Some(data_ref.get_block(&self.config, lx, ly, lz))
+    }
+
+    fn get_fluid_level_world(&self, world_x: i32, world_y: i32, world_z: i32) -> u8 {
         let chunk_coord = world_to_chunk_coords(&self.config, world_x, world_y, world_z);
         if let Some(entity) = self.chunk_entity_map.get(&chunk_coord) {
             if let Ok(data_ref) = self.world.get::<&ChunkData>(*entity) {
                 let (lx, ly, lz) = world_to_local_coords(&self.config, world_x, world_y, world_z);
-                return data_ref.get_block(&self.config, lx, ly, lz);
+                return data_ref.get_fluid_level(&self.config, lx, ly, lz);
             }
         }
-        BlockType::Air
+        FLUID_SOURCE_LEVEL
+    }
+
+    fn set_fluid_world(
+        &mut self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+        block_type: BlockType,
+        level: u8,
+    ) -> bool {
+        self.write_block_world(
+            IVec3::new(world_x, world_y, world_z),
+            block_type,
+            level,
+            true,
+        )
+    }
+
+    fn set_natural_block_world(
+        &mut self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+        block_type: BlockType,
+    ) -> bool {
+        self.write_block_world(
+            IVec3::new(world_x, world_y, world_z),
+            block_type,
+            FLUID_SOURCE_LEVEL,
+            false,
+        )
     }
 }
@@ -1,103 +1,247 @@
 use crate::components::{
-    world_to_chunk_coords, ChunkCoord, ChunkData, ChunkDirty, ChunkModified, Renderable,
+    chunk_load_priority, chunk_view_priority, is_past_unload_distance, is_within_render_distance,
+    weighted_chunk_distance_sq, world_to_chunk_coords, ChunkCoord, ChunkData, ChunkDirty,
+    ChunkModified, Renderable,
 };
-use crate::persistence::LoadRequest;
+use crate::persistence::{LoadRequest, LoadResult};
+use crate::resources::{Config, FailureTracker, FrameBudget, RequestQueue, WorldGenerator};
 use crate::state::GameState;
+use crossbeam_channel::TrySendError;
 use fnv::FnvHashSet;
 use hecs::Entity;
 
 pub struct ChunkLoadingSystem {
-    last_camera_chunk_coord_xz: Option<(i32, i32)>,
+    last_camera_chunk_coord: Option<ChunkCoord>,
+    // Requests handed to the workers and not answered yet.
     pending_requests: FnvHashSet<ChunkCoord>,
+    // Requests waiting for a free slot, keyed by coordinate with the column's surface chunk y.
+    load_queue: RequestQueue<ChunkCoord, i32>,
+    // A finished chunk that didn't fit in last frame's spawn budget.
+    carried_result: Option<LoadResult>,
+    // Coordinates whose load or generation panicked, retried up to `max_job_attempts` times.
+    failures: FailureTracker<ChunkCoord>,
 }
 
 impl ChunkLoadingSystem {
     pub fn new() -> Self {
         Self {
-            last_camera_chunk_coord_xz: None,
+            last_camera_chunk_coord: None,
             pending_requests: FnvHashSet::default(),
+            load_queue: RequestQueue::new(),
+            carried_result: None,
+            failures: FailureTracker::default(),
         }
     }
 
+    pub fn queued_requests(&self) -> usize {
+        self.load_queue.len()
+    }
+
+    pub fn in_flight_requests(&self) -> usize {
+        self.pending_requests.len()
+    }
+
+    // Chunks that failed on every attempt and won't be requested again while in range.
+    pub fn errored_chunks(&self) -> usize {
+        self.failures.errored_count()
+    }
+
     pub fn update(&mut self, game_state: &mut GameState) {
-        while let Ok((coord, opt_chunk_data)) = game_state.gen_result_rx.try_recv() {
+        self.failures
+            .set_max_attempts(game_state.config.max_job_attempts);
+        let mut budget = FrameBudget::from_millis(game_state.config.spawn_budget_ms_per_frame);
+        while let Some((coord, opt_chunk_data)) = self
+            .carried_result
+            .take()
+            .or_else(|| game_state.gen_result_rx.try_recv().ok())
+        {
+            if !budget.try_spend(0) {
+                self.carried_result = Some((coord, opt_chunk_data));
+                break;
+            }
             self.pending_requests.remove(&coord);
+            game_state.gen_cancellations.revoke(&coord);
+            let opt_chunk_data = match opt_chunk_data {
+                Ok(opt_chunk_data) => {
+                    self.failures.clear(&coord);
+                    opt_chunk_data
+                }
+                Err(_) => {
+                    if !self.is_chunk_within_render_distance(coord, game_state) {
+                        continue;
+                    }
+                    if self.failures.record_failure(coord) {
+                        let surface_chunk_y = Self::surface_chunk_y(
+                            &game_state.config,
+                            &game_state.world_generator,
+                            coord.0,
+                            coord.2,
+                        );
+                        self.load_queue.push(coord, 0.0, surface_chunk_y);
+                    } else {
+                        eprintln!(
+                            "Giving up on chunk {:?} after {} failed attempts",
+                            coord, game_state.config.max_job_attempts
+                        );
+                    }
+                    continue;
+                }
+            };
             if let Some(chunk_data) = opt_chunk_data {
                 if self.is_chunk_within_render_distance(coord, game_state)
                     && !game_state.chunk_entity_map.contains_key(&coord)
                 {
-                    let new_entity = game_state.world.spawn((coord, chunk_data, ChunkDirty));
-                    game_state.chunk_entity_map.insert(coord, new_entity);
-                    self.mark_neighbors_dirty(coord, game_state);
+                    self.spawn_chunk(coord, chunk_data, game_state);
                 }
             }
         }
 
-        let camera_pos = game_state.camera.position;
-        let current_cam_chunk_xz = (
-            world_to_chunk_coords(&game_state.config, camera_pos.x.floor() as i32, 0, 0).0,
-            world_to_chunk_coords(&game_state.config, 0, 0, camera_pos.z.floor() as i32).2,
+        let camera_pos = game_state.camera.position.floor();
+        let camera_chunk = world_to_chunk_coords(
+            &game_state.config,
+            camera_pos.x as i32,
+            camera_pos.y as i32,
+            camera_pos.z as i32,
         );
 
-        let needs_recalc = match self.last_camera_chunk_coord_xz {
-            Some(last_xz) => last_xz != current_cam_chunk_xz,
-            None => true,
-        };
+        if self.last_camera_chunk_coord != Some(camera_chunk) {
+            self.last_camera_chunk_coord = Some(camera_chunk);
+            self.update_loaded_range(camera_chunk, game_state);
+        }
 
-        if !needs_recalc {
+        self.dispatch_requests(camera_chunk, &mut budget, game_state);
+    }
+
+    fn spawn_chunk(&self, coord: ChunkCoord, chunk_data: ChunkData, game_state: &mut GameState) {
+        let new_entity = game_state.world.spawn((coord, chunk_data, ChunkDirty));
+        game_state.chunk_entity_map.insert(coord, new_entity);
+        self.mark_neighbors_dirty(coord, game_state);
+    }
+
+    // Re-ranks the queue for the current view, then fills the free in-flight slots from it.
+    // Chunks still held in memory are spawned directly, within what is left of the spawn budget.
+    fn dispatch_requests(
+        &mut self,
+        camera_chunk: ChunkCoord,
+        budget: &mut FrameBudget,
+        game_state: &mut GameState,
+    ) {
+        if self.load_queue.is_empty() {
             return;
         }
-        self.last_camera_chunk_coord_xz = Some(current_cam_chunk_xz);
 
-        let (cam_cx, cam_cz) = current_cam_chunk_xz;
-        let load_dist = game_state.config.load_distance;
-        let render_dist = game_state.config.render_distance;
-        let load_dist_sq = load_dist * load_dist;
-        let render_dist_sq = render_dist * render_dist;
+        let frustum = game_state.camera.frustum();
+        let config = &game_state.config;
+        let chunk_entity_map = &game_state.chunk_entity_map;
+        self.load_queue.reprioritize(|coord, surface_chunk_y| {
+            if chunk_entity_map.contains_key(coord)
+                || !is_within_render_distance(config, camera_chunk, *coord)
+            {
+                return None;
+            }
+            let base = chunk_load_priority(config, camera_chunk, *coord, *surface_chunk_y);
+            Some(chunk_view_priority(config, &frustum, *coord, base))
+        });
 
-        let mut target_render_chunks = FnvHashSet::<ChunkCoord>::default();
+        let load_dist_sq = (config.load_distance * config.load_distance) as f32;
+        while let Some((coord, surface_chunk_y)) = self.load_queue.pop() {
+            if game_state.chunk_memory_cache.contains(&coord) {
+                if !budget.try_spend(0) {
+                    self.load_queue.push(coord, 0.0, surface_chunk_y);
+                    break;
+                }
+                if let Some(chunk_data) = game_state.chunk_memory_cache.remove(&coord) {
+                    self.spawn_chunk(coord, chunk_data, game_state);
+                }
+                continue;
+            }
+            let config = &game_state.config;
+            if self.pending_requests.len() >= config.max_in_flight_load_requests {
+                self.load_queue.push(coord, 0.0, surface_chunk_y);
+                break;
+            }
+            let request_type =
+                if weighted_chunk_distance_sq(config, camera_chunk, coord) <= load_dist_sq {
+                    LoadRequest::LoadOrGenerate(coord)
+                } else {
+                    LoadRequest::LoadFromCache(coord)
+                };
 
-        for dz in -render_dist..=render_dist {
-            for dx in -render_dist..=render_dist {
-                let dist_sq = dx * dx + dz * dz;
-                if dist_sq <= render_dist_sq {
-                    let target_cx = cam_cx + dx;
-                    let target_cz = cam_cz + dz;
-                    for target_cy in game_state.config.min_chunk_y..=game_state.config.max_chunk_y {
-                        target_render_chunks.insert(ChunkCoord(target_cx, target_cy, target_cz));
-                    }
+            game_state.gen_cancellations.revoke(&coord);
+            match game_state.gen_request_tx.try_send(request_type) {
+                Ok(()) => {
+                    self.pending_requests.insert(coord);
+                }
+                Err(TrySendError::Full(_)) => {
+                    self.load_queue.push(coord, 0.0, surface_chunk_y);
+                    break;
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    eprintln!("Failed to send chunk load request for {:?}", coord);
+                    break;
                 }
             }
         }
+    }
+
+    fn update_loaded_range(&mut self, camera_chunk: ChunkCoord, game_state: &mut GameState) {
+        let config = &game_state.config;
+        let render_dist = config.render_distance;
 
         let currently_loaded_coords: FnvHashSet<ChunkCoord> =
             game_state.chunk_entity_map.keys().copied().collect();
 
-        for coord_to_load in target_render_chunks.iter() {
-            if !currently_loaded_coords.contains(coord_to_load)
-                && !self.pending_requests.contains(coord_to_load)
-            {
-                let dx = coord_to_load.0 - cam_cx;
-                let dz = coord_to_load.2 - cam_cz;
-                let request_type = if dx * dx + dz * dz <= load_dist_sq {
-                    LoadRequest::LoadOrGenerate(*coord_to_load)
-                } else {
-                    LoadRequest::LoadFromCache(*coord_to_load)
-                };
-
-                if game_state.gen_request_tx.send(request_type).is_ok() {
-                    self.pending_requests.insert(*coord_to_load);
-                } else {
-                    eprintln!("Failed to send chunk load request for {:?}", coord_to_load);
+        for dz in -render_dist..=render_dist {
+            for dx in -render_dist..=render_dist {
+                if dx * dx + dz * dz > render_dist * render_dist {
+                    continue;
+                }
+                let target_cx = camera_chunk.0 + dx;
+                let target_cz = camera_chunk.2 + dz;
+                let mut surface_chunk_y = None;
+                for target_cy in config.min_chunk_y..=config.max_chunk_y {
+                    let coord = ChunkCoord(target_cx, target_cy, target_cz);
+                    if !is_within_render_distance(config, camera_chunk, coord)
+                        || currently_loaded_coords.contains(&coord)
+                        || self.pending_requests.contains(&coord)
+                        || self.load_queue.contains(&coord)
+                        || self.failures.is_errored(&coord)
+                    {
+                        continue;
+                    }
+                    let surface_chunk_y = *surface_chunk_y.get_or_insert_with(|| {
+                        Self::surface_chunk_y(
+                            config,
+                            &game_state.world_generator,
+                            target_cx,
+                            target_cz,
+                        )
+                    });
+                    // Real priorities are assigned when the queue is re-ranked before dispatch.
+                    self.load_queue.push(coord, 0.0, surface_chunk_y);
                 }
             }
         }
 
+        // Chunks that errored get another chance once they leave range and come back.
+        self.failures
+            .retain(|coord| is_within_render_distance(config, camera_chunk, *coord));
+
+        // Requests already with the workers for chunks that left range.
+        let stale_requests: Vec<ChunkCoord> = self
+            .pending_requests
+            .iter()
+            .filter(|coord| !is_within_render_distance(config, camera_chunk, **coord))
+            .copied()
+            .collect();
+        for coord in stale_requests {
+            self.pending_requests.remove(&coord);
+            game_state.gen_cancellations.cancel(coord);
+        }
+
         let mut coords_to_unload = Vec::new();
         for loaded_coord in currently_loaded_coords.iter() {
-            let dx = loaded_coord.0 - cam_cx;
-            let dz = loaded_coord.2 - cam_cz;
-            if dx * dx + dz * dz > render_dist_sq {
+            if is_past_unload_distance(config, camera_chunk, *loaded_coord) {
                 coords_to_unload.push(*loaded_coord);
             }
         }
@@ -105,39 +249,40 @@ impl ChunkLoadingSystem {
         struct UnloadInfo {
             entity: Entity,
             coord: ChunkCoord,
-            data_to_save: Option<ChunkData>,
-            opaque_mesh_id_to_remove: Option<usize>,
-            transparent_mesh_id_to_remove: Option<usize>,
+            chunk_data: Option<ChunkData>,
+            modified: bool,
+            mesh_ids_to_remove: Vec<usize>,
         }
         let mut unload_infos = Vec::new();
 
         for coord in coords_to_unload {
             if let Some(entity) = game_state.chunk_entity_map.get(&coord).copied() {
                 if game_state.world.contains(entity) {
-                    let mut data_to_save: Option<ChunkData> = None;
-                    if game_state.world.get::<&ChunkModified>(entity).is_ok() {
-                        if let Ok(data_ref) = game_state.world.get::<&ChunkData>(entity) {
-                            data_to_save = Some((*data_ref).clone());
-                        } else {
-                            eprintln!(
-                                "ChunkData missing for modified chunk {:?} during unload check",
-                                coord
-                            );
+                    let modified = game_state.world.get::<&ChunkModified>(entity).is_ok();
+                    let chunk_data = match game_state.world.get::<&ChunkData>(entity) {
+                        Ok(data_ref) => Some((*data_ref).clone()),
+                        Err(_) => {
+                            if modified {
+                                eprintln!(
+                                    "ChunkData missing for modified chunk {:?} during unload check",
+                                    coord
+                                );
+                            }
+                            None
                         }
-                    }
+                    };
 
-                    let (opaque_id, transparent_id) =
-                        match game_state.world.get::<&Renderable>(entity) {
-                            Ok(r) => (r.opaque_mesh_id, r.transparent_mesh_id),
-                            Err(_) => (None, None),
-                        };
+                    let mesh_ids = match game_state.world.get::<&Renderable>(entity) {
+                        Ok(r) => r.mesh_ids().collect(),
+                        Err(_) => Vec::new(),
+                    };
 
                     unload_infos.push(UnloadInfo {
                         entity,
                         coord,
-                        data_to_save,
-                        opaque_mesh_id_to_remove: opaque_id,
-                        transparent_mesh_id_to_remove: transparent_id,
+                        chunk_data,
+                        modified,
+                        mesh_ids_to_remove: mesh_ids,
                     });
                 } else {
                     game_state.chunk_entity_map.remove(&coord);
@@ -146,21 +291,34 @@ impl ChunkLoadingSystem {
             self.pending_requests.remove(&coord);
         }
 
+        game_state
+            .chunk_memory_cache
+            .set_max_bytes(game_state.config.chunk_memory_budget_bytes);
         for info in unload_infos {
-            if let Some(data) = info.data_to_save {
-                if let Err(e) = game_state.chunk_cache.save_chunk(info.coord, &data) {
-                    eprintln!("Failed to save chunk {:?} during unload: {}", info.coord, e);
-                }
-                if game_state.world.contains(info.entity) {
-                    let _ = game_state.world.remove_one::<ChunkModified>(info.entity);
+            if let Some(data) = info.chunk_data {
+                if info.modified {
+                    if let Err(e) = game_state.chunk_cache.save_chunk(info.coord, &data) {
+                        // Keep the chunk loaded and modified so its edits survive until a
+                        // later unload pass manages to save it.
+                        eprintln!(
+                            "Failed to save chunk {:?} during unload, keeping it loaded: {}",
+                            info.coord, e
+                        );
+                        continue;
+                    }
+                    if game_state.world.contains(info.entity) {
+                        let _ = game_state.world.remove_one::<ChunkModified>(info.entity);
+                    }
                 }
+                // Saved, or unmodified and regenerated identically, so the memory copy can be
+                // evicted at any time.
+                let bytes = data.byte_size();
+                game_state
+                    .chunk_memory_cache
+                    .insert(info.coord, data, bytes);
             }
 
-            if let Some(mesh_id) = info.opaque_mesh_id_to_remove {
-                game_state.renderer.cleanup_mesh_buffers(mesh_id);
-                game_state.mesh_registry.remove_mesh(mesh_id);
-            }
-            if let Some(mesh_id) = info.transparent_mesh_id_to_remove {
+            for mesh_id in info.mesh_ids_to_remove {
                 game_state.renderer.cleanup_mesh_buffers(mesh_id);
                 game_state.mesh_registry.remove_mesh(mesh_id);
             }
@@ -178,16 +336,22 @@ impl ChunkLoadingSystem {
         }
     }
 
+    fn surface_chunk_y(
+        config: &Config,
+        world_generator: &WorldGenerator,
+        chunk_x: i32,
+        chunk_z: i32,
+    ) -> i32 {
+        let center_x = chunk_x * config.chunk_width as i32 + config.chunk_width as i32 / 2;
+        let center_z = chunk_z * config.chunk_depth as i32 + config.chunk_depth as i32 / 2;
+        let height = world_generator.calculate_terrain_height(center_x, center_z);
+        world_to_chunk_coords(config, 0, height, 0).1
+    }
+
     fn is_chunk_within_render_distance(&self, coord: ChunkCoord, game_state: &GameState) -> bool {
-        if let Some((cam_cx, cam_cz)) = self.last_camera_chunk_coord_xz {
-            let render_dist = game_state.config.render_distance;
-            let render_dist_sq = render_dist * render_dist;
-            let dx = coord.0 - cam_cx;
-            let dz = coord.2 - cam_cz;
-            dx * dx + dz * dz <= render_dist_sq
-        } else {
-            false
-        }
+        self.last_camera_chunk_coord.is_some_and(|camera_chunk| {
+            is_within_render_distance(&game_state.config, camera_chunk, coord)
+        })
     }
 
     fn mark_neighbors_dirty(&self, coord: ChunkCoord, game_state: &mut GameState) {
@@ -1,76 +1,74 @@
 use crate::components::{
-    chunk_coord_to_world_pos, world_to_chunk_coords, ChunkCoord, ChunkData, ChunkDirty, Renderable,
-    Transform, LOD,
+    chunk_coord_to_world_pos, chunk_view_priority, is_within_render_distance,
+    weighted_chunk_distance_sq, world_to_chunk_coords, ChunkCoord, ChunkData, ChunkDirty,
+    Renderable, Transform, LOD,
 };
-use crate::resources::ChunkMeshData;
+use crate::resources::{ChunkMeshData, FailureTracker, FrameBudget, Mesh, RequestQueue};
 use crate::state::GameState;
+use crossbeam_channel::TrySendError;
 use fnv::FnvHashSet;
 use hecs::Entity;
 use std::ops::Deref;
 
-type MeshResult = (Entity, ChunkCoord, Option<ChunkMeshData>, LOD);
-
-use crate::state::MeshRequestData;
+use crate::state::{MeshRequestData, MeshResultData};
 
 pub struct ChunkMeshingSystem {
     pending_mesh_requests: FnvHashSet<ChunkCoord>,
-    last_camera_chunk_coord_xz: Option<(i32, i32)>,
-    lod1_distance_sq: i32,
-    lod2_distance_sq: i32,
-    lod4_distance_sq: i32,
-    lod8_distance_sq: i32,
-    render_distance_sq: i32,
+    mesh_queue: RequestQueue<ChunkCoord, MeshRequestData>,
+    // A finished mesh that didn't fit in last frame's upload budget.
+    carried_result: Option<MeshResultData>,
+    last_camera_chunk_coord: Option<ChunkCoord>,
+    // Chunks whose meshing panicked. Errored chunks are only retried after another edit.
+    failures: FailureTracker<ChunkCoord>,
 }
 
 impl ChunkMeshingSystem {
     pub fn new() -> Self {
         Self {
             pending_mesh_requests: FnvHashSet::default(),
-            last_camera_chunk_coord_xz: None,
-            lod1_distance_sq: 0,
-            lod2_distance_sq: 0,
-            lod4_distance_sq: 0,
-            lod8_distance_sq: 0,
-            render_distance_sq: 0,
+            mesh_queue: RequestQueue::new(),
+            carried_result: None,
+            last_camera_chunk_coord: None,
+            failures: FailureTracker::default(),
         }
     }
 
-    pub fn update_lod_parameters(&mut self, game_state: &GameState) {
-        let camera_pos = game_state.camera.position;
-        let cam_chunk_x =
-            world_to_chunk_coords(&game_state.config, camera_pos.x.floor() as i32, 0, 0).0;
-        let cam_chunk_z =
-            world_to_chunk_coords(&game_state.config, 0, 0, camera_pos.z.floor() as i32).2;
-        self.last_camera_chunk_coord_xz = Some((cam_chunk_x, cam_chunk_z));
-
-        let lod1_dist = game_state.config.load_distance;
-        let lod2_dist = game_state.config.lod2_distance;
-        let lod4_dist = game_state.config.lod4_distance;
-        let lod8_dist = game_state.config.lod8_distance;
-        let render_dist = game_state.config.render_distance;
-
-        self.lod1_distance_sq = lod1_dist * lod1_dist;
-        self.lod2_distance_sq = lod2_dist * lod2_dist;
-        self.lod4_distance_sq = lod4_dist * lod4_dist;
-        self.lod8_distance_sq = lod8_dist * lod8_dist;
-        self.render_distance_sq = render_dist * render_dist;
+    pub fn queued_requests(&self) -> usize {
+        self.mesh_queue.len()
     }
 
-    pub fn process_mesh_results_and_requests(&mut self, game_state: &mut GameState) {
-        self.process_mesh_results(game_state);
-        let (requests_to_send, entities_processed) = self.collect_meshing_requests(game_state);
+    pub fn in_flight_requests(&self) -> usize {
+        self.pending_mesh_requests.len()
+    }
 
-        for request_data in requests_to_send {
-            let coord = request_data.1;
-            if game_state.mesh_request_tx.send(request_data).is_ok() {
-                self.pending_mesh_requests.insert(coord);
-            } else {
-                eprintln!(
-                    "Failed to send mesh request for {:?}, channel closed?",
-                    coord
-                );
-                continue;
-            }
+    pub fn errored_chunks(&self) -> usize {
+        self.failures.errored_count()
+    }
+
+    pub fn update_lod_parameters(&mut self, game_state: &GameState) {
+        let camera_pos = game_state.camera.position.floor();
+        self.last_camera_chunk_coord = Some(world_to_chunk_coords(
+            &game_state.config,
+            camera_pos.x as i32,
+            camera_pos.y as i32,
+            camera_pos.z as i32,
+        ));
+    }
+
+    pub fn process_mesh_results_and_requests(
+        &mut self,
+        game_state: &mut GameState,
+        upload_budget: &mut FrameBudget,
+    ) {
+        self.failures
+            .set_max_attempts(game_state.config.max_job_attempts);
+        self.process_mesh_results(game_state, upload_budget);
+        self.drop_meshes_past_render_distance(game_state);
+        let (requests_to_queue, entities_processed) = self.collect_meshing_requests(game_state);
+
+        for request_data in requests_to_queue {
+            // Real priorities are assigned when the queue is re-ranked before dispatch.
+            self.mesh_queue.push(request_data.1, 0.0, request_data);
         }
 
         for entity in entities_processed {
@@ -82,99 +80,187 @@ impl ChunkMeshingSystem {
                 }
             }
         }
+
+        self.dispatch_requests(game_state);
     }
 
-    fn process_mesh_results(&mut self, game_state: &mut GameState) {
-        let results_to_process: Vec<MeshResult> = game_state.mesh_result_rx.try_iter().collect();
+    // Drops queued and in-flight requests for chunks that were unloaded or whose LOD changed,
+    // re-ranks the rest for the current view and fills the free in-flight slots.
+    fn dispatch_requests(&mut self, game_state: &mut GameState) {
+        let Some(camera_chunk) = self.last_camera_chunk_coord else {
+            return;
+        };
 
-        for (entity, coord, maybe_chunk_mesh_data, generated_lod) in results_to_process {
+        let stale_requests: Vec<ChunkCoord> = self
+            .pending_mesh_requests
+            .iter()
+            .filter(|coord| !game_state.chunk_entity_map.contains_key(coord))
+            .copied()
+            .collect();
+        for coord in stale_requests {
             self.pending_mesh_requests.remove(&coord);
+            game_state.mesh_cancellations.cancel(coord);
+        }
+        let chunk_entity_map = &game_state.chunk_entity_map;
+        self.failures
+            .retain(|coord| chunk_entity_map.contains_key(coord));
 
-            if !game_state.world.contains(entity) {
-                continue;
+        if self.mesh_queue.is_empty() {
+            return;
+        }
+
+        let frustum = game_state.camera.frustum();
+        let config = &game_state.config;
+        let world = &game_state.world;
+        let cancelled = self.mesh_queue.reprioritize(|coord, request| {
+            let dist_sq = weighted_chunk_distance_sq(config, camera_chunk, *coord);
+            if !world.contains(request.0)
+                || LOD::for_distance_sq(dist_sq, config) != Some(request.4)
+            {
+                return None;
             }
+            Some(chunk_view_priority(config, &frustum, *coord, dist_sq))
+        });
+        // The chunk may have been marked clean when this request was queued; mark it dirty
+        // again so the next pass rebuilds it with current data at the right LOD.
+        for coord in cancelled {
+            if let Some(&entity) = game_state.chunk_entity_map.get(&coord) {
+                if game_state.world.contains(entity) {
+                    let _ = game_state.world.insert_one(entity, ChunkDirty);
+                }
+            }
+        }
 
-            let (existing_opaque_id, existing_transparent_id) =
-                match game_state.world.get::<&Renderable>(entity) {
-                    Ok(r) => (r.opaque_mesh_id, r.transparent_mesh_id),
-                    Err(_) => (None, None),
-                };
+        while self.pending_mesh_requests.len() < game_state.config.max_in_flight_mesh_requests {
+            let Some((coord, request_data)) = self.mesh_queue.pop() else {
+                break;
+            };
+            game_state.mesh_cancellations.revoke(&coord);
+            match game_state.mesh_request_tx.try_send(request_data) {
+                Ok(()) => {
+                    self.pending_mesh_requests.insert(coord);
+                }
+                Err(TrySendError::Full(request_data)) => {
+                    self.mesh_queue.push(coord, 0.0, request_data);
+                    break;
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    eprintln!(
+                        "Failed to send mesh request for {:?}, channel closed?",
+                        coord
+                    );
+                    break;
+                }
+            }
+        }
+    }
 
-            let mut final_opaque_mesh_id: Option<usize> = None;
-            let mut final_transparent_mesh_id: Option<usize> = None;
-            let mut needs_component_update = false;
+    fn process_mesh_results(&mut self, game_state: &mut GameState, budget: &mut FrameBudget) {
+        while let Some(result) = self
+            .carried_result
+            .take()
+            .or_else(|| game_state.mesh_result_rx.try_recv().ok())
+        {
+            let upload_bytes = match &result.2 {
+                Ok(chunk_mesh_data) => chunk_mesh_data.as_ref().map_or(0, ChunkMeshData::byte_size),
+                Err(_) => 0,
+            };
+            if !budget.try_spend(upload_bytes) {
+                self.carried_result = Some(result);
+                break;
+            }
 
-            match maybe_chunk_mesh_data {
-                Some(chunk_mesh_data) => {
-                    match chunk_mesh_data.opaque {
-                        Some(mesh) => {
-                            let new_id = Self::register_or_update_mesh(
-                                game_state,
-                                existing_opaque_id,
-                                mesh.vertices,
-                                mesh.indices,
-                            );
-                            if Self::upload_mesh_buffers(game_state, new_id) {
-                                final_opaque_mesh_id = Some(new_id);
-                                needs_component_update = true;
-                                if let Some(old_id) = existing_opaque_id {
-                                    if old_id != new_id {
-                                        Self::cleanup_mesh_resources(game_state, old_id);
-                                    }
-                                }
-                            } else {
-                                Self::cleanup_mesh_resources(game_state, new_id);
-                                if let Some(old_id) = existing_opaque_id {
-                                    Self::cleanup_mesh_resources(game_state, old_id);
-                                }
-                            }
-                        }
-                        None => {
-                            if let Some(old_id) = existing_opaque_id {
-                                Self::cleanup_mesh_resources(game_state, old_id);
-                                needs_component_update = true;
-                            }
-                        }
-                    }
+            let (entity, coord, maybe_chunk_mesh_data, visibility, generated_lod) = result;
+            self.pending_mesh_requests.remove(&coord);
+            game_state.mesh_cancellations.revoke(&coord);
 
-                    match chunk_mesh_data.transparent {
-                        Some(mesh) => {
-                            let new_id = Self::register_or_update_mesh(
-                                game_state,
-                                existing_transparent_id,
-                                mesh.vertices,
-                                mesh.indices,
-                            );
-                            if Self::upload_mesh_buffers(game_state, new_id) {
-                                final_transparent_mesh_id = Some(new_id);
-                                needs_component_update = true;
-                                if let Some(old_id) = existing_transparent_id {
-                                    if old_id != new_id {
-                                        Self::cleanup_mesh_resources(game_state, old_id);
-                                    }
-                                }
-                            } else {
-                                Self::cleanup_mesh_resources(game_state, new_id);
-                                if let Some(old_id) = existing_transparent_id {
-                                    Self::cleanup_mesh_resources(game_state, old_id);
-                                }
-                            }
-                        }
-                        None => {
-                            if let Some(old_id) = existing_transparent_id {
-                                Self::cleanup_mesh_resources(game_state, old_id);
-                                needs_component_update = true;
-                            }
-                        }
+            if !game_state.world.contains(entity) {
+                continue;
+            }
+            if self.last_camera_chunk_coord.is_some_and(|camera_chunk| {
+                !is_within_render_distance(&game_state.config, camera_chunk, coord)
+            }) {
+                continue;
+            }
+            let maybe_chunk_mesh_data = match maybe_chunk_mesh_data {
+                Ok(maybe_chunk_mesh_data) => {
+                    self.failures.clear(&coord);
+                    maybe_chunk_mesh_data
+                }
+                Err(_) => {
+                    // Keep whatever mesh the chunk had; a retry goes through the dirty path.
+                    if self.failures.record_failure(coord) {
+                        let _ = game_state.world.insert_one(entity, ChunkDirty);
+                    } else {
+                        eprintln!(
+                            "Giving up on meshing chunk {:?} after {} failed attempts",
+                            coord, game_state.config.max_job_attempts
+                        );
                     }
+                    continue;
+                }
+            };
+            if let Err(e) = game_state.world.insert_one(entity, visibility) {
+                eprintln!(
+                    "Failed to insert visibility for {:?} at {:?}: {}",
+                    entity, coord, e
+                );
+            }
+
+            let (
+                existing_opaque_id,
+                existing_transparent_id,
+                existing_water_id,
+                existing_shadow_id,
+            ) = match game_state.world.get::<&Renderable>(entity) {
+                Ok(r) => (
+                    r.opaque_mesh_id,
+                    r.transparent_mesh_id,
+                    r.water_mesh_id,
+                    r.shadow_mesh_id,
+                ),
+                Err(_) => (None, None, None, None),
+            };
 
-                    if needs_component_update
-                        || final_opaque_mesh_id.is_some()
+            match maybe_chunk_mesh_data {
+                Some(chunk_mesh_data) => {
+                    let (final_opaque_mesh_id, opaque_changed) = Self::apply_mesh_update(
+                        game_state,
+                        existing_opaque_id,
+                        chunk_mesh_data.opaque,
+                        coord,
+                    );
+                    let (final_transparent_mesh_id, transparent_changed) = Self::apply_mesh_update(
+                        game_state,
+                        existing_transparent_id,
+                        chunk_mesh_data.transparent,
+                        coord,
+                    );
+                    let (final_water_mesh_id, water_changed) = Self::apply_mesh_update(
+                        game_state,
+                        existing_water_id,
+                        chunk_mesh_data.water,
+                        coord,
+                    );
+                    let (final_shadow_mesh_id, shadow_changed) = Self::apply_mesh_update(
+                        game_state,
+                        existing_shadow_id,
+                        chunk_mesh_data.shadow,
+                        coord,
+                    );
+                    let needs_component_update =
+                        opaque_changed || transparent_changed || water_changed || shadow_changed;
+                    let has_visible_mesh = final_opaque_mesh_id.is_some()
                         || final_transparent_mesh_id.is_some()
-                    {
+                        || final_water_mesh_id.is_some();
+
+                    if needs_component_update || has_visible_mesh {
                         let world_pos = chunk_coord_to_world_pos(&game_state.config, coord);
-                        let new_renderable =
+                        let mut new_renderable =
                             Renderable::new(final_opaque_mesh_id, final_transparent_mesh_id);
+                        new_renderable.water_mesh_id = final_water_mesh_id;
+                        new_renderable.shadow_mesh_id = final_shadow_mesh_id;
+                        let new_mesh_ids: Vec<usize> = new_renderable.mesh_ids().collect();
                         let components = (
                             Transform::new(world_pos, glam::Vec3::ZERO, glam::Vec3::ONE),
                             new_renderable,
@@ -186,15 +272,11 @@ impl ChunkMeshingSystem {
                                 "Failed to insert render components for {:?} at {:?}: {}",
                                 entity, coord, e
                             );
-                            if let Some(id) = final_opaque_mesh_id {
-                                Self::cleanup_mesh_resources(game_state, id);
-                            }
-                            if let Some(id) = final_transparent_mesh_id {
+                            for id in new_mesh_ids {
                                 Self::cleanup_mesh_resources(game_state, id);
                             }
                         }
-                    } else if final_opaque_mesh_id.is_none() && final_transparent_mesh_id.is_none()
-                    {
+                    } else {
                         if let Err(e) = game_state
                             .world
                             .remove::<(Transform, Renderable, LOD)>(entity)
@@ -207,18 +289,15 @@ impl ChunkMeshingSystem {
                     }
                 }
                 None => {
-                    let mut opaque_id_to_remove: Option<usize> = None;
-                    let mut transparent_id_to_remove: Option<usize> = None;
-
-                    if let Ok(renderable_ref) = game_state.world.get::<&Renderable>(entity) {
-                        opaque_id_to_remove = renderable_ref.opaque_mesh_id;
-                        transparent_id_to_remove = renderable_ref.transparent_mesh_id;
-                    }
-
-                    if let Some(id) = opaque_id_to_remove {
-                        Self::cleanup_mesh_resources(game_state, id);
-                    }
-                    if let Some(id) = transparent_id_to_remove {
+                    for id in [
+                        existing_opaque_id,
+                        existing_transparent_id,
+                        existing_water_id,
+                        existing_shadow_id,
+                    ]
+                    .into_iter()
+                    .flatten()
+                    {
                         Self::cleanup_mesh_resources(game_state, id);
                     }
 
@@ -230,6 +309,47 @@ impl ChunkMeshingSystem {
         }
     }
 
+    // Registers and uploads `mesh`, replacing `existing_id`. Returns the id now in use and
+    // whether the chunk's `Renderable` has to be rewritten.
+    fn apply_mesh_update(
+        game_state: &mut GameState,
+        existing_id: Option<usize>,
+        mesh: Option<Mesh>,
+        coord: ChunkCoord,
+    ) -> (Option<usize>, bool) {
+        match mesh {
+            Some(mesh) => {
+                let new_id = Self::register_or_update_mesh(
+                    game_state,
+                    existing_id,
+                    mesh.vertices,
+                    mesh.indices,
+                );
+                if Self::upload_mesh_buffers(game_state, new_id, coord) {
+                    if let Some(old_id) = existing_id {
+                        if old_id != new_id {
+                            Self::cleanup_mesh_resources(game_state, old_id);
+                        }
+                    }
+                    (Some(new_id), true)
+                } else {
+                    Self::cleanup_mesh_resources(game_state, new_id);
+                    if let Some(old_id) = existing_id {
+                        Self::cleanup_mesh_resources(game_state, old_id);
+                    }
+                    (None, false)
+                }
+            }
+            None => match existing_id {
+                Some(old_id) => {
+                    Self::cleanup_mesh_resources(game_state, old_id);
+                    (None, true)
+                }
+                None => (None, false),
+            },
+        }
+    }
+
     fn register_or_update_mesh(
         game_state: &mut GameState,
         existing_id: Option<usize>,
@@ -242,14 +362,15 @@ impl ChunkMeshingSystem {
         }
     }
 
-    fn upload_mesh_buffers(game_state: &mut GameState, mesh_id: usize) -> bool {
+    fn upload_mesh_buffers(game_state: &mut GameState, mesh_id: usize, coord: ChunkCoord) -> bool {
         if let Some(mesh_data) = game_state.mesh_registry.meshes.get(&mesh_id) {
-            game_state.renderer.upload_mesh_buffers(
+            let origin = chunk_coord_to_world_pos(&game_state.config, coord);
+            game_state.renderer.upload_chunk_mesh(
                 mesh_id,
                 &mesh_data.vertices,
                 &mesh_data.indices,
-            );
-            true
+                origin,
+            )
         } else {
             eprintln!(
                 "Mesh data missing in registry after register/update for ID {}",
@@ -259,6 +380,36 @@ impl ChunkMeshingSystem {
         }
     }
 
+    // Chunks kept loaded in the unload padding band aren't drawn, since far terrain covers
+    // them there. Their meshes are freed and rebuilt if they come back into range.
+    fn drop_meshes_past_render_distance(&self, game_state: &mut GameState) {
+        let Some(camera_chunk) = self.last_camera_chunk_coord else {
+            return;
+        };
+        let past_range: Vec<Entity> = game_state
+            .world
+            .query::<(&ChunkCoord, &LOD)>()
+            .iter()
+            .filter(|(_entity, (coord, _lod))| {
+                !is_within_render_distance(&game_state.config, camera_chunk, **coord)
+            })
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in past_range {
+            let mesh_ids: Vec<usize> = match game_state.world.get::<&Renderable>(entity) {
+                Ok(renderable) => renderable.mesh_ids().collect(),
+                Err(_) => Vec::new(),
+            };
+            for id in mesh_ids {
+                Self::cleanup_mesh_resources(game_state, id);
+            }
+            let _ = game_state
+                .world
+                .remove::<(Transform, Renderable, LOD)>(entity);
+        }
+    }
+
     fn cleanup_mesh_resources(game_state: &mut GameState, mesh_id: usize) {
         game_state.renderer.cleanup_mesh_buffers(mesh_id);
         game_state.mesh_registry.remove_mesh(mesh_id);
@@ -271,8 +422,8 @@ impl ChunkMeshingSystem {
         let mut requests_to_send: Vec<MeshRequestData> = Vec::new();
         let mut entities_to_undirty = Vec::new();
 
-        let (cam_cx, cam_cz) = match self.last_camera_chunk_coord_xz {
-            Some(coords) => coords,
+        let camera_chunk = match self.last_camera_chunk_coord {
+            Some(coord) => coord,
             None => {
                 return (requests_to_send, entities_to_undirty);
             }
@@ -282,8 +433,10 @@ impl ChunkMeshingSystem {
             .world
             .query::<(&ChunkCoord, &ChunkData, Option<&ChunkDirty>, Option<&LOD>)>()
             .iter()
-            .filter(|(_entity, (coord, _data, _dirty, _lod))| {
+            .filter(|(_entity, (coord, _data, dirty, _lod))| {
                 !self.pending_mesh_requests.contains(coord)
+                    && (dirty.is_some()
+                        || (!self.mesh_queue.contains(coord) && !self.failures.is_errored(coord)))
             })
             .map(|(entity, (coord, data, dirty_opt, current_lod_opt))| {
                 (
@@ -297,25 +450,13 @@ impl ChunkMeshingSystem {
             .collect::<Vec<_>>();
 
         for (entity, chunk_coord, chunk_data, is_dirty, current_lod) in query_candidates {
-            let dx = chunk_coord.0 - cam_cx;
-            let dz = chunk_coord.2 - cam_cz;
-            let dist_sq = dx * dx + dz * dz;
-
-            if dist_sq > self.render_distance_sq {
+            let dist_sq = weighted_chunk_distance_sq(&game_state.config, camera_chunk, chunk_coord);
+            let Some(required_lod) = LOD::for_distance_sq(dist_sq, &game_state.config) else {
                 continue;
-            }
-
-            let required_lod = if dist_sq <= self.lod1_distance_sq {
-                LOD::LOD1
-            } else if dist_sq <= self.lod2_distance_sq {
-                LOD::LOD2
-            } else if dist_sq <= self.lod4_distance_sq {
-                LOD::LOD4
-            } else {
-                LOD::LOD8
             };
 
-            let required_neighbor_lods = self.get_neighbor_lods(chunk_coord, cam_cx, cam_cz);
+            let required_neighbor_lods =
+                self.get_neighbor_lods(chunk_coord, camera_chunk, game_state);
 
             let mut needs_remesh = false;
 
@@ -372,6 +513,7 @@ impl ChunkMeshingSystem {
                             chunk_data,
                             Box::new(neighbor_data),
                             required_lod,
+                            required_neighbor_lods,
                         ));
                         entities_to_undirty.push(entity);
                     }
@@ -435,7 +577,12 @@ impl ChunkMeshingSystem {
         }
     }
 
-    fn get_neighbor_lods(&self, coord: ChunkCoord, cam_cx: i32, cam_cz: i32) -> [LOD; 6] {
+    fn get_neighbor_lods(
+        &self,
+        coord: ChunkCoord,
+        camera_chunk: ChunkCoord,
+        game_state: &GameState,
+    ) -> [LOD; 6] {
         let neighbor_offsets = [
             (1, 0, 0),
             (-1, 0, 0),
@@ -449,19 +596,10 @@ impl ChunkMeshingSystem {
         for (i, offset) in neighbor_offsets.iter().enumerate() {
             let neighbor_coord =
                 ChunkCoord(coord.0 + offset.0, coord.1 + offset.1, coord.2 + offset.2);
-            let dx = neighbor_coord.0 - cam_cx;
-            let dz = neighbor_coord.2 - cam_cz;
-            let dist_sq = dx * dx + dz * dz;
-
-            neighbor_lods[i] = if dist_sq <= self.lod1_distance_sq {
-                LOD::LOD1
-            } else if dist_sq <= self.lod2_distance_sq {
-                LOD::LOD2
-            } else if dist_sq <= self.lod4_distance_sq {
-                LOD::LOD4
-            } else {
-                LOD::LOD8
-            }
+            let dist_sq =
+                weighted_chunk_distance_sq(&game_state.config, camera_chunk, neighbor_coord);
+            neighbor_lods[i] =
+                LOD::for_distance_sq(dist_sq, &game_state.config).unwrap_or(LOD::LOD8);
         }
         neighbor_lods
     }
@@ -0,0 +1,203 @@
+use crate::components::{BlockType, FallingBlock, Renderable, Transform};
+use crate::resources::BlockAccess;
+use crate::state::GameState;
+use glam::{IVec3, Vec3};
+use hecs::Entity;
+
+pub struct FallingBlockSystem {
+    last_update_time: Option<f32>,
+}
+
+impl FallingBlockSystem {
+    pub fn new() -> Self {
+        Self {
+            last_update_time: None,
+        }
+    }
+
+    pub fn update(&mut self, game_state: &mut GameState) {
+        let delta_time = match self.last_update_time {
+            Some(last) => game_state.total_time - last,
+            None => 0.0,
+        };
+        self.last_update_time = Some(game_state.total_time);
+
+        self.process_gravity_checks(game_state);
+        self.update_falling_blocks(game_state, delta_time);
+    }
+
+    fn process_gravity_checks(&self, game_state: &mut GameState) {
+        if game_state.scheduled_gravity_checks.is_empty() {
+            return;
+        }
+        let mut positions: Vec<IVec3> = game_state.scheduled_gravity_checks.drain().collect();
+        positions.sort_unstable_by_key(|pos| (pos.y, pos.x, pos.z));
+
+        for pos in positions {
+            let Some(block_type) = Self::starts_falling(game_state, pos) else {
+                continue;
+            };
+            if game_state.set_block_world(pos.x, pos.y, pos.z, BlockType::Air) {
+                Self::spawn_falling_block(game_state, pos, block_type);
+            }
+        }
+    }
+
+    // The block at `pos` if gravity pulls it down, which it does when the block below is
+    // loaded and open.
+    pub fn starts_falling(world: &impl BlockAccess, pos: IVec3) -> Option<BlockType> {
+        let block_type = world
+            .get_loaded_block_world(pos.x, pos.y, pos.z)
+            .filter(|block| block.is_affected_by_gravity())?;
+        let below = pos - IVec3::Y;
+        world
+            .get_loaded_block_world(below.x, below.y, below.z)
+            .is_some_and(|block| block.is_replaceable())
+            .then_some(block_type)
+    }
+
+    fn spawn_falling_block(game_state: &mut GameState, pos: IVec3, block_type: BlockType) {
+        let texture_layers = game_state.texture_manager.get_all_layers();
+        let opaque_mesh_id = match game_state
+            .mesh_generator
+            .generate_block_mesh(block_type, &texture_layers)
+        {
+            Some(mesh) => {
+                let mesh_id = game_state
+                    .mesh_registry
+                    .register_mesh(mesh.vertices, mesh.indices);
+                if let Some(mesh_data) = game_state.mesh_registry.meshes.get(&mesh_id) {
+                    game_state.renderer.upload_mesh_buffers(
+                        mesh_id,
+                        &mesh_data.vertices,
+                        &mesh_data.indices,
+                    );
+                }
+                Some(mesh_id)
+            }
+            None => None,
+        };
+
+        game_state.world.spawn((
+            FallingBlock::new(block_type),
+            Transform::new(pos.as_vec3(), Vec3::ZERO, Vec3::ONE),
+            Renderable::new(opaque_mesh_id, None),
+        ));
+    }
+
+    fn update_falling_blocks(&self, game_state: &mut GameState, delta_time: f32) {
+        let gravity = game_state.config.falling_block_gravity;
+        let max_speed = game_state.config.falling_block_max_speed;
+
+        let falling: Vec<(Entity, BlockType, Vec3, f32)> = game_state
+            .world
+            .query::<(&FallingBlock, &Transform)>()
+            .iter()
+            .map(|(entity, (falling, transform))| {
+                (
+                    entity,
+                    falling.block_type,
+                    transform.position,
+                    falling.velocity,
+                )
+            })
+            .collect();
+
+        for (entity, block_type, position, velocity) in falling {
+            let cell = Self::landing_cell(position);
+            let landed = match Self::is_supported(game_state, cell) {
+                Some(landed) => landed,
+                None => {
+                    // The chunk below isn't loaded, so the fall can't be resolved. Turn it back
+                    // into a block where it is rather than leaving the entity and its mesh alive.
+                    Self::land_falling_block(game_state, entity, cell, block_type);
+                    continue;
+                }
+            };
+
+            let next_velocity = (velocity + gravity * delta_time).min(max_speed);
+            let next_y = position.y - (next_velocity * delta_time).min(1.0);
+
+            if landed && next_y <= cell.y as f32 {
+                Self::land_falling_block(game_state, entity, cell, block_type);
+                continue;
+            }
+
+            if let Ok(mut falling_block) = game_state.world.get::<&mut FallingBlock>(entity) {
+                falling_block.velocity = next_velocity;
+            }
+            if let Ok(mut transform) = game_state.world.get::<&mut Transform>(entity) {
+                transform.position.y = if landed {
+                    next_y.max(cell.y as f32)
+                } else {
+                    next_y
+                };
+            }
+        }
+    }
+
+    // The cell a falling block at `position` occupies, rounded up so it comes to rest as soon
+    // as its bottom reaches the top of the block below.
+    pub fn landing_cell(position: Vec3) -> IVec3 {
+        IVec3::new(
+            position.x.round() as i32,
+            position.y.ceil() as i32,
+            position.z.round() as i32,
+        )
+    }
+
+    // Whether the block under `cell` stops a fall, or None if its chunk isn't loaded. The
+    // bottom of the world always does.
+    pub fn is_supported(world: &impl BlockAccess, cell: IVec3) -> Option<bool> {
+        let config = world.config();
+        let below = cell - IVec3::Y;
+        if below.y < config.min_chunk_y * config.chunk_height as i32 {
+            return Some(true);
+        }
+        world
+            .get_loaded_block_world(below.x, below.y, below.z)
+            .map(|block| !block.is_replaceable())
+    }
+
+    // Turns a landed block back into a world block, unless its cell has since filled with
+    // something solid. Returns whether it was placed.
+    pub fn place_landed_block(
+        world: &mut impl BlockAccess,
+        cell: IVec3,
+        block_type: BlockType,
+    ) -> bool {
+        let target_is_open = world
+            .get_loaded_block_world(cell.x, cell.y, cell.z)
+            .is_some_and(|block| block.is_replaceable());
+        target_is_open && world.set_block_world(cell.x, cell.y, cell.z, block_type)
+    }
+
+    fn land_falling_block(
+        game_state: &mut GameState,
+        entity: Entity,
+        cell: IVec3,
+        block_type: BlockType,
+    ) {
+        Self::place_landed_block(game_state, cell, block_type);
+
+        let opaque_mesh_id = game_state
+            .world
+            .get::<&Renderable>(entity)
+            .ok()
+            .and_then(|renderable| renderable.opaque_mesh_id);
+        if let Some(mesh_id) = opaque_mesh_id {
+            game_state.renderer.cleanup_mesh_buffers(mesh_id);
+            game_state.mesh_registry.remove_mesh(mesh_id);
+        }
+
+        if let Err(e) = game_state.world.despawn(entity) {
+            eprintln!("Error despawning falling block {:?}: {}", entity, e);
+        }
+    }
+}
+
+impl Default for FallingBlockSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
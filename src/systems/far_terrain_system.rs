@@ -0,0 +1,204 @@
+use crate::components::world_to_chunk_coords;
+use crate::persistence::FarTileResult;
+use crate::resources::{
+    far_tiles_in_range, FailureTracker, FarTerrainTile, FarTileCoord, FrameBudget, RequestQueue,
+};
+use crate::state::GameState;
+use crossbeam_channel::TrySendError;
+use fnv::FnvHashSet;
+use glam::Vec3;
+
+pub struct FarTerrainSystem {
+    // The camera's tile and chunk height; the tiles wanted change with either.
+    camera_cell: Option<(FarTileCoord, i32)>,
+    wanted_tiles: FnvHashSet<FarTileCoord>,
+    pending_tiles: FnvHashSet<FarTileCoord>,
+    request_queue: RequestQueue<FarTileCoord, ()>,
+    carried_result: Option<FarTileResult>,
+    failures: FailureTracker<FarTileCoord>,
+}
+
+impl FarTerrainSystem {
+    pub fn new() -> Self {
+        Self {
+            camera_cell: None,
+            wanted_tiles: FnvHashSet::default(),
+            pending_tiles: FnvHashSet::default(),
+            request_queue: RequestQueue::new(),
+            carried_result: None,
+            failures: FailureTracker::default(),
+        }
+    }
+
+    pub fn update(&mut self, game_state: &mut GameState, upload_budget: &mut FrameBudget) {
+        if !game_state.config.far_terrain_enabled {
+            if !game_state.far_terrain.tiles.is_empty() {
+                let tiles: Vec<FarTileCoord> =
+                    game_state.far_terrain.tiles.keys().copied().collect();
+                for tile in tiles {
+                    Self::remove_tile(game_state, tile);
+                }
+            }
+            // Keep draining so workers never block on a full result channel.
+            while game_state.far_tile_result_rx.try_recv().is_ok() {}
+            self.camera_cell = None;
+            self.wanted_tiles.clear();
+            self.pending_tiles.clear();
+            self.request_queue.clear();
+            self.carried_result = None;
+            self.failures.clear_all();
+            return;
+        }
+
+        self.failures
+            .set_max_attempts(game_state.config.max_job_attempts);
+        self.process_results(game_state, upload_budget);
+
+        let config = &game_state.config;
+        let camera_position = game_state.camera.position;
+        let size = config.far_terrain_tile_size as f32;
+        let camera_tile = (
+            (camera_position.x / size).floor() as i32,
+            (camera_position.z / size).floor() as i32,
+        );
+        let camera_chunk_y = world_to_chunk_coords(
+            config,
+            camera_position.x.floor() as i32,
+            camera_position.y.floor() as i32,
+            camera_position.z.floor() as i32,
+        )
+        .1;
+        if self.camera_cell != Some((camera_tile, camera_chunk_y)) {
+            self.camera_cell = Some((camera_tile, camera_chunk_y));
+            self.update_wanted_tiles(game_state, camera_position);
+        }
+
+        while let Some((tile, ())) = self.request_queue.pop() {
+            match game_state.far_tile_request_tx.try_send(tile) {
+                Ok(()) => {
+                    self.pending_tiles.insert(tile);
+                }
+                Err(TrySendError::Full(_)) => {
+                    // Queue order is by distance; requeueing at the front keeps it nearest-first.
+                    self.request_queue.push(tile, f32::MIN, ());
+                    break;
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    eprintln!("Failed to send far terrain request for tile {:?}", tile);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn update_wanted_tiles(&mut self, game_state: &mut GameState, camera_position: Vec3) {
+        let in_range = far_tiles_in_range(camera_position, &game_state.config);
+        self.wanted_tiles = in_range.iter().copied().collect();
+
+        let stale: Vec<FarTileCoord> = game_state
+            .far_terrain
+            .tiles
+            .keys()
+            .filter(|tile| !self.wanted_tiles.contains(tile))
+            .copied()
+            .collect();
+        for tile in stale {
+            Self::remove_tile(game_state, tile);
+        }
+
+        let wanted_tiles = &self.wanted_tiles;
+        self.failures.retain(|tile| wanted_tiles.contains(tile));
+        self.request_queue.clear();
+        for (rank, tile) in in_range.into_iter().enumerate() {
+            if game_state.far_terrain.tiles.contains_key(&tile)
+                || self.pending_tiles.contains(&tile)
+                || self.failures.is_errored(&tile)
+            {
+                continue;
+            }
+            self.request_queue.push(tile, rank as f32, ());
+        }
+    }
+
+    fn process_results(&mut self, game_state: &mut GameState, budget: &mut FrameBudget) {
+        while let Some((tile, mesh)) = self
+            .carried_result
+            .take()
+            .or_else(|| game_state.far_tile_result_rx.try_recv().ok())
+        {
+            let upload_bytes = mesh.as_ref().map_or(0, |mesh| mesh.byte_size());
+            if !budget.try_spend(upload_bytes) {
+                self.carried_result = Some((tile, mesh));
+                break;
+            }
+            self.pending_tiles.remove(&tile);
+            let mesh = match mesh {
+                Ok(mesh) => {
+                    self.failures.clear(&tile);
+                    mesh
+                }
+                Err(_) => {
+                    // Retries go to the back of the queue; the worker already logged the panic.
+                    if self.wanted_tiles.contains(&tile) && self.failures.record_failure(tile) {
+                        self.request_queue.push(tile, f32::MAX, ());
+                    }
+                    continue;
+                }
+            };
+            if !self.wanted_tiles.contains(&tile) || mesh.indices.is_empty() {
+                continue;
+            }
+
+            let (min_height, max_height) = mesh
+                .vertices
+                .chunks_exact(9)
+                .fold((f32::MAX, f32::MIN), |(min, max), vertex| {
+                    (min.min(vertex[1]), max.max(vertex[1]))
+                });
+
+            if let Some(old) = game_state.far_terrain.tiles.remove(&tile) {
+                game_state.renderer.cleanup_mesh_buffers(old.mesh_id);
+                game_state.mesh_registry.remove_mesh(old.mesh_id);
+            }
+
+            let mesh_id = game_state
+                .mesh_registry
+                .register_mesh(mesh.vertices, mesh.indices);
+            let Some(mesh_data) = game_state.mesh_registry.meshes.get(&mesh_id) else {
+                continue;
+            };
+            if !game_state.renderer.upload_chunk_mesh(
+                mesh_id,
+                &mesh_data.vertices,
+                &mesh_data.indices,
+                Vec3::ZERO,
+            ) {
+                eprintln!("Failed to upload far terrain tile {:?}", tile);
+                game_state.mesh_registry.remove_mesh(mesh_id);
+                continue;
+            }
+
+            game_state.far_terrain.tiles.insert(
+                tile,
+                FarTerrainTile {
+                    mesh_id,
+                    min_height,
+                    max_height,
+                },
+            );
+        }
+    }
+
+    fn remove_tile(game_state: &mut GameState, tile: FarTileCoord) {
+        if let Some(removed) = game_state.far_terrain.tiles.remove(&tile) {
+            game_state.renderer.cleanup_mesh_buffers(removed.mesh_id);
+            game_state.mesh_registry.remove_mesh(removed.mesh_id);
+        }
+    }
+}
+
+impl Default for FarTerrainSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
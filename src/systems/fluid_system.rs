@@ -0,0 +1,156 @@
+use crate::components::{
+    is_fluid_falling, is_fluid_source, BlockType, FLUID_FALLING_LEVEL, FLUID_MAX_FLOW_LEVEL,
+    FLUID_SOURCE_LEVEL,
+};
+use crate::resources::BlockAccess;
+use crate::state::GameState;
+use glam::IVec3;
+
+const HORIZONTAL_OFFSETS: [IVec3; 4] = [IVec3::X, IVec3::NEG_X, IVec3::Z, IVec3::NEG_Z];
+
+pub struct FluidSystem {
+    last_tick_time: f32,
+}
+
+impl FluidSystem {
+    pub fn new() -> Self {
+        Self {
+            last_tick_time: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, game_state: &mut GameState) {
+        if game_state.total_time - self.last_tick_time < game_state.config.fluid_tick_interval {
+            return;
+        }
+        self.last_tick_time = game_state.total_time;
+
+        if game_state.scheduled_fluid_updates.is_empty() {
+            return;
+        }
+
+        let max_updates = game_state.config.max_fluid_updates_per_tick;
+        let mut positions: Vec<IVec3> = game_state.scheduled_fluid_updates.drain().collect();
+        if positions.len() > max_updates {
+            positions.sort_unstable_by_key(|pos| (pos.y, pos.x, pos.z));
+            let deferred = positions.split_off(max_updates);
+            game_state.scheduled_fluid_updates.extend(deferred);
+        }
+
+        for pos in positions {
+            Self::update_fluid_block(game_state, pos);
+        }
+    }
+
+    // Applies the flow rules to the block at `pos`: flowing water takes its level from its
+    // neighbors or dries up, water falls into air below and otherwise spreads sideways.
+    pub fn update_fluid_block(world: &mut impl BlockAccess, pos: IVec3) {
+        if world.get_loaded_block_world(pos.x, pos.y, pos.z) != Some(BlockType::Water) {
+            return;
+        }
+
+        let mut level = world.get_fluid_level_world(pos.x, pos.y, pos.z);
+
+        if !is_fluid_source(level) {
+            match Self::calculate_flow_level(world, pos) {
+                Some(new_level) if new_level != level => {
+                    world.set_fluid_world(pos.x, pos.y, pos.z, BlockType::Water, new_level);
+                    level = new_level;
+                }
+                Some(_) => {}
+                None => {
+                    world.set_block_world(pos.x, pos.y, pos.z, BlockType::Air);
+                    return;
+                }
+            }
+        }
+
+        let below = pos - IVec3::Y;
+        match world.get_loaded_block_world(below.x, below.y, below.z) {
+            Some(BlockType::Air) => {
+                world.set_fluid_world(
+                    below.x,
+                    below.y,
+                    below.z,
+                    BlockType::Water,
+                    FLUID_FALLING_LEVEL,
+                );
+                return;
+            }
+            Some(BlockType::Water) | None => return,
+            Some(_) => {}
+        }
+
+        let spread_level = if is_fluid_source(level) || is_fluid_falling(level) {
+            FLUID_SOURCE_LEVEL + 1
+        } else {
+            level + 1
+        };
+        if spread_level > FLUID_MAX_FLOW_LEVEL {
+            return;
+        }
+
+        for offset in HORIZONTAL_OFFSETS {
+            let neighbor = pos + offset;
+            if world.get_loaded_block_world(neighbor.x, neighbor.y, neighbor.z)
+                == Some(BlockType::Air)
+            {
+                world.set_fluid_world(
+                    neighbor.x,
+                    neighbor.y,
+                    neighbor.z,
+                    BlockType::Water,
+                    spread_level,
+                );
+            }
+        }
+    }
+
+    fn calculate_flow_level(world: &impl BlockAccess, pos: IVec3) -> Option<u8> {
+        let above = pos + IVec3::Y;
+        if world.get_block_world(above.x, above.y, above.z) == BlockType::Water {
+            return Some(FLUID_FALLING_LEVEL);
+        }
+
+        let mut adjacent_sources = 0;
+        let mut lowest_neighbor_level: Option<u8> = None;
+        for offset in HORIZONTAL_OFFSETS {
+            let neighbor = pos + offset;
+            if world.get_block_world(neighbor.x, neighbor.y, neighbor.z) != BlockType::Water {
+                continue;
+            }
+            let neighbor_level = world.get_fluid_level_world(neighbor.x, neighbor.y, neighbor.z);
+            if is_fluid_source(neighbor_level) {
+                adjacent_sources += 1;
+            }
+            let effective_level = if is_fluid_falling(neighbor_level) {
+                FLUID_SOURCE_LEVEL
+            } else {
+                neighbor_level
+            };
+            lowest_neighbor_level =
+                Some(lowest_neighbor_level.map_or(effective_level, |l| l.min(effective_level)));
+        }
+
+        if adjacent_sources >= 2 {
+            let below = pos - IVec3::Y;
+            let below_block = world.get_block_world(below.x, below.y, below.z);
+            let below_is_source = below_block == BlockType::Water
+                && is_fluid_source(world.get_fluid_level_world(below.x, below.y, below.z));
+            if below_is_source || (below_block != BlockType::Air && below_block != BlockType::Water)
+            {
+                return Some(FLUID_SOURCE_LEVEL);
+            }
+        }
+
+        lowest_neighbor_level
+            .map(|l| l + 1)
+            .filter(|l| *l <= FLUID_MAX_FLOW_LEVEL)
+    }
+}
+
+impl Default for FluidSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
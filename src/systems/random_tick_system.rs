@@ -0,0 +1,211 @@
+use crate::components::{is_fluid_source, world_to_chunk_coords, BlockType, ChunkCoord};
+use crate::resources::BlockAccess;
+use crate::state::GameState;
+use fnv::FnvHashSet;
+use glam::IVec3;
+use rand::Rng;
+use std::collections::VecDeque;
+
+const NEIGHBOR_OFFSETS: [IVec3; 6] = [
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+pub struct RandomTickSystem {
+    last_tick_time: f32,
+}
+
+impl RandomTickSystem {
+    pub fn new() -> Self {
+        Self {
+            last_tick_time: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, game_state: &mut GameState) {
+        if game_state.total_time - self.last_tick_time < game_state.config.random_tick_interval {
+            return;
+        }
+        self.last_tick_time = game_state.total_time;
+
+        let camera_pos = game_state.camera.position;
+        let cam_chunk = world_to_chunk_coords(
+            &game_state.config,
+            camera_pos.x.floor() as i32,
+            0,
+            camera_pos.z.floor() as i32,
+        );
+        let tick_dist = game_state.config.random_tick_distance;
+        let tick_dist_sq = tick_dist * tick_dist;
+
+        let chunks_to_tick: Vec<ChunkCoord> = game_state
+            .chunk_entity_map
+            .keys()
+            .filter(|coord| {
+                let dx = coord.0 - cam_chunk.0;
+                let dz = coord.2 - cam_chunk.2;
+                dx * dx + dz * dz <= tick_dist_sq
+            })
+            .copied()
+            .collect();
+
+        let width = game_state.config.chunk_width as i32;
+        let height = game_state.config.chunk_height as i32;
+        let depth = game_state.config.chunk_depth as i32;
+        let mut rng = rand::rng();
+
+        for coord in chunks_to_tick {
+            for _ in 0..game_state.config.random_ticks_per_chunk {
+                let pos = IVec3::new(
+                    coord.0 * width + rng.random_range(0..width),
+                    coord.1 * height + rng.random_range(0..height),
+                    coord.2 * depth + rng.random_range(0..depth),
+                );
+                Self::tick_block(game_state, pos);
+            }
+        }
+    }
+
+    fn tick_block(game_state: &mut GameState, pos: IVec3) {
+        let block_type = match game_state.get_loaded_block_world(pos.x, pos.y, pos.z) {
+            Some(block_type) => block_type,
+            None => return,
+        };
+
+        match block_type {
+            BlockType::Air => Self::tick_air(game_state, pos),
+            BlockType::Dirt => Self::tick_dirt(game_state, pos),
+            BlockType::GrassyDirt => Self::tick_grassy_dirt(game_state, pos),
+            BlockType::SnowyDirt => Self::tick_snowy_dirt(game_state, pos),
+            BlockType::Water => Self::tick_water(game_state, pos),
+            BlockType::Ice => Self::tick_ice(game_state, pos),
+            BlockType::Leaves => Self::tick_leaves(game_state, pos),
+            _ => {}
+        }
+    }
+
+    // Snow settles on exposed solid tops above the snow line.
+    fn tick_air(game_state: &mut GameState, pos: IVec3) {
+        let below = pos - IVec3::Y;
+        let holds_snow = game_state
+            .get_loaded_block_world(below.x, below.y, below.z)
+            .is_some_and(|block| block.can_hold_snow());
+        if holds_snow
+            && game_state.world_generator.is_freezing(pos.x, pos.y, pos.z)
+            && game_state.is_exposed_to_sky(pos.x, pos.y, pos.z)
+        {
+            game_state.set_natural_block_world(pos.x, pos.y, pos.z, BlockType::Snow);
+        }
+    }
+
+    fn tick_dirt(game_state: &mut GameState, pos: IVec3) {
+        if Self::is_covered(game_state, pos) {
+            return;
+        }
+
+        let mut next_to_grass = false;
+        'search: for dy in -1..=1 {
+            for dz in -1..=1 {
+                for dx in -1..=1 {
+                    let neighbor = pos + IVec3::new(dx, dy, dz);
+                    if game_state.get_block_world(neighbor.x, neighbor.y, neighbor.z)
+                        == BlockType::GrassyDirt
+                    {
+                        next_to_grass = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+
+        if next_to_grass && game_state.is_exposed_to_sky(pos.x, pos.y, pos.z) {
+            let new_block = if game_state.world_generator.is_freezing(pos.x, pos.y, pos.z) {
+                BlockType::SnowyDirt
+            } else {
+                BlockType::GrassyDirt
+            };
+            game_state.set_natural_block_world(pos.x, pos.y, pos.z, new_block);
+        }
+    }
+
+    fn tick_grassy_dirt(game_state: &mut GameState, pos: IVec3) {
+        if Self::is_covered(game_state, pos) {
+            game_state.set_natural_block_world(pos.x, pos.y, pos.z, BlockType::Dirt);
+        } else if game_state.world_generator.is_freezing(pos.x, pos.y, pos.z)
+            && game_state.is_exposed_to_sky(pos.x, pos.y, pos.z)
+        {
+            game_state.set_natural_block_world(pos.x, pos.y, pos.z, BlockType::SnowyDirt);
+        }
+    }
+
+    fn tick_snowy_dirt(game_state: &mut GameState, pos: IVec3) {
+        if Self::is_covered(game_state, pos) {
+            return;
+        }
+        if !game_state.world_generator.is_freezing(pos.x, pos.y, pos.z) {
+            game_state.set_natural_block_world(pos.x, pos.y, pos.z, BlockType::GrassyDirt);
+        }
+    }
+
+    fn tick_water(game_state: &mut GameState, pos: IVec3) {
+        let level = game_state.get_fluid_level_world(pos.x, pos.y, pos.z);
+        let above = pos + IVec3::Y;
+        if is_fluid_source(level)
+            && game_state.get_block_world(above.x, above.y, above.z) == BlockType::Air
+            && game_state.world_generator.is_freezing(pos.x, pos.y, pos.z)
+            && game_state.is_exposed_to_sky(pos.x, pos.y, pos.z)
+        {
+            game_state.set_natural_block_world(pos.x, pos.y, pos.z, BlockType::Ice);
+        }
+    }
+
+    fn tick_ice(game_state: &mut GameState, pos: IVec3) {
+        if !game_state.world_generator.is_freezing(pos.x, pos.y, pos.z) {
+            game_state.set_natural_block_world(pos.x, pos.y, pos.z, BlockType::Water);
+        }
+    }
+
+    fn tick_leaves(game_state: &mut GameState, pos: IVec3) {
+        let max_distance = game_state.config.leaf_decay_distance;
+        let mut visited: FnvHashSet<IVec3> = FnvHashSet::default();
+        let mut queue: VecDeque<(IVec3, i32)> = VecDeque::new();
+        visited.insert(pos);
+        queue.push_back((pos, 0));
+
+        while let Some((current, distance)) = queue.pop_front() {
+            if distance >= max_distance {
+                continue;
+            }
+            for offset in NEIGHBOR_OFFSETS {
+                let neighbor = current + offset;
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                match game_state.get_loaded_block_world(neighbor.x, neighbor.y, neighbor.z) {
+                    Some(BlockType::Log) | None => return,
+                    Some(BlockType::Leaves) => queue.push_back((neighbor, distance + 1)),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        game_state.set_natural_block_world(pos.x, pos.y, pos.z, BlockType::Air);
+    }
+
+    fn is_covered(game_state: &GameState, pos: IVec3) -> bool {
+        let above = pos + IVec3::Y;
+        game_state
+            .get_block_world(above.x, above.y, above.z)
+            .is_culled_by()
+    }
+}
+
+impl Default for RandomTickSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
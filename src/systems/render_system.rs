@@ -1,11 +1,103 @@
 use crate::components::{
-    chunk_coord_to_aabb_center, get_chunk_extents, ChunkCoord, Renderable, Transform,
+    chunk_coord_to_aabb_center, chunk_coord_to_world_pos, get_chunk_extents, world_to_chunk_coords,
+    ChunkCoord, ChunkVisibility, Renderable, Transform,
 };
 use crate::gl;
-use crate::resources::Config;
+use crate::resources::{
+    calculate_shadow_cascades, far_tile_origin, fog_range, light_direction, light_level,
+    scattering_sky_color, sun_direction, ChunkDrawList, CloudMode, Config, Frustum, RenderStats,
+    ShaderProgram,
+};
 use crate::state::GameState;
-use glam::{Mat3, Mat4, Quat, Vec3};
-use std::f32::consts::PI;
+use fnv::FnvHashSet;
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3};
+use std::collections::VecDeque;
+
+const CHUNK_FACE_OFFSETS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+// Horizon colors the fog fades into, away from and towards the sun.
+struct FogParameters {
+    color: Vec3,
+    sun_color: Vec3,
+    sun_direction: Vec3,
+}
+
+impl FogParameters {
+    fn new(sun_direction: Vec3, sky_color: Vec3, config: &Config) -> Self {
+        if !config.scattering_sky {
+            return Self {
+                color: sky_color,
+                sun_color: sky_color,
+                sun_direction,
+            };
+        }
+
+        let sun_horizontal = Vec3::new(sun_direction.x, 0.0, sun_direction.z)
+            .try_normalize()
+            .unwrap_or(Vec3::X);
+        let towards_sun = (sun_horizontal + Vec3::Y * 0.05).normalize();
+        let away_from_sun = (-sun_horizontal + Vec3::Y * 0.05).normalize();
+        Self {
+            color: scattering_sky_color(away_from_sun, sun_direction, config),
+            sun_color: scattering_sky_color(towards_sun, sun_direction, config),
+            sun_direction,
+        }
+    }
+
+    fn set_uniforms(&self, shader: &ShaderProgram, config: &Config) {
+        let (fog_start, fog_end) = fog_range(config);
+        shader.set_uniform_bool("fogEnabled", config.fog_enabled);
+        shader.set_uniform_float("fogStart", fog_start);
+        shader.set_uniform_float("fogEnd", fog_end);
+        shader.set_uniform_vec3("fogColor", &self.color);
+        shader.set_uniform_vec3("fogSunColor", &self.sun_color);
+        shader.set_uniform_vec3("sunDirection", &self.sun_direction);
+        shader.set_uniform_float("heightFogDensity", config.height_fog_density);
+        shader.set_uniform_float("heightFogFalloff", config.height_fog_falloff);
+        shader.set_uniform_float("fogBaseHeight", config.sea_level as f32);
+    }
+}
+
+// Placement of the cloud layer for this frame: centered on the camera and scrolled by the wind.
+struct CloudLayer {
+    center: Vec2,
+    extent: f32,
+    offset: Vec2,
+    coverage: f32,
+}
+
+impl CloudLayer {
+    fn new(game_state: &GameState) -> Self {
+        let config = &game_state.config;
+        let drift = config.cloud_wind * game_state.total_time;
+        Self {
+            center: Vec2::new(game_state.camera.position.x, game_state.camera.position.z),
+            extent: game_state.camera.z_far(),
+            offset: Vec2::new(
+                drift.x.rem_euclid(config.cloud_scale),
+                drift.y.rem_euclid(config.cloud_scale),
+            ),
+            coverage: game_state.weather.cloud_coverage(config),
+        }
+    }
+
+    fn set_uniforms(&self, shader: &ShaderProgram, config: &Config, texture_unit: i32) {
+        shader.set_uniform_int("cloudTexture", texture_unit);
+        shader.set_uniform_vec2("cloudOffset", &self.offset);
+        shader.set_uniform_float("cloudScale", config.cloud_scale);
+        shader.set_uniform_float("cloudCoverage", self.coverage);
+        shader.set_uniform_vec2("cloudCenter", &self.center);
+        shader.set_uniform_float("cloudExtent", self.extent);
+        shader.set_uniform_float("cloudAltitude", config.cloud_altitude);
+    }
+}
 
 pub struct RenderSystem {}
 
@@ -18,7 +110,7 @@ impl RenderSystem {
         let config = &game_state.config;
         let time = game_state.time_of_day;
         let sky_color = calculate_sky_color(time, config);
-        let light_level = calculate_light_level(time, config);
+        let light_level = light_level(time, config);
         let night_factor = (1.0
             - (light_level - config.min_light_level)
                 / (config.max_light_level - config.min_light_level))
@@ -28,9 +120,7 @@ impl RenderSystem {
             + (config.max_ambient_intensity - config.min_ambient_intensity) * light_level;
         let ambient_color = sky_color * ambient_intensity;
 
-        let angle = time * 2.0 * PI;
-        let sun_dir = Vec3::new(angle.sin(), (angle + PI).cos(), 0.0).normalize();
-        let moon_dir = -sun_dir;
+        let sun_dir = sun_direction(time);
 
         let sun_color = Vec3::new(1.0, 0.98, 0.9);
         let moon_color = Vec3::new(0.15, 0.175, 0.25);
@@ -39,47 +129,21 @@ impl RenderSystem {
             / (config.max_light_level - config.min_light_level))
             .clamp(0.0, 1.0);
 
-        let light_direction = sun_dir.lerp(moon_dir, 1.0 - sun_blend_factor).normalize();
+        let light_direction = light_direction(time, config);
         let light_color = sun_color.lerp(moon_color, 1.0 - sun_blend_factor);
 
-        let shadow_distance_world = config.shadow_distance as f32 * config.chunk_width as f32;
-        let light_offset_distance = shadow_distance_world * 1.5;
-        let light_pos = game_state.camera.position - light_direction * light_offset_distance;
-
-        let light_view_matrix = Mat4::look_at_rh(light_pos, game_state.camera.position, Vec3::Y);
-
-        let shadow_map_world_size = shadow_distance_world * 2.0;
-        let world_units_per_texel = shadow_map_world_size / config.shadow_map_resolution as f32;
-
-        let shadow_area_center_world = game_state.camera.position;
-
-        let shadow_area_center_light_view =
-            light_view_matrix.transform_point3(shadow_area_center_world);
+        let weather_darkening = game_state.weather.darkening(config);
+        let lightning_flash = game_state.weather.lightning_flash();
+        let sky_color = apply_weather(sky_color, weather_darkening, lightning_flash);
+        let ambient_color = apply_weather(ambient_color, weather_darkening, lightning_flash * 0.5);
+        let light_color = light_color * (1.0 - weather_darkening);
 
-        let snapped_center_x = (shadow_area_center_light_view.x / world_units_per_texel).floor()
-            * world_units_per_texel;
-        let snapped_center_y = (shadow_area_center_light_view.y / world_units_per_texel).floor()
-            * world_units_per_texel;
+        let sky_color = to_scene_color(sky_color, config);
+        let ambient_color = to_scene_color(ambient_color, config);
+        let light_color = to_scene_color(light_color, config);
 
-        let half_size = shadow_distance_world;
-        let snapped_left = snapped_center_x - half_size;
-        let snapped_right = snapped_center_x + half_size;
-        let snapped_bottom = snapped_center_y - half_size;
-        let snapped_top = snapped_center_y + half_size;
-
-        let near_plane = 1.0;
-        let far_plane = light_offset_distance + shadow_distance_world;
-
-        let light_projection_matrix = Mat4::orthographic_rh(
-            snapped_left,
-            snapped_right,
-            snapped_bottom,
-            snapped_top,
-            far_plane,
-            near_plane,
-        );
-
-        game_state.light_space_matrix = light_projection_matrix * light_view_matrix;
+        game_state.shadow_cascades =
+            calculate_shadow_cascades(&mut game_state.camera, light_direction, config);
 
         let mut viewport = [0i32; 4];
         unsafe {
@@ -91,48 +155,130 @@ impl RenderSystem {
         let window_width = viewport[2];
         let window_height = viewport[3];
 
-        game_state.renderer.bind_shadow_fbo();
-
-        game_state.shadow_shader_program.use_program();
-        game_state
-            .shadow_shader_program
-            .set_uniform_mat4("lightSpaceMatrix", &game_state.light_space_matrix);
-
-        for (_entity, (transform, renderable, _chunk_coord)) in game_state
+        let mut stats = RenderStats::default();
+        let mut chunk_draws = ChunkDrawList::new();
+        let mut chunk_casters: Vec<(ChunkCoord, Option<usize>, Option<usize>)> = Vec::new();
+        let mut entity_draws: Vec<(gl::types::GLuint, i32, Mat4)> = Vec::new();
+        for (_entity, (transform, renderable, chunk_coord)) in game_state
             .world
-            .query::<(&Transform, &Renderable, &ChunkCoord)>()
+            .query::<(&Transform, &Renderable, Option<&ChunkCoord>)>()
             .iter()
         {
+            if let Some(chunk_coord) = chunk_coord {
+                if renderable.opaque_mesh_id.is_some() || renderable.shadow_mesh_id.is_some() {
+                    chunk_casters.push((
+                        *chunk_coord,
+                        renderable.opaque_mesh_id,
+                        renderable.shadow_mesh_id,
+                    ));
+                }
+                continue;
+            }
+
             if let Some(opaque_mesh_id) = renderable.opaque_mesh_id {
                 if let Some(mesh) = game_state.mesh_registry.meshes.get(&opaque_mesh_id) {
                     if let Some(vao) = game_state.renderer.vaos.get(&opaque_mesh_id) {
-                        let model_matrix = transform.model_matrix();
-                        game_state
-                            .shadow_shader_program
-                            .set_uniform_mat4("modelMatrix", &model_matrix);
-
-                        unsafe {
-                            game_state.renderer.gl.BindVertexArray(*vao);
-                            let index_count = mesh.indices.len() as i32;
-                            if index_count > 0 {
-                                game_state.renderer.gl.DrawElements(
-                                    gl::TRIANGLES,
-                                    index_count,
-                                    gl::UNSIGNED_INT,
-                                    std::ptr::null(),
-                                );
-                            }
+                        let index_count = mesh.indices.len() as i32;
+                        if index_count > 0 {
+                            entity_draws.push((*vao, index_count, transform.model_matrix()));
                         }
                     }
                 }
             }
         }
 
+        let cloud_layer = game_state
+            .config
+            .clouds_enabled
+            .then(|| CloudLayer::new(game_state));
+        if cloud_layer.is_some() {
+            game_state.renderer.bind_cloud_texture(gl::TEXTURE4);
+        }
+
+        let chunk_extents = get_chunk_extents(&game_state.config);
+        for (cascade_index, cascade) in game_state.shadow_cascades.iter().enumerate() {
+            game_state.shadow_shader_program.use_program();
+            // The nearest cascade always uses full-detail meshes so contact shadows stay tight.
+            let use_coarse_casters = cascade_index > 0;
+            let light_frustum = Frustum::from_matrix(cascade.light_space_matrix);
+
+            chunk_draws.clear();
+            for (chunk_coord, opaque_mesh_id, shadow_mesh_id) in &chunk_casters {
+                let mesh_id = if use_coarse_casters {
+                    shadow_mesh_id.or(*opaque_mesh_id)
+                } else {
+                    *opaque_mesh_id
+                };
+                let Some(mesh_id) = mesh_id else {
+                    continue;
+                };
+
+                let aabb_center = chunk_coord_to_aabb_center(&game_state.config, *chunk_coord);
+                if !light_frustum.intersects_aabb(aabb_center, chunk_extents) {
+                    stats.shadow_casters_culled += 1;
+                    continue;
+                }
+                stats.shadow_casters_drawn += 1;
+                game_state
+                    .renderer
+                    .push_chunk_draw(&mut chunk_draws, mesh_id);
+            }
+
+            game_state.renderer.bind_shadow_fbo(cascade_index);
+            game_state
+                .shadow_shader_program
+                .set_uniform_mat4("lightSpaceMatrix", &cascade.light_space_matrix);
+
+            for (vao, index_count, model_matrix) in &entity_draws {
+                game_state
+                    .shadow_shader_program
+                    .set_uniform_mat4("modelMatrix", model_matrix);
+                unsafe {
+                    game_state.renderer.gl.BindVertexArray(*vao);
+                    game_state.renderer.gl.DrawElements(
+                        gl::TRIANGLES,
+                        *index_count,
+                        gl::UNSIGNED_INT,
+                        std::ptr::null(),
+                    );
+                }
+            }
+
+            game_state
+                .shadow_shader_program
+                .set_uniform_mat4("modelMatrix", &Mat4::IDENTITY);
+            game_state.renderer.draw_chunk_batch(&chunk_draws);
+
+            if let Some(cloud_layer) = cloud_layer
+                .as_ref()
+                .filter(|_| game_state.config.cloud_shadows)
+            {
+                let cloud_shadow_shader = &game_state.cloud_shadow_shader_program;
+                cloud_shadow_shader.use_program();
+                cloud_shadow_shader
+                    .set_uniform_mat4("lightSpaceMatrix", &cascade.light_space_matrix);
+                cloud_layer.set_uniforms(cloud_shadow_shader, &game_state.config, 4);
+                unsafe {
+                    game_state.renderer.gl.Disable(gl::CULL_FACE);
+                }
+                game_state.renderer.draw_cloud_plane();
+                unsafe {
+                    game_state.renderer.gl.Enable(gl::CULL_FACE);
+                }
+            }
+        }
+
         game_state
             .renderer
             .unbind_shadow_fbo(window_width, window_height);
+        game_state.post_processing.begin_scene(&game_state.config);
 
-        game_state.renderer.clear(sky_color);
+        let mut fog = FogParameters::new(sun_dir, sky_color, &game_state.config);
+        if game_state.config.scattering_sky {
+            fog.color = apply_weather(fog.color, weather_darkening, lightning_flash);
+            fog.sun_color = apply_weather(fog.sun_color, weather_darkening, lightning_flash);
+        }
+        game_state.renderer.clear(fog.color);
 
         let view_matrix = game_state.camera.view_matrix();
         let projection_matrix = game_state.camera.projection_matrix();
@@ -140,6 +286,33 @@ impl RenderSystem {
         let camera_pos = game_state.camera.position;
         let camera_z_far = game_state.camera.z_far();
 
+        if game_state.config.scattering_sky {
+            let view_matrix_no_translation = Mat4::from_mat3(Mat3::from_mat4(view_matrix));
+            let inverse_view_projection =
+                (projection_matrix * view_matrix_no_translation).inverse();
+            let sky_shader = &game_state.sky_shader_program;
+            sky_shader.use_program();
+            sky_shader.set_uniform_mat4("inverseViewProjection", &inverse_view_projection);
+            sky_shader.set_uniform_vec3("sunDirection", &sun_dir);
+            sky_shader.set_uniform_float("sunIntensity", game_state.config.sky_sun_intensity);
+            sky_shader.set_uniform_float("mieAnisotropy", game_state.config.sky_mie_anisotropy);
+            sky_shader.set_uniform_vec3(
+                "nightColor",
+                &to_scene_color(game_state.config.midnight_color, &game_state.config),
+            );
+            sky_shader.set_uniform_float("weatherDarkening", weather_darkening);
+            sky_shader.set_uniform_float("lightningFlash", lightning_flash);
+            unsafe {
+                game_state.renderer.gl.Disable(crate::gl::DEPTH_TEST);
+                game_state.renderer.gl.DepthMask(crate::gl::FALSE);
+            }
+            game_state.renderer.draw_sky();
+            unsafe {
+                game_state.renderer.gl.Enable(crate::gl::DEPTH_TEST);
+                game_state.renderer.gl.DepthMask(crate::gl::TRUE);
+            }
+        }
+
         if night_factor > 0.0 {
             game_state.star_shader_program.use_program();
 
@@ -215,9 +388,29 @@ impl RenderSystem {
         game_state
             .shader_program
             .set_uniform_float("shininess", config.material_shininess);
+        fog.set_uniforms(&game_state.shader_program, config);
+        for (i, cascade) in game_state.shadow_cascades.iter().enumerate() {
+            game_state.shader_program.set_uniform_mat4(
+                &format!("lightSpaceMatrices[{}]", i),
+                &cascade.light_space_matrix,
+            );
+            game_state
+                .shader_program
+                .set_uniform_float(&format!("cascadeSplits[{}]", i), cascade.split_depth);
+            game_state
+                .shader_program
+                .set_uniform_float(&format!("cascadeBias[{}]", i), cascade.depth_bias);
+            game_state.shader_program.set_uniform_int(
+                &format!("cascadePcfRadius[{}]", i),
+                config.shadow_cascade_pcf_radius[i],
+            );
+        }
+        game_state
+            .shader_program
+            .set_uniform_int("cascadeCount", game_state.shadow_cascades.len() as i32);
         game_state
             .shader_program
-            .set_uniform_mat4("lightSpaceMatrix", &game_state.light_space_matrix);
+            .set_uniform_float("cascadeBlendFraction", config.shadow_cascade_blend_fraction);
         game_state.renderer.bind_shadow_map_texture(gl::TEXTURE1);
         game_state.shader_program.set_uniform_int("shadowMap", 1);
 
@@ -231,6 +424,11 @@ impl RenderSystem {
             .unwrap_or(0.0);
         let celestial_distance = camera_z_far * 0.9;
         let celestial_scale = camera_z_far * 0.05;
+        let sun_intensity = if config.hdr_enabled {
+            config.sun_brightness
+        } else {
+            1.0
+        };
 
         let moon_dir = -sun_dir;
 
@@ -257,6 +455,9 @@ impl RenderSystem {
         game_state
             .shader_program
             .set_uniform_float("celestialLayerIndex", sun_layer);
+        game_state
+            .shader_program
+            .set_uniform_float("celestialIntensity", sun_intensity);
         unsafe {
             game_state.renderer.gl.DrawElements(
                 crate::gl::TRIANGLES,
@@ -279,6 +480,9 @@ impl RenderSystem {
         game_state
             .shader_program
             .set_uniform_float("celestialLayerIndex", moon_layer);
+        game_state
+            .shader_program
+            .set_uniform_float("celestialIntensity", 1.0);
         unsafe {
             game_state.renderer.gl.DrawElements(
                 crate::gl::TRIANGLES,
@@ -300,14 +504,38 @@ impl RenderSystem {
         game_state.texture_manager.bind_texture_array(gl::TEXTURE0);
         game_state.shader_program.set_uniform_int("blockTexture", 0);
 
+        let visible_chunks = if game_state.config.occlusion_culling {
+            self.find_visible_chunks(game_state, &frustum)
+        } else {
+            None
+        };
+        chunk_draws.clear();
         for (_entity, (transform, renderable, chunk_coord)) in game_state
             .world
-            .query::<(&Transform, &Renderable, &ChunkCoord)>()
+            .query::<(&Transform, &Renderable, Option<&ChunkCoord>)>()
             .iter()
         {
-            let aabb_center = chunk_coord_to_aabb_center(&game_state.config, *chunk_coord);
-            let chunk_extents = get_chunk_extents(&game_state.config);
-            if !frustum.intersects_aabb(aabb_center, chunk_extents) {
+            if let Some(chunk_coord) = chunk_coord {
+                stats.chunks_considered += 1;
+                let aabb_center = chunk_coord_to_aabb_center(&game_state.config, *chunk_coord);
+                let chunk_extents = get_chunk_extents(&game_state.config);
+                if !frustum.intersects_aabb(aabb_center, chunk_extents) {
+                    stats.frustum_culled += 1;
+                    continue;
+                }
+                if visible_chunks
+                    .as_ref()
+                    .is_some_and(|visible| !visible.contains(chunk_coord))
+                {
+                    stats.occlusion_culled += 1;
+                    continue;
+                }
+                stats.chunks_drawn += 1;
+                if let Some(opaque_mesh_id) = renderable.opaque_mesh_id {
+                    game_state
+                        .renderer
+                        .push_chunk_draw(&mut chunk_draws, opaque_mesh_id);
+                }
                 continue;
             }
 
@@ -335,9 +563,30 @@ impl RenderSystem {
                 }
             }
         }
-        for (_entity, (transform, renderable, chunk_coord)) in game_state
+        game_state
+            .shader_program
+            .set_uniform_mat4("modelMatrix", &Mat4::IDENTITY);
+        game_state.renderer.draw_chunk_batch(&chunk_draws);
+
+        if game_state.config.far_terrain_enabled {
+            self.render_far_terrain(game_state, &frustum, &mut chunk_draws);
+        }
+
+        if let Some(cloud_layer) = &cloud_layer {
+            self.render_clouds(
+                game_state,
+                cloud_layer,
+                (window_width, window_height),
+                (light_direction, light_color, ambient_color),
+            );
+            game_state.shader_program.use_program();
+        }
+
+        let mut transparent_chunks: Vec<(f32, usize, ChunkCoord)> = Vec::new();
+        let mut water_chunks: Vec<(f32, usize, ChunkCoord)> = Vec::new();
+        for (_entity, (renderable, chunk_coord)) in game_state
             .world
-            .query::<(&Transform, &Renderable, &ChunkCoord)>()
+            .query::<(&Renderable, &ChunkCoord)>()
             .iter()
         {
             let aabb_center = chunk_coord_to_aabb_center(&game_state.config, *chunk_coord);
@@ -345,55 +594,456 @@ impl RenderSystem {
             if !frustum.intersects_aabb(aabb_center, chunk_extents) {
                 continue;
             }
+            if visible_chunks
+                .as_ref()
+                .is_some_and(|visible| !visible.contains(chunk_coord))
+            {
+                continue;
+            }
 
+            let distance = aabb_center.distance_squared(camera_pos);
             if let Some(transparent_mesh_id) = renderable.transparent_mesh_id {
-                if let Some(mesh) = game_state.mesh_registry.meshes.get(&transparent_mesh_id) {
-                    if let Some(vao) = game_state.renderer.vaos.get(&transparent_mesh_id) {
-                        let model_matrix = transform.model_matrix();
-                        game_state
-                            .shader_program
-                            .set_uniform_mat4("modelMatrix", &model_matrix);
-                        unsafe {
-                            game_state.renderer.gl.BindVertexArray(*vao);
-                            let index_count = mesh.indices.len() as i32;
-                            if index_count > 0 {
-                                game_state.renderer.gl.DrawElements(
-                                    crate::gl::TRIANGLES,
-                                    index_count,
-                                    crate::gl::UNSIGNED_INT,
-                                    std::ptr::null(),
-                                );
-                            }
-                        }
-                    }
-                }
+                transparent_chunks.push((distance, transparent_mesh_id, *chunk_coord));
+            }
+            if let Some(water_mesh_id) = renderable.water_mesh_id {
+                water_chunks.push((distance, water_mesh_id, *chunk_coord));
             }
         }
+        transparent_chunks.sort_by(|a, b| b.0.total_cmp(&a.0));
+        water_chunks.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut resort_chunks: Vec<(f32, usize, ChunkCoord)> = transparent_chunks
+            .iter()
+            .chain(water_chunks.iter())
+            .copied()
+            .collect();
+        resort_chunks.sort_by(|a, b| b.0.total_cmp(&a.0));
+        self.resort_transparent_meshes(game_state, &resort_chunks);
+
+        let draw_water = game_state.config.water_rendering && !water_chunks.is_empty();
+        if draw_water {
+            self.prepare_water(
+                game_state,
+                (window_width, window_height),
+                (light_direction, light_color, ambient_color),
+                &fog,
+            );
+        }
+
+        // Water and glass both blend over what is behind them, so they share one back to front
+        // order. Within a chunk the water goes first.
+        let mut blended_chunks: Vec<(f32, usize, bool)> = transparent_chunks
+            .iter()
+            .map(|&(distance, mesh_id, _)| (distance, mesh_id, false))
+            .chain(
+                water_chunks
+                    .iter()
+                    .filter(|_| draw_water)
+                    .map(|&(distance, mesh_id, _)| (distance, mesh_id, true)),
+            )
+            .collect();
+        blended_chunks.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.2.cmp(&a.2)));
+        for run in blended_chunks.chunk_by(|a, b| a.2 == b.2) {
+            if run[0].2 {
+                game_state.water_shader_program.use_program();
+            } else {
+                game_state.shader_program.use_program();
+            }
+            chunk_draws.clear();
+            for (_, mesh_id, _) in run {
+                game_state
+                    .renderer
+                    .push_chunk_draw(&mut chunk_draws, *mesh_id);
+            }
+            game_state.renderer.draw_chunk_batch(&chunk_draws);
+        }
+        game_state.shader_program.use_program();
+
+        if game_state.weather.precipitation_intensity() > 0.0 {
+            self.render_precipitation(game_state, (light_color, ambient_color));
+        }
 
         unsafe {
             game_state.renderer.gl.BindVertexArray(0);
         }
+
+        game_state
+            .post_processing
+            .finish(&game_state.config, game_state.total_time);
+
+        game_state.renderer.stats = stats;
+    }
+
+    // Clouds go after opaque terrain so mountains can poke through them. The flat layer is a
+    // depth-tested plane; the raymarched layer stops each ray at the captured scene depth.
+    fn render_clouds(
+        &self,
+        game_state: &mut GameState,
+        cloud_layer: &CloudLayer,
+        window_size: (i32, i32),
+        lighting: (Vec3, Vec3, Vec3),
+    ) {
+        let (light_direction, light_color, ambient_color) = lighting;
+        let view_matrix = game_state.camera.view_matrix();
+        let projection_matrix = game_state.camera.projection_matrix();
+
+        unsafe {
+            game_state.renderer.gl.DepthMask(gl::FALSE);
+            game_state.renderer.gl.Disable(gl::CULL_FACE);
+        }
+        game_state.renderer.bind_cloud_texture(gl::TEXTURE4);
+
+        match game_state.config.cloud_mode {
+            CloudMode::Flat => {
+                let cloud_shader = &game_state.cloud_shader_program;
+                cloud_shader.use_program();
+                cloud_shader.set_uniform_mat4("viewMatrix", &view_matrix);
+                cloud_shader.set_uniform_mat4("projectionMatrix", &projection_matrix);
+                cloud_shader.set_uniform_vec3("lightDirection", &light_direction);
+                cloud_shader.set_uniform_vec3("lightColor", &light_color);
+                cloud_shader.set_uniform_vec3("ambientColor", &ambient_color);
+                cloud_layer.set_uniforms(cloud_shader, &game_state.config, 4);
+                game_state.renderer.draw_cloud_plane();
+            }
+            CloudMode::Raymarched => {
+                game_state
+                    .renderer
+                    .copy_scene_to_textures(window_size.0, window_size.1);
+                game_state
+                    .renderer
+                    .bind_scene_textures(gl::TEXTURE2, gl::TEXTURE3);
+
+                let camera = &game_state.camera;
+                let camera_forward = (camera.target - camera.position).normalize();
+                let view_matrix_no_translation = Mat4::from_mat3(Mat3::from_mat4(view_matrix));
+                let inverse_view_projection =
+                    (projection_matrix * view_matrix_no_translation).inverse();
+
+                let cloud_shader = &game_state.cloud_raymarch_shader_program;
+                cloud_shader.use_program();
+                cloud_shader.set_uniform_mat4("inverseViewProjection", &inverse_view_projection);
+                cloud_shader.set_uniform_int("sceneDepth", 3);
+                cloud_shader.set_uniform_vec3("cameraPosition", &camera.position);
+                cloud_shader.set_uniform_vec3("cameraForward", &camera_forward);
+                cloud_shader.set_uniform_float("zNear", camera.z_near());
+                cloud_shader.set_uniform_float("zFar", camera.z_far());
+                cloud_shader.set_uniform_float("cloudThickness", game_state.config.cloud_thickness);
+                cloud_shader
+                    .set_uniform_int("raymarchSteps", game_state.config.cloud_raymarch_steps);
+                cloud_shader.set_uniform_vec3("lightDirection", &light_direction);
+                cloud_shader.set_uniform_vec3("lightColor", &light_color);
+                cloud_shader.set_uniform_vec3("ambientColor", &ambient_color);
+                cloud_layer.set_uniforms(cloud_shader, &game_state.config, 4);
+
+                unsafe {
+                    game_state.renderer.gl.Disable(gl::DEPTH_TEST);
+                }
+                game_state.renderer.draw_sky();
+                unsafe {
+                    game_state.renderer.gl.Enable(gl::DEPTH_TEST);
+                }
+            }
+        }
+
+        unsafe {
+            game_state.renderer.gl.Enable(gl::CULL_FACE);
+            game_state.renderer.gl.DepthMask(gl::TRUE);
+        }
+    }
+
+    // Rain streaks and snowflakes are generated entirely in the vertex shader; the heightmap
+    // of the highest block per column keeps them out of covered areas, and says which columns
+    // get snow rather than rain.
+    fn render_precipitation(&self, game_state: &mut GameState, lighting: (Vec3, Vec3)) {
+        let (light_color, ambient_color) = lighting;
+        if game_state.precipitation_heightmap.take_dirty() {
+            let heightmap = &game_state.precipitation_heightmap;
+            game_state
+                .renderer
+                .upload_precipitation_heightmap(heightmap.size(), &heightmap.texels());
+        }
+
+        let config = &game_state.config;
+        let weather = &game_state.weather;
+        let heightmap_origin = game_state.precipitation_heightmap.origin().as_vec2();
+        let shader = &game_state.precipitation_shader_program;
+        shader.use_program();
+        shader.set_uniform_mat4("viewMatrix", &game_state.camera.view_matrix());
+        shader.set_uniform_mat4("projectionMatrix", &game_state.camera.projection_matrix());
+        shader.set_uniform_vec3("cameraPosition", &game_state.camera.position);
+        shader.set_uniform_float("time", game_state.total_time);
+        shader.set_uniform_float("intensity", weather.precipitation_intensity());
+        shader.set_uniform_float("areaRadius", config.precipitation_radius as f32);
+        shader.set_uniform_float("areaHeight", config.precipitation_height);
+        shader.set_uniform_vec2("heightmapOrigin", &heightmap_origin);
+        shader.set_uniform_vec3("lightColor", &light_color);
+        shader.set_uniform_vec3("ambientColor", &ambient_color);
+        game_state
+            .renderer
+            .bind_precipitation_heightmap(gl::TEXTURE5);
+        shader.set_uniform_int("heightmap", 5);
+
+        unsafe {
+            game_state.renderer.gl.DepthMask(gl::FALSE);
+        }
+
+        shader.set_uniform_bool("isSnow", false);
+        shader.set_uniform_float("fallSpeed", config.rain_fall_speed);
+        shader.set_uniform_float("streakLength", config.rain_fall_speed * 0.04);
+        game_state
+            .renderer
+            .draw_procedural(gl::LINES, config.rain_particle_count * 2);
+
+        shader.set_uniform_bool("isSnow", true);
+        shader.set_uniform_float("fallSpeed", config.snow_fall_speed);
+        unsafe {
+            game_state.renderer.gl.Enable(gl::PROGRAM_POINT_SIZE);
+        }
+        game_state
+            .renderer
+            .draw_procedural(gl::POINTS, config.snow_particle_count);
+
+        unsafe {
+            game_state.renderer.gl.Disable(gl::PROGRAM_POINT_SIZE);
+            game_state.renderer.gl.DepthMask(gl::TRUE);
+        }
+    }
+
+    // Water is drawn after the opaque pass so it can sample a copy of the scene color and
+    // depth for refraction, absorption and screen-space reflections.
+    fn prepare_water(
+        &self,
+        game_state: &mut GameState,
+        window_size: (i32, i32),
+        lighting: (Vec3, Vec3, Vec3),
+        fog: &FogParameters,
+    ) {
+        let (light_direction, light_color, ambient_color) = lighting;
+        game_state
+            .renderer
+            .copy_scene_to_textures(window_size.0, window_size.1);
+
+        let config = &game_state.config;
+        let water_shader = &game_state.water_shader_program;
+        water_shader.use_program();
+        water_shader.set_uniform_mat4("viewMatrix", &game_state.camera.view_matrix());
+        water_shader.set_uniform_mat4("projectionMatrix", &game_state.camera.projection_matrix());
+        water_shader.set_uniform_vec3("cameraPosition", &game_state.camera.position);
+        water_shader.set_uniform_float("zNear", game_state.camera.z_near());
+        water_shader.set_uniform_float("zFar", game_state.camera.z_far());
+        water_shader.set_uniform_vec3("lightDirection", &light_direction);
+        water_shader.set_uniform_vec3("lightColor", &light_color);
+        water_shader.set_uniform_vec3("ambientColor", &ambient_color);
+        water_shader.set_uniform_vec3("skyColor", &fog.color);
+        fog.set_uniforms(water_shader, config);
+        water_shader.set_uniform_float("shininess", config.material_shininess);
+        water_shader.set_uniform_float("time", game_state.total_time);
+        water_shader.set_uniform_vec3("waterColor", &to_scene_color(config.water_color, config));
+        water_shader.set_uniform_float("waterAbsorption", config.water_absorption);
+        water_shader.set_uniform_float("waveStrength", config.water_wave_strength);
+        water_shader.set_uniform_float("waveSpeed", config.water_wave_speed);
+        water_shader.set_uniform_float("refractionStrength", config.water_refraction_strength);
+
+        game_state.texture_manager.bind_texture_array(gl::TEXTURE0);
+        water_shader.set_uniform_int("blockTexture", 0);
+        game_state
+            .renderer
+            .bind_scene_textures(gl::TEXTURE2, gl::TEXTURE3);
+        water_shader.set_uniform_int("sceneColor", 2);
+        water_shader.set_uniform_int("sceneDepth", 3);
+    }
+
+    fn render_far_terrain(
+        &self,
+        game_state: &mut GameState,
+        frustum: &Frustum,
+        chunk_draws: &mut ChunkDrawList,
+    ) {
+        let tile_size = game_state.config.far_terrain_tile_size as f32;
+        chunk_draws.clear();
+        for (tile, far_tile) in &game_state.far_terrain.tiles {
+            let origin = far_tile_origin(*tile, &game_state.config);
+            let half_height = (far_tile.max_height - far_tile.min_height) * 0.5;
+            let center = Vec3::new(
+                origin.x + tile_size * 0.5,
+                far_tile.min_height + half_height,
+                origin.y + tile_size * 0.5,
+            );
+            let extents = Vec3::new(tile_size * 0.5, half_height + 0.5, tile_size * 0.5);
+            if !frustum.intersects_aabb(center, extents) {
+                continue;
+            }
+            game_state
+                .renderer
+                .push_chunk_draw(chunk_draws, far_tile.mesh_id);
+        }
+
+        game_state
+            .shader_program
+            .set_uniform_bool("isFarTerrain", true);
+        let config = &game_state.config;
+        let camera_pos = game_state.camera.position;
+        let camera_chunk = world_to_chunk_coords(
+            config,
+            camera_pos.x.floor() as i32,
+            camera_pos.y.floor() as i32,
+            camera_pos.z.floor() as i32,
+        );
+        let shader = &game_state.shader_program;
+        shader.set_uniform_vec3(
+            "cameraChunk",
+            &Vec3::new(
+                camera_chunk.0 as f32,
+                camera_chunk.1 as f32,
+                camera_chunk.2 as f32,
+            ),
+        );
+        shader.set_uniform_vec3(
+            "chunkSize",
+            &Vec3::new(
+                config.chunk_width as f32,
+                config.chunk_height as f32,
+                config.chunk_depth as f32,
+            ),
+        );
+        shader.set_uniform_float("chunkRenderDistance", config.render_distance as f32);
+        shader.set_uniform_float("lodVerticalWeight", config.lod_vertical_weight);
+        game_state.renderer.draw_chunk_batch(chunk_draws);
+        game_state
+            .shader_program
+            .set_uniform_bool("isFarTerrain", false);
+    }
+
+    // Cave culling: breadth-first walk outwards from the camera chunk, only stepping from
+    // the face a chunk was entered through to faces its see-through blocks connect to, and
+    // never back towards the camera. Chunks that are never reached cannot be seen.
+    fn find_visible_chunks(
+        &self,
+        game_state: &GameState,
+        frustum: &Frustum,
+    ) -> Option<FnvHashSet<ChunkCoord>> {
+        let config = &game_state.config;
+        let camera_pos = game_state.camera.position;
+        let start = world_to_chunk_coords(
+            config,
+            camera_pos.x.floor() as i32,
+            camera_pos.y.floor() as i32,
+            camera_pos.z.floor() as i32,
+        );
+        if !game_state.chunk_entity_map.contains_key(&start) {
+            return None;
+        }
+
+        let chunk_extents = get_chunk_extents(config);
+        let mut visible: FnvHashSet<ChunkCoord> = FnvHashSet::default();
+        let mut queue: VecDeque<(ChunkCoord, Option<usize>, u8)> = VecDeque::new();
+        visible.insert(start);
+        queue.push_back((start, None, 0));
+
+        while let Some((coord, entry_face, directions)) = queue.pop_front() {
+            let visibility = game_state
+                .chunk_entity_map
+                .get(&coord)
+                .and_then(|entity| game_state.world.get::<&ChunkVisibility>(*entity).ok())
+                .map(|visibility| *visibility)
+                .unwrap_or_default();
+
+            for (face, offset) in CHUNK_FACE_OFFSETS.iter().enumerate() {
+                if directions & (1 << ChunkVisibility::opposite_face(face)) != 0 {
+                    continue;
+                }
+                if let Some(entry_face) = entry_face {
+                    if !visibility.is_connected(entry_face, face) {
+                        continue;
+                    }
+                }
+
+                let neighbor =
+                    ChunkCoord(coord.0 + offset.0, coord.1 + offset.1, coord.2 + offset.2);
+                if visible.contains(&neighbor)
+                    || !game_state.chunk_entity_map.contains_key(&neighbor)
+                {
+                    continue;
+                }
+                let aabb_center = chunk_coord_to_aabb_center(config, neighbor);
+                if !frustum.intersects_aabb(aabb_center, chunk_extents) {
+                    continue;
+                }
+
+                visible.insert(neighbor);
+                queue.push_back((
+                    neighbor,
+                    Some(ChunkVisibility::opposite_face(face)),
+                    directions | (1 << face),
+                ));
+            }
+        }
+
+        Some(visible)
+    }
+
+    // Re-sorts faces inside nearby transparent meshes whenever the camera has moved to a
+    // different block since the last sort. `chunks` is ordered far to near, so the
+    // per-frame budget goes to the closest chunks first.
+    fn resort_transparent_meshes(
+        &self,
+        game_state: &mut GameState,
+        chunks: &[(f32, usize, ChunkCoord)],
+    ) {
+        let camera_pos = game_state.camera.position;
+        let camera_cell = camera_pos.floor().as_ivec3();
+        let cam_chunk = world_to_chunk_coords(
+            &game_state.config,
+            camera_cell.x,
+            camera_cell.y,
+            camera_cell.z,
+        );
+        let sort_distance = game_state.config.transparent_sort_distance;
+        let mut budget = game_state.config.max_transparent_resorts_per_frame;
+
+        for (_, mesh_id, chunk_coord) in chunks.iter().rev() {
+            if budget == 0 {
+                break;
+            }
+            let in_range = (chunk_coord.0 - cam_chunk.0).abs() <= sort_distance
+                && (chunk_coord.1 - cam_chunk.1).abs() <= sort_distance
+                && (chunk_coord.2 - cam_chunk.2).abs() <= sort_distance;
+            let needs_sort =
+                game_state.renderer.transparent_sort_cells.get(mesh_id) != Some(&camera_cell);
+            if !in_range || !needs_sort {
+                continue;
+            }
+
+            if let Some(mesh) = game_state.mesh_registry.meshes.get_mut(mesh_id) {
+                let origin = chunk_coord_to_world_pos(&game_state.config, *chunk_coord);
+                mesh.sort_triangles_back_to_front(camera_pos - origin);
+                if game_state
+                    .renderer
+                    .update_chunk_indices(*mesh_id, &mesh.indices)
+                {
+                    game_state
+                        .renderer
+                        .transparent_sort_cells
+                        .insert(*mesh_id, camera_cell);
+                }
+            }
+            budget -= 1;
+        }
     }
 }
 
-fn calculate_light_level(time: f32, config: &Config) -> f32 {
-    let sunrise_start = config.sunrise_center_time - config.day_night_transition_duration;
-    let sunrise_end = config.sunrise_center_time + config.day_night_transition_duration;
-    let sunset_start = config.sunset_center_time - config.day_night_transition_duration;
-    let sunset_end = config.sunset_center_time + config.day_night_transition_duration;
+// Overcast weather desaturates and darkens a color; lightning briefly brightens it.
+fn apply_weather(color: Vec3, darkening: f32, flash: f32) -> Vec3 {
+    let grey = Vec3::splat((color.x + color.y + color.z) / 3.0);
+    color.lerp(grey, darkening) * (1.0 - darkening) + Vec3::splat(flash)
+}
 
-    if (sunrise_end..sunset_start).contains(&time) {
-        config.max_light_level
-    } else if (sunrise_start..sunrise_end).contains(&time) {
-        let factor = (time - sunrise_start) / (sunrise_end - sunrise_start);
-        config.min_light_level
-            + (config.max_light_level - config.min_light_level) * factor.clamp(0.0, 1.0)
-    } else if (sunset_start..sunset_end).contains(&time) {
-        let factor = (time - sunset_start) / (sunset_end - sunset_start);
-        config.max_light_level
-            - (config.max_light_level - config.min_light_level) * factor.clamp(0.0, 1.0)
+// The configured colors are authored in display space; the HDR target wants linear light.
+fn to_scene_color(color: Vec3, config: &Config) -> Vec3 {
+    if config.hdr_enabled {
+        color.max(Vec3::ZERO).powf(config.gamma)
     } else {
-        config.min_light_level
+        color
     }
 }
 
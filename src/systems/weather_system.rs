@@ -0,0 +1,98 @@
+use crate::components::{world_to_chunk_coords, world_to_local_coords, BlockType, ChunkData};
+use crate::resources::PrecipitationHeightmap;
+use crate::state::GameState;
+use glam::IVec2;
+
+pub struct WeatherSystem {
+    last_update_time: f32,
+    last_heightmap_time: f32,
+    heightmap_center: Option<IVec2>,
+}
+
+impl WeatherSystem {
+    pub fn new() -> Self {
+        Self {
+            last_update_time: 0.0,
+            last_heightmap_time: 0.0,
+            heightmap_center: None,
+        }
+    }
+
+    pub fn update(&mut self, game_state: &mut GameState) {
+        if !game_state.config.weather_enabled {
+            return;
+        }
+        let delta_time = game_state.total_time - self.last_update_time;
+        self.last_update_time = game_state.total_time;
+
+        let mut rng = rand::rng();
+        game_state
+            .weather
+            .update(delta_time, &game_state.config, &mut rng);
+
+        // Nothing is falling, so the heightmap can go stale until it is needed again.
+        if game_state.weather.precipitation_intensity() <= 0.0 {
+            self.heightmap_center = None;
+            return;
+        }
+        let kind = game_state.weather.precipitation_kind();
+        game_state
+            .precipitation_heightmap
+            .set_precipitation_kind(kind);
+
+        let camera_pos = game_state.camera.position;
+        let center = IVec2::new(camera_pos.x.floor() as i32, camera_pos.z.floor() as i32);
+        let refresh_due = game_state.total_time - self.last_heightmap_time
+            >= game_state.config.heightmap_refresh_interval;
+        let columns = if self.heightmap_center.is_none() || refresh_due {
+            game_state.precipitation_heightmap.recenter(center);
+            self.last_heightmap_time = game_state.total_time;
+            game_state.precipitation_heightmap.columns()
+        } else if self.heightmap_center != Some(center) {
+            game_state.precipitation_heightmap.recenter(center)
+        } else {
+            return;
+        };
+        self.heightmap_center = Some(center);
+
+        for column in columns {
+            Self::update_column(game_state, column);
+        }
+    }
+
+    // Walks the column's loaded chunks from the top of the world down to its highest block.
+    fn update_column(game_state: &mut GameState, column: IVec2) {
+        let config = &game_state.config;
+        let (local_x, _, local_z) = world_to_local_coords(config, column.x, 0, column.y);
+        let chunk_height = config.chunk_height as i32;
+        let mut height = PrecipitationHeightmap::NO_GROUND;
+
+        'chunks: for chunk_y in (config.min_chunk_y..=config.max_chunk_y).rev() {
+            let chunk_coord =
+                world_to_chunk_coords(config, column.x, chunk_y * chunk_height, column.y);
+            let Some(entity) = game_state.chunk_entity_map.get(&chunk_coord) else {
+                continue;
+            };
+            let Ok(chunk_data) = game_state.world.get::<&ChunkData>(*entity) else {
+                continue;
+            };
+            for local_y in (0..config.chunk_height).rev() {
+                if chunk_data.get_block(config, local_x, local_y, local_z) != BlockType::Air {
+                    height = (chunk_y * chunk_height + local_y as i32) as f32;
+                    break 'chunks;
+                }
+            }
+        }
+
+        let snow_line = game_state.world_generator.snow_line(column.x, column.y);
+        game_state
+            .precipitation_heightmap
+            .set_column(column.x, column.y, height, snow_line);
+    }
+}
+
+impl Default for WeatherSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,44 @@
+use crate::resources::{JobCounts, JobKind, JobStats};
+use crate::state::GameState;
+
+pub struct WorkerStatsSystem {
+    last_sample_time: f32,
+    last_counts: [JobCounts; 3],
+}
+
+impl WorkerStatsSystem {
+    pub fn new() -> Self {
+        Self {
+            last_sample_time: 0.0,
+            last_counts: [JobCounts::default(); 3],
+        }
+    }
+
+    pub fn update(&mut self, game_state: &mut GameState) {
+        let elapsed = game_state.total_time - self.last_sample_time;
+        if elapsed < game_state.config.worker_stats_interval {
+            return;
+        }
+        self.last_sample_time = game_state.total_time;
+        game_state.worker_report.respawned_workers += game_state.respawn_dead_workers();
+
+        let (generation_threads, meshing_threads) = game_state.worker_thread_counts();
+        for (i, kind) in JobKind::ALL.into_iter().enumerate() {
+            let (threads, queue_len) = match kind {
+                JobKind::Generation => (generation_threads, game_state.gen_request_tx.len()),
+                JobKind::Meshing => (meshing_threads, game_state.mesh_request_tx.len()),
+                JobKind::FarTerrain => (generation_threads, game_state.far_tile_request_tx.len()),
+            };
+            let counts = game_state.worker_stats.counts(kind);
+            *game_state.worker_report.get_mut(kind) =
+                JobStats::between(self.last_counts[i], counts, elapsed, threads, queue_len);
+            self.last_counts[i] = counts;
+        }
+    }
+}
+
+impl Default for WorkerStatsSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -14,7 +14,7 @@ use winit::event_loop::ActiveEventLoop;
 use winit::window::{CursorGrabMode, Window};
 
 pub enum GlDisplayCreationState {
-    Builder(DisplayBuilder),
+    Builder(Box<DisplayBuilder>),
     Init,
 }
 
@@ -36,7 +36,7 @@ impl WindowManager {
             .with_window_attributes(Some(Window::default_attributes().with_title("Meinkraft")));
         Self {
             template,
-            gl_display: GlDisplayCreationState::Builder(display_builder),
+            gl_display: GlDisplayCreationState::Builder(Box::new(display_builder)),
             gl_context: None,
             state: None,
         }
@@ -181,6 +181,12 @@ impl WindowManager {
         }
     }
 
+    pub fn set_title(&self, title: &str) {
+        if let Some(state) = &self.state {
+            state.window.set_title(title);
+        }
+    }
+
     pub fn get_dimensions(&self) -> Option<(u32, u32)> {
         self.state.as_ref().map(|s| {
             let size = s.window.inner_size();
@@ -0,0 +1,130 @@
+use meinkraft::resources::{ArenaAllocation, BufferArena};
+
+#[test]
+fn allocations_are_contiguous_and_disjoint() {
+    let mut arena = BufferArena::new(100);
+    let a = arena.allocate(10).unwrap();
+    let b = arena.allocate(20).unwrap();
+    let c = arena.allocate(30).unwrap();
+
+    assert_eq!(
+        a,
+        ArenaAllocation {
+            offset: 0,
+            size: 10
+        }
+    );
+    assert_eq!(
+        b,
+        ArenaAllocation {
+            offset: 10,
+            size: 20
+        }
+    );
+    assert_eq!(
+        c,
+        ArenaAllocation {
+            offset: 30,
+            size: 30
+        }
+    );
+    assert_eq!(arena.used(), 60);
+    assert_eq!(arena.largest_free_block(), 40);
+}
+
+#[test]
+fn allocation_fails_when_no_block_fits() {
+    let mut arena = BufferArena::new(50);
+    assert!(arena.allocate(0).is_none());
+    assert!(arena.allocate(51).is_none());
+    assert!(arena.allocate(50).is_some());
+    assert!(arena.allocate(1).is_none());
+    assert_eq!(arena.free_block_count(), 0);
+}
+
+#[test]
+fn freed_ranges_are_reused_first_fit() {
+    let mut arena = BufferArena::new(100);
+    let a = arena.allocate(10).unwrap();
+    let _b = arena.allocate(10).unwrap();
+    arena.free(a);
+
+    let c = arena.allocate(5).unwrap();
+    assert_eq!(c.offset, 0);
+    let d = arena.allocate(10).unwrap();
+    assert_eq!(d.offset, 20);
+}
+
+#[test]
+fn adjacent_free_blocks_coalesce() {
+    let mut arena = BufferArena::new(30);
+    let a = arena.allocate(10).unwrap();
+    let b = arena.allocate(10).unwrap();
+    let c = arena.allocate(10).unwrap();
+
+    arena.free(a);
+    arena.free(c);
+    assert_eq!(arena.free_block_count(), 2);
+
+    arena.free(b);
+    assert_eq!(arena.free_block_count(), 1);
+    assert_eq!(arena.largest_free_block(), 30);
+    assert_eq!(arena.used(), 0);
+}
+
+#[test]
+fn double_free_is_ignored() {
+    let mut arena = BufferArena::new(20);
+    let a = arena.allocate(10).unwrap();
+    let _b = arena.allocate(10).unwrap();
+    arena.free(a);
+    arena.free(a);
+    assert_eq!(arena.used(), 10);
+    assert_eq!(arena.free_block_count(), 1);
+}
+
+#[test]
+fn grow_extends_trailing_free_block() {
+    let mut arena = BufferArena::new(20);
+    let _a = arena.allocate(15).unwrap();
+    assert!(arena.allocate(10).is_none());
+
+    arena.grow(40);
+    assert_eq!(arena.capacity(), 40);
+    assert_eq!(arena.free_block_count(), 1);
+    let b = arena.allocate(10).unwrap();
+    assert_eq!(b.offset, 15);
+}
+
+#[test]
+fn grow_after_full_arena_adds_new_block() {
+    let mut arena = BufferArena::new(10);
+    let _a = arena.allocate(10).unwrap();
+    arena.grow(25);
+    let b = arena.allocate(15).unwrap();
+    assert_eq!(
+        b,
+        ArenaAllocation {
+            offset: 10,
+            size: 15
+        }
+    );
+}
+
+#[test]
+fn churn_returns_to_a_single_free_block() {
+    let mut arena = BufferArena::new(1000);
+    let mut live = Vec::new();
+    for i in 0..50 {
+        live.push(arena.allocate(1 + i % 7).unwrap());
+    }
+    for allocation in live.iter().step_by(2) {
+        arena.free(*allocation);
+    }
+    for allocation in live.iter().skip(1).step_by(2) {
+        arena.free(*allocation);
+    }
+    assert_eq!(arena.used(), 0);
+    assert_eq!(arena.free_block_count(), 1);
+    assert_eq!(arena.largest_free_block(), 1000);
+}
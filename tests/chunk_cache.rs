@@ -0,0 +1,80 @@
+use meinkraft::components::{BlockType, ChunkCoord, ChunkData, FLUID_SOURCE_LEVEL};
+use meinkraft::resources::Config;
+use meinkraft::ChunkCache;
+use serde::Serialize;
+use std::path::PathBuf;
+
+fn cache_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("meinkraft-test-{}-{}", name, std::process::id()))
+}
+
+#[test]
+fn saved_chunks_round_trip_with_fluid_levels() {
+    let config = Config::new();
+    let dir = cache_dir("round-trip");
+    let cache = ChunkCache::in_dir(dir.clone()).unwrap();
+    let coord = ChunkCoord(1, -2, 3);
+
+    let mut chunk = ChunkData::new(&config);
+    chunk.set_block(&config, 1, 2, 3, BlockType::Water);
+    chunk.fluid_levels[7] = 3;
+    cache.save_chunk(coord, &chunk).unwrap();
+
+    let loaded = cache.load_chunk(coord).unwrap().expect("chunk was saved");
+    assert_eq!(&*loaded.blocks, &*chunk.blocks);
+    assert_eq!(&*loaded.fluid_levels, &*chunk.fluid_levels);
+    assert!(cache.load_chunk(ChunkCoord(0, 0, 0)).unwrap().is_none());
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn block_only_saves_are_migrated_with_source_fluid_levels() {
+    #[derive(Serialize)]
+    struct BlockOnlyChunk {
+        blocks: Vec<BlockType>,
+    }
+
+    let config = Config::new();
+    let dir = cache_dir("legacy");
+    let cache = ChunkCache::in_dir(dir.clone()).unwrap();
+    let mut blocks = vec![BlockType::Air; config.chunk_size];
+    blocks[5] = BlockType::Stone;
+    let bytes = bincode::serde::encode_to_vec(
+        BlockOnlyChunk {
+            blocks: blocks.clone(),
+        },
+        bincode::config::standard(),
+    )
+    .unwrap();
+    std::fs::write(dir.join("4_0_-4.chunk"), bytes).unwrap();
+
+    let loaded = cache
+        .load_chunk(ChunkCoord(4, 0, -4))
+        .unwrap()
+        .expect("legacy chunk should load");
+    assert_eq!(&*loaded.blocks, blocks.as_slice());
+    assert!(loaded
+        .fluid_levels
+        .iter()
+        .all(|level| *level == FLUID_SOURCE_LEVEL));
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn unknown_versions_are_reported_instead_of_misread() {
+    let dir = cache_dir("version");
+    let cache = ChunkCache::in_dir(dir.clone()).unwrap();
+    let mut bytes = b"MKCH".to_vec();
+    bytes.extend_from_slice(&99u32.to_le_bytes());
+    std::fs::write(dir.join("0_0_0.chunk"), bytes).unwrap();
+
+    let error = cache
+        .load_chunk(ChunkCoord(0, 0, 0))
+        .err()
+        .expect("should fail");
+    assert!(error.to_string().contains("version 99"));
+
+    let _ = std::fs::remove_dir_all(dir);
+}
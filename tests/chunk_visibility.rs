@@ -0,0 +1,78 @@
+use meinkraft::components::{BlockType, ChunkData, ChunkVisibility};
+use meinkraft::resources::{Config, MeshGenerator};
+
+const POS_X: usize = 0;
+const NEG_X: usize = 1;
+const POS_Y: usize = 2;
+const NEG_Y: usize = 3;
+const POS_Z: usize = 4;
+const NEG_Z: usize = 5;
+
+fn solid_chunk(config: &Config) -> ChunkData {
+    let mut chunk = ChunkData::new(config);
+    for block in chunk.blocks.iter_mut() {
+        *block = BlockType::Stone;
+    }
+    chunk
+}
+
+#[test]
+fn empty_and_solid_chunks() {
+    let config = Config::new();
+    let mesh_generator = MeshGenerator::new();
+
+    let air = ChunkData::new(&config);
+    assert_eq!(
+        mesh_generator.compute_chunk_visibility(&air, &config),
+        ChunkVisibility::all()
+    );
+
+    let stone = solid_chunk(&config);
+    assert_eq!(
+        mesh_generator.compute_chunk_visibility(&stone, &config),
+        ChunkVisibility::none()
+    );
+}
+
+#[test]
+fn straight_tunnel_connects_only_its_ends() {
+    let config = Config::new();
+    let mut chunk = solid_chunk(&config);
+    for x in 0..config.chunk_width {
+        chunk.set_block(&config, x, 5, 5, BlockType::Air);
+    }
+
+    let visibility = MeshGenerator::new().compute_chunk_visibility(&chunk, &config);
+    assert!(visibility.is_connected(POS_X, NEG_X));
+    assert!(visibility.is_connected(NEG_X, POS_X));
+    assert!(!visibility.is_connected(POS_X, POS_Y));
+    assert!(!visibility.is_connected(NEG_Y, POS_Z));
+    assert!(!visibility.is_connected(POS_Z, NEG_Z));
+}
+
+#[test]
+fn separate_pockets_do_not_connect() {
+    let config = Config::new();
+    let mut chunk = solid_chunk(&config);
+    for y in 0..config.chunk_height {
+        chunk.set_block(&config, 3, y, 3, BlockType::Air);
+    }
+    for z in 0..config.chunk_depth {
+        chunk.set_block(&config, 8, 8, z, BlockType::Water);
+    }
+
+    let visibility = MeshGenerator::new().compute_chunk_visibility(&chunk, &config);
+    assert!(visibility.is_connected(POS_Y, NEG_Y));
+    assert!(visibility.is_connected(POS_Z, NEG_Z));
+    assert!(!visibility.is_connected(POS_Y, POS_Z));
+    assert!(!visibility.is_connected(NEG_Y, NEG_Z));
+}
+
+#[test]
+fn opposite_faces_pair_up() {
+    for face in 0..6 {
+        let opposite = ChunkVisibility::opposite_face(face);
+        assert_ne!(face, opposite);
+        assert_eq!(ChunkVisibility::opposite_face(opposite), face);
+    }
+}
@@ -0,0 +1,55 @@
+use meinkraft::resources::generate_cloud_density;
+
+const RESOLUTION: usize = 64;
+
+fn max_neighbor_step(density: &[u8], across_wrap: bool) -> u8 {
+    let at = |x: usize, y: usize| density[(y % RESOLUTION) * RESOLUTION + x % RESOLUTION];
+    let mut max_step = 0;
+    for y in 0..RESOLUTION {
+        for x in 0..RESOLUTION {
+            let on_edge = x == RESOLUTION - 1 || y == RESOLUTION - 1;
+            if on_edge != across_wrap {
+                continue;
+            }
+            max_step = max_step
+                .max(at(x, y).abs_diff(at(x + 1, y)))
+                .max(at(x, y).abs_diff(at(x, y + 1)));
+        }
+    }
+    max_step
+}
+
+#[test]
+fn density_is_deterministic_per_seed() {
+    let first = generate_cloud_density(42069, RESOLUTION);
+    assert_eq!(first.len(), RESOLUTION * RESOLUTION);
+    assert_eq!(first, generate_cloud_density(42069, RESOLUTION));
+    assert_ne!(first, generate_cloud_density(1234, RESOLUTION));
+}
+
+#[test]
+fn density_has_both_clear_and_cloudy_areas() {
+    let density = generate_cloud_density(42069, RESOLUTION);
+    let min = *density.iter().min().unwrap();
+    let max = *density.iter().max().unwrap();
+    assert!(
+        max - min > 100,
+        "density range {}..{} is too flat",
+        min,
+        max
+    );
+}
+
+#[test]
+fn density_tiles_without_seams() {
+    let density = generate_cloud_density(42069, RESOLUTION);
+    let wrap_step = max_neighbor_step(&density, true);
+    let interior_step = max_neighbor_step(&density, false);
+    assert!(
+        wrap_step <= interior_step,
+        "seam step {} exceeds largest interior step {}",
+        wrap_step,
+        interior_step
+    );
+    assert!(interior_step < 64);
+}
@@ -0,0 +1,104 @@
+// Shared by several test binaries, each using only part of it.
+#![allow(dead_code)]
+
+use fnv::FnvHashMap;
+use glam::IVec3;
+use meinkraft::components::{
+    world_to_chunk_coords, world_to_local_coords, BlockType, ChunkCoord, ChunkData,
+    FLUID_SOURCE_LEVEL,
+};
+use meinkraft::resources::{BlockAccess, Config};
+
+// A few chunks of blocks standing in for the loaded world. Every write is recorded so tests
+// can schedule follow-up updates the way GameState does.
+pub struct TestWorld {
+    pub config: Config,
+    pub chunks: FnvHashMap<ChunkCoord, ChunkData>,
+    pub changed: Vec<IVec3>,
+}
+
+impl TestWorld {
+    // Loads all-air chunks at `coords`.
+    pub fn new(coords: &[ChunkCoord]) -> Self {
+        let config = Config::new();
+        let chunks = coords
+            .iter()
+            .map(|coord| (*coord, ChunkData::new(&config)))
+            .collect();
+        Self {
+            config,
+            chunks,
+            changed: Vec::new(),
+        }
+    }
+
+    // Fills the inclusive box between `min` and `max` without recording the writes.
+    pub fn fill(&mut self, min: IVec3, max: IVec3, block_type: BlockType) {
+        for y in min.y..=max.y {
+            for z in min.z..=max.z {
+                for x in min.x..=max.x {
+                    self.set_block_world(x, y, z, block_type);
+                }
+            }
+        }
+        self.changed.clear();
+    }
+
+    pub fn block(&self, pos: IVec3) -> Option<BlockType> {
+        self.get_loaded_block_world(pos.x, pos.y, pos.z)
+    }
+
+    pub fn fluid_level(&self, pos: IVec3) -> u8 {
+        self.get_fluid_level_world(pos.x, pos.y, pos.z)
+    }
+}
+
+impl BlockAccess for TestWorld {
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    fn get_loaded_block_world(
+        &self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+    ) -> Option<BlockType> {
+        let coord = world_to_chunk_coords(&self.config, world_x, world_y, world_z);
+        let (x, y, z) = world_to_local_coords(&self.config, world_x, world_y, world_z);
+        self.chunks
+            .get(&coord)
+            .map(|chunk| chunk.get_block(&self.config, x, y, z))
+    }
+
+    fn get_fluid_level_world(&self, world_x: i32, world_y: i32, world_z: i32) -> u8 {
+        let coord = world_to_chunk_coords(&self.config, world_x, world_y, world_z);
+        let (x, y, z) = world_to_local_coords(&self.config, world_x, world_y, world_z);
+        self.chunks.get(&coord).map_or(FLUID_SOURCE_LEVEL, |chunk| {
+            chunk.get_fluid_level(&self.config, x, y, z)
+        })
+    }
+
+    fn set_fluid_world(
+        &mut self,
+        world_x: i32,
+        world_y: i32,
+        world_z: i32,
+        block_type: BlockType,
+        level: u8,
+    ) -> bool {
+        let coord = world_to_chunk_coords(&self.config, world_x, world_y, world_z);
+        let (x, y, z) = world_to_local_coords(&self.config, world_x, world_y, world_z);
+        let Some(chunk) = self.chunks.get_mut(&coord) else {
+            return false;
+        };
+        if chunk.get_block(&self.config, x, y, z) == block_type
+            && chunk.get_fluid_level(&self.config, x, y, z) == level
+        {
+            return true;
+        }
+        chunk.set_fluid(&self.config, x, y, z, block_type, level);
+        self.changed.push(IVec3::new(world_x, world_y, world_z));
+        true
+    }
+}
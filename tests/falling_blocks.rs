@@ -0,0 +1,92 @@
+mod common;
+
+use common::TestWorld;
+use glam::{IVec3, Vec3};
+use meinkraft::components::{BlockType, ChunkCoord};
+use meinkraft::resources::BlockAccess;
+use meinkraft::systems::FallingBlockSystem;
+
+fn two_chunk_column() -> TestWorld {
+    TestWorld::new(&[ChunkCoord(0, 0, 0), ChunkCoord(0, 1, 0)])
+}
+
+#[test]
+fn landing_cell_rounds_the_height_up() {
+    assert_eq!(
+        FallingBlockSystem::landing_cell(Vec3::new(3.0, 9.2, -1.4)),
+        IVec3::new(3, 10, -1)
+    );
+    assert_eq!(
+        FallingBlockSystem::landing_cell(Vec3::new(3.0, 9.0, 0.6)),
+        IVec3::new(3, 9, 1)
+    );
+}
+
+#[test]
+fn only_loose_blocks_over_open_loaded_space_start_falling() {
+    let mut world = two_chunk_column();
+    world.fill(
+        IVec3::new(0, 20, 0),
+        IVec3::new(15, 20, 15),
+        BlockType::Stone,
+    );
+    world.set_block_world(1, 18, 1, BlockType::Sand);
+    world.set_block_world(2, 18, 2, BlockType::Gravel);
+    world.set_block_world(2, 17, 2, BlockType::Water);
+    world.set_block_world(3, 21, 3, BlockType::Sand);
+    world.set_block_world(4, 18, 4, BlockType::Stone);
+    world.set_block_world(5, 0, 5, BlockType::Sand);
+
+    let falls =
+        |world: &TestWorld, x, y, z| FallingBlockSystem::starts_falling(world, IVec3::new(x, y, z));
+    assert_eq!(falls(&world, 1, 18, 1), Some(BlockType::Sand));
+    assert_eq!(falls(&world, 2, 18, 2), Some(BlockType::Gravel));
+    assert_eq!(falls(&world, 3, 21, 3), None, "resting on stone");
+    assert_eq!(falls(&world, 4, 18, 4), None, "stone ignores gravity");
+    assert_eq!(falls(&world, 5, 0, 5), None, "chunk below isn't loaded");
+}
+
+#[test]
+fn falls_stop_on_solid_blocks_and_the_bottom_of_the_world() {
+    let mut world = two_chunk_column();
+    world.set_block_world(1, 9, 1, BlockType::Stone);
+    world.set_block_world(2, 9, 2, BlockType::Water);
+
+    let supported =
+        |world: &TestWorld, x, y, z| FallingBlockSystem::is_supported(world, IVec3::new(x, y, z));
+    assert_eq!(supported(&world, 1, 10, 1), Some(true));
+    assert_eq!(
+        supported(&world, 2, 10, 2),
+        Some(false),
+        "water doesn't hold"
+    );
+    assert_eq!(supported(&world, 3, 10, 3), Some(false));
+    assert_eq!(
+        supported(&world, 3, 40, 3),
+        None,
+        "chunk below isn't loaded"
+    );
+
+    let config = world.config().clone();
+    let bottom = config.min_chunk_y * config.chunk_height as i32;
+    assert_eq!(supported(&world, 3, bottom, 3), Some(true));
+}
+
+#[test]
+fn landed_blocks_replace_air_and_water_but_not_solids() {
+    let mut world = two_chunk_column();
+    world.set_block_world(2, 5, 2, BlockType::Water);
+    world.set_block_world(3, 5, 3, BlockType::Stone);
+
+    let mut land = |x, y, z| {
+        FallingBlockSystem::place_landed_block(&mut world, IVec3::new(x, y, z), BlockType::Sand)
+    };
+    assert!(land(1, 5, 1));
+    assert!(land(2, 5, 2));
+    assert!(!land(3, 5, 3));
+    assert!(!land(4, 40, 4), "chunk isn't loaded");
+
+    assert_eq!(world.block(IVec3::new(1, 5, 1)), Some(BlockType::Sand));
+    assert_eq!(world.block(IVec3::new(2, 5, 2)), Some(BlockType::Sand));
+    assert_eq!(world.block(IVec3::new(3, 5, 3)), Some(BlockType::Stone));
+}
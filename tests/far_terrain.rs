@@ -0,0 +1,167 @@
+use glam::{Vec2, Vec3};
+use meinkraft::components::{weighted_chunk_distance_sq, world_to_chunk_coords};
+use meinkraft::resources::{
+    far_terrain_inner_radius, far_terrain_radius, far_tile_origin, far_tiles_in_range, fog_range,
+    generate_far_terrain_tile, Config, WorldGenerator,
+};
+use std::collections::HashMap;
+
+const FLOATS_PER_VERTEX: usize = 9;
+
+fn texture_layers() -> HashMap<String, f32> {
+    ["grass_top", "sand", "stone", "snow", "water_still", "ice"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_string(), i as f32))
+        .collect()
+}
+
+fn tile_distance_range(tile: (i32, i32), camera: Vec2, config: &Config) -> (f32, f32) {
+    let min = far_tile_origin(tile, config);
+    let max = min + Vec2::splat(config.far_terrain_tile_size as f32);
+    let nearest = camera.clamp(min, max).distance(camera);
+    let farthest = [min, max, Vec2::new(min.x, max.y), Vec2::new(max.x, min.y)]
+        .iter()
+        .map(|corner| corner.distance(camera))
+        .fold(0.0, f32::max);
+    (nearest, farthest)
+}
+
+#[test]
+fn tiles_cover_only_the_ring_beyond_loaded_chunks_nearest_first() {
+    let config = Config::new();
+    let camera = Vec3::new(37.0, 70.0, -12.0);
+    let camera_xz = Vec2::new(camera.x, camera.z);
+    let tiles = far_tiles_in_range(camera, &config);
+    assert!(!tiles.is_empty());
+
+    let inner = far_terrain_inner_radius(&config, 4);
+    let outer = far_terrain_radius(&config);
+    let mut previous = 0.0;
+    for tile in &tiles {
+        let (nearest, farthest) = tile_distance_range(*tile, camera_xz, &config);
+        assert!(
+            farthest >= inner,
+            "tile {:?} lies inside the chunk area",
+            tile
+        );
+        assert!(nearest <= outer, "tile {:?} lies past the far edge", tile);
+        assert!(nearest >= previous);
+        previous = nearest;
+    }
+
+    let camera_tile = (
+        (camera.x / config.far_terrain_tile_size as f32).floor() as i32,
+        (camera.z / config.far_terrain_tile_size as f32).floor() as i32,
+    );
+    assert!(!tiles.contains(&camera_tile));
+}
+
+#[test]
+fn flying_cameras_get_far_terrain_where_the_ground_is_out_of_range() {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let top = ((config.max_chunk_y + 1) * config.chunk_height as i32) as f32 - 1.0;
+    let tile_size = config.far_terrain_tile_size as i32;
+    let render_dist_sq = (config.render_distance * config.render_distance) as f32;
+
+    for camera in [Vec3::new(8.0, 70.0, 8.0), Vec3::new(-300.0, top, 500.0)] {
+        let camera_chunk = world_to_chunk_coords(
+            &config,
+            camera.x.floor() as i32,
+            camera.y.floor() as i32,
+            camera.z.floor() as i32,
+        );
+        let tiles = far_tiles_in_range(camera, &config);
+        let outer = far_terrain_radius(&config);
+
+        let mut uncovered = 0;
+        let reach = outer as i32;
+        for dz in (-reach..=reach).step_by(8) {
+            for dx in (-reach..=reach).step_by(8) {
+                if ((dx * dx + dz * dz) as f32).sqrt() > outer - 16.0 {
+                    continue;
+                }
+                let world_x = camera.x as i32 + dx;
+                let world_z = camera.z as i32 + dz;
+                let (height, _) = world_generator.surface_block(world_x, world_z);
+                let ground_chunk = world_to_chunk_coords(&config, world_x, height, world_z);
+                if weighted_chunk_distance_sq(&config, camera_chunk, ground_chunk) <= render_dist_sq
+                {
+                    continue;
+                }
+                uncovered += 1;
+                let tile = (world_x.div_euclid(tile_size), world_z.div_euclid(tile_size));
+                assert!(
+                    tiles.contains(&tile),
+                    "ground at ({}, {}, {}) is out of chunk range but has no far tile",
+                    world_x,
+                    height,
+                    world_z
+                );
+            }
+        }
+        assert!(uncovered > 0);
+    }
+
+    // High up, the loaded ground reaches much less far than the render distance.
+    assert!(
+        far_terrain_inner_radius(&config, config.max_chunk_y)
+            < far_terrain_inner_radius(&config, 4) * 0.5
+    );
+}
+
+#[test]
+fn tile_mesh_follows_the_generated_surface() {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let mesh = generate_far_terrain_tile(&world_generator, (5, -3), &texture_layers(), &config);
+
+    let cells = config.far_terrain_tile_size / config.far_terrain_sample_spacing;
+    assert_eq!(mesh.vertices.len(), cells * cells * 4 * FLOATS_PER_VERTEX);
+    assert_eq!(mesh.indices.len(), cells * cells * 6);
+
+    for vertex in mesh.vertices.chunks_exact(FLOATS_PER_VERTEX) {
+        let world_x = (vertex[0] + 0.5) as i32;
+        let world_z = (vertex[2] + 0.5) as i32;
+        let (height, _) = world_generator.surface_block(world_x, world_z);
+        assert_eq!(vertex[1], height as f32 + 0.5);
+        assert!(vertex[7] > 0.0, "normals point up");
+    }
+}
+
+#[test]
+fn neighboring_tiles_share_their_edge_heights() {
+    let config = Config::new();
+    let world_generator = WorldGenerator::new(config.clone());
+    let layers = texture_layers();
+    let west = generate_far_terrain_tile(&world_generator, (2, 1), &layers, &config);
+    let east = generate_far_terrain_tile(&world_generator, (3, 1), &layers, &config);
+
+    let edge_x = far_tile_origin((3, 1), &config).x - 0.5;
+    let edge_points = |vertices: &[f32]| {
+        let mut points: Vec<(i32, i32)> = vertices
+            .chunks_exact(FLOATS_PER_VERTEX)
+            .filter(|vertex| vertex[0] == edge_x)
+            .map(|vertex| (vertex[2] as i32, (vertex[1] * 2.0) as i32))
+            .collect();
+        points.sort();
+        points.dedup();
+        points
+    };
+
+    let west_edge = edge_points(&west.vertices);
+    assert!(!west_edge.is_empty());
+    assert_eq!(west_edge, edge_points(&east.vertices));
+}
+
+#[test]
+fn fog_hides_the_far_terrain_edge() {
+    let config = Config::new();
+    assert!(config.far_terrain_enabled);
+    let (fog_start, fog_end) = fog_range(&config);
+    let render_edge = config.render_distance as f32 * config.chunk_width as f32;
+
+    assert!(fog_start < fog_end && fog_end < far_terrain_radius(&config));
+    assert!(fog_end > render_edge);
+}
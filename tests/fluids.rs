@@ -0,0 +1,142 @@
+mod common;
+
+use common::TestWorld;
+use glam::IVec3;
+use meinkraft::components::{
+    BlockType, ChunkCoord, FLUID_FALLING_LEVEL, FLUID_MAX_FLOW_LEVEL, FLUID_SOURCE_LEVEL,
+};
+use meinkraft::resources::BlockAccess;
+use meinkraft::systems::FluidSystem;
+
+const NEIGHBOR_OFFSETS: [IVec3; 7] = [
+    IVec3::ZERO,
+    IVec3::X,
+    IVec3::NEG_X,
+    IVec3::Y,
+    IVec3::NEG_Y,
+    IVec3::Z,
+    IVec3::NEG_Z,
+];
+
+// Three by three chunks with a stone floor at y = 0.
+fn floored_world() -> TestWorld {
+    let coords: Vec<ChunkCoord> = (-1..=1)
+        .flat_map(|x| (-1..=1).map(move |z| ChunkCoord(x, 0, z)))
+        .collect();
+    let mut world = TestWorld::new(&coords);
+    world.fill(
+        IVec3::new(-16, 0, -16),
+        IVec3::new(31, 0, 31),
+        BlockType::Stone,
+    );
+    world
+}
+
+// Runs fluid ticks, each updating the blocks around the previous tick's writes, until the
+// water stops changing.
+fn settle(world: &mut TestWorld) {
+    for _ in 0..64 {
+        let mut scheduled: Vec<IVec3> = world
+            .changed
+            .drain(..)
+            .flat_map(|pos| NEIGHBOR_OFFSETS.map(|offset| pos + offset))
+            .collect();
+        if scheduled.is_empty() {
+            return;
+        }
+        scheduled.sort_unstable_by_key(|pos| (pos.y, pos.x, pos.z));
+        scheduled.dedup();
+        for pos in scheduled {
+            FluidSystem::update_fluid_block(world, pos);
+        }
+    }
+    panic!("water never settled");
+}
+
+#[test]
+fn flow_loses_a_level_per_block_until_it_runs_out() {
+    let mut world = floored_world();
+    let source = IVec3::new(8, 1, 8);
+    world.set_block_world(source.x, source.y, source.z, BlockType::Water);
+    settle(&mut world);
+
+    assert_eq!(world.fluid_level(source), FLUID_SOURCE_LEVEL);
+    for distance in 1..=FLUID_MAX_FLOW_LEVEL as i32 {
+        let pos = source + IVec3::X * distance;
+        assert_eq!(world.block(pos), Some(BlockType::Water));
+        assert_eq!(world.fluid_level(pos), distance as u8);
+    }
+    let past_the_end = source + IVec3::X * (FLUID_MAX_FLOW_LEVEL as i32 + 1);
+    assert_eq!(world.block(past_the_end), Some(BlockType::Air));
+    assert_eq!(world.block(source + IVec3::Y), Some(BlockType::Air));
+}
+
+#[test]
+fn water_falls_into_air_before_spreading() {
+    let mut world = floored_world();
+    let source = IVec3::new(8, 5, 8);
+    world.set_block_world(source.x, source.y, source.z, BlockType::Water);
+    FluidSystem::update_fluid_block(&mut world, source);
+
+    let below = source - IVec3::Y;
+    assert_eq!(world.block(below), Some(BlockType::Water));
+    assert_eq!(world.fluid_level(below), FLUID_FALLING_LEVEL);
+    assert_eq!(world.block(source + IVec3::X), Some(BlockType::Air));
+
+    settle(&mut world);
+    let landing = IVec3::new(8, 1, 8);
+    assert_eq!(world.fluid_level(landing), FLUID_FALLING_LEVEL);
+    assert_eq!(
+        world.fluid_level(landing + IVec3::X),
+        FLUID_SOURCE_LEVEL + 1
+    );
+}
+
+#[test]
+fn flow_dries_up_once_its_source_is_removed() {
+    let mut world = floored_world();
+    let source = IVec3::new(8, 1, 8);
+    world.set_block_world(source.x, source.y, source.z, BlockType::Water);
+    settle(&mut world);
+    assert_eq!(world.block(source + IVec3::Z * 3), Some(BlockType::Water));
+
+    world.set_block_world(source.x, source.y, source.z, BlockType::Air);
+    settle(&mut world);
+    for x in -16..32 {
+        for z in -16..32 {
+            assert_eq!(
+                world.block(IVec3::new(x, 1, z)),
+                Some(BlockType::Air),
+                "water left at {}, {}",
+                x,
+                z
+            );
+        }
+    }
+}
+
+#[test]
+fn two_sources_turn_the_flow_between_them_into_a_source() {
+    let mut world = floored_world();
+    world.set_block_world(8, 1, 8, BlockType::Water);
+    world.set_block_world(10, 1, 8, BlockType::Water);
+    settle(&mut world);
+
+    let between = IVec3::new(9, 1, 8);
+    assert_eq!(world.block(between), Some(BlockType::Water));
+    assert_eq!(world.fluid_level(between), FLUID_SOURCE_LEVEL);
+}
+
+#[test]
+fn water_does_not_flow_into_unloaded_chunks() {
+    let mut world = TestWorld::new(&[ChunkCoord(0, 0, 0)]);
+    world.fill(IVec3::new(0, 0, 0), IVec3::new(15, 0, 15), BlockType::Stone);
+    world.set_block_world(15, 1, 8, BlockType::Water);
+    settle(&mut world);
+
+    assert_eq!(world.block(IVec3::new(16, 1, 8)), None);
+    assert_eq!(
+        world.fluid_level(IVec3::new(14, 1, 8)),
+        FLUID_SOURCE_LEVEL + 1
+    );
+}
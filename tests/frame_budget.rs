@@ -0,0 +1,41 @@
+use meinkraft::resources::{ChunkMeshData, FrameBudget, Mesh};
+
+#[test]
+fn first_item_is_accepted_even_over_budget() {
+    let mut budget = FrameBudget::new(100, 1000.0);
+    assert!(budget.try_spend(500));
+    assert!(!budget.try_spend(1));
+    assert_eq!(budget.items(), 1);
+    assert_eq!(budget.spent_bytes(), 500);
+}
+
+#[test]
+fn items_are_accepted_until_the_byte_budget_runs_out() {
+    let mut budget = FrameBudget::new(1000, 1000.0);
+    let accepted = (0..10).take_while(|_| budget.try_spend(300)).count();
+    assert_eq!(accepted, 3);
+    assert_eq!(budget.spent_bytes(), 900);
+}
+
+#[test]
+fn an_exhausted_time_budget_refuses_after_the_first_item() {
+    let mut budget = FrameBudget::from_millis(0.0);
+    assert!(budget.try_spend(0));
+    assert!(!budget.try_spend(0));
+}
+
+#[test]
+fn mesh_byte_size_counts_every_buffer() {
+    let mesh = Mesh {
+        vertices: vec![0.0; 36],
+        indices: vec![0; 6],
+    };
+    assert_eq!(mesh.byte_size(), (36 + 6) * 4);
+
+    let chunk_mesh = ChunkMeshData {
+        opaque: Some(mesh.clone()),
+        water: Some(mesh.clone()),
+        ..Default::default()
+    };
+    assert_eq!(chunk_mesh.byte_size(), 2 * mesh.byte_size());
+}
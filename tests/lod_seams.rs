@@ -0,0 +1,175 @@
+use meinkraft::components::{BlockType, ChunkCoord, ChunkData, LOD};
+use meinkraft::resources::{Config, Mesh, MeshGenerator};
+use std::collections::HashMap;
+
+const FLOATS_PER_VERTEX: usize = 9;
+
+type GridPoint = (i32, i32, i32);
+const LODS: [LOD; 4] = [LOD::LOD1, LOD::LOD2, LOD::LOD4, LOD::LOD8];
+
+fn texture_layers() -> HashMap<String, f32> {
+    HashMap::from([("stone".to_string(), 0.0)])
+}
+
+// Bumpy stone terrain with scattered air pockets, continuous across chunk borders.
+fn terrain_chunk(config: &Config, chunk_x: i32) -> ChunkData {
+    let mut chunk = ChunkData::new(config);
+    for x in 0..config.chunk_width {
+        for z in 0..config.chunk_depth {
+            let world_x = chunk_x as usize * config.chunk_width + x;
+            let height = 4 + (world_x * world_x * 3 + z * 5 + world_x * z) % 11;
+            for y in 0..height {
+                let pocket = y > 1 && y + 2 < height && (world_x + y * 3 + z * 2).is_multiple_of(7);
+                if !pocket {
+                    chunk.set_block(config, x, y, z, BlockType::Stone);
+                }
+            }
+        }
+    }
+    chunk
+}
+
+fn mesh_chunk(
+    config: &Config,
+    chunk: &ChunkData,
+    neighbors: &[Option<ChunkData>; 6],
+    lod: LOD,
+    neighbor_lods: [LOD; 6],
+) -> Mesh {
+    MeshGenerator::new()
+        .generate_chunk_mesh(
+            ChunkCoord(0, 0, 0),
+            chunk,
+            neighbors,
+            &neighbor_lods,
+            &texture_layers(),
+            lod,
+            config,
+        )
+        .and_then(|mesh| mesh.opaque)
+        .expect("terrain should produce an opaque mesh")
+}
+
+fn quantize(vertex: &[f32], x_offset: f32) -> GridPoint {
+    (
+        ((vertex[0] + x_offset) * 4.0).round() as i32,
+        (vertex[1] * 4.0).round() as i32,
+        (vertex[2] * 4.0).round() as i32,
+    )
+}
+
+fn add_directed_edges(
+    mesh: &Mesh,
+    x_offset: f32,
+    edges: &mut HashMap<(GridPoint, GridPoint), i32>,
+) {
+    for triangle in mesh.indices.chunks(3) {
+        let corners: Vec<_> = triangle
+            .iter()
+            .map(|&index| {
+                let start = index as usize * FLOATS_PER_VERTEX;
+                quantize(&mesh.vertices[start..start + 3], x_offset)
+            })
+            .collect();
+        for i in 0..3 {
+            let (from, to) = (corners[i], corners[(i + 1) % 3]);
+            if from != to {
+                *edges.entry((from, to)).or_insert(0) += 1;
+            }
+        }
+    }
+}
+
+// A closed, consistently wound surface uses every edge once in each direction. A gap leaves
+// unmatched edges, and so does a T-junction, where one side's long edge meets the other
+// side's shorter edges.
+#[test]
+fn border_is_watertight_for_every_lod_pairing() {
+    let config = Config::new();
+    let left = terrain_chunk(&config, 0);
+    let right = terrain_chunk(&config, 1);
+
+    for left_lod in LODS {
+        for right_lod in LODS {
+            let mut left_neighbors: [Option<ChunkData>; 6] = Default::default();
+            left_neighbors[0] = Some(right.clone());
+            let mut left_neighbor_lods = [left_lod; 6];
+            left_neighbor_lods[0] = right_lod;
+
+            let mut right_neighbors: [Option<ChunkData>; 6] = Default::default();
+            right_neighbors[1] = Some(left.clone());
+            let mut right_neighbor_lods = [right_lod; 6];
+            right_neighbor_lods[1] = left_lod;
+
+            let left_mesh = mesh_chunk(
+                &config,
+                &left,
+                &left_neighbors,
+                left_lod,
+                left_neighbor_lods,
+            );
+            let right_mesh = mesh_chunk(
+                &config,
+                &right,
+                &right_neighbors,
+                right_lod,
+                right_neighbor_lods,
+            );
+
+            let mut edges = HashMap::new();
+            add_directed_edges(&left_mesh, 0.0, &mut edges);
+            add_directed_edges(&right_mesh, config.chunk_width as f32, &mut edges);
+
+            let open_edges: Vec<_> = edges
+                .iter()
+                .filter(|&(&(from, to), &count)| {
+                    edges.get(&(to, from)).copied().unwrap_or(0) != count
+                })
+                .collect();
+            assert!(
+                open_edges.is_empty(),
+                "{:?} next to {:?} leaves {} open edges, e.g. {:?}",
+                left_lod,
+                right_lod,
+                open_edges.len(),
+                open_edges.first()
+            );
+        }
+    }
+}
+
+#[test]
+fn matching_lods_emit_plain_quads() {
+    let config = Config::new();
+    let left = terrain_chunk(&config, 0);
+    let right = terrain_chunk(&config, 1);
+    let mut neighbors: [Option<ChunkData>; 6] = Default::default();
+    neighbors[0] = Some(right);
+
+    for lod in LODS {
+        let mesh = mesh_chunk(&config, &left, &neighbors, lod, [lod; 6]);
+        let vertex_count = mesh.vertices.len() / FLOATS_PER_VERTEX;
+        assert_eq!(vertex_count * 6, mesh.indices.len() * 4, "{:?}", lod);
+    }
+}
+
+#[test]
+fn coarse_side_gets_seam_vertices_on_the_finer_grid() {
+    let config = Config::new();
+    let left = terrain_chunk(&config, 0);
+    let right = terrain_chunk(&config, 1);
+    let mut neighbors: [Option<ChunkData>; 6] = Default::default();
+    neighbors[0] = Some(right);
+    let mut neighbor_lods = [LOD::LOD8; 6];
+    neighbor_lods[0] = LOD::LOD1;
+
+    let mesh = mesh_chunk(&config, &left, &neighbors, LOD::LOD8, neighbor_lods);
+    let seam_x = config.chunk_width as f32 - 0.5;
+    let seam_zs: std::collections::HashSet<i32> = mesh
+        .vertices
+        .chunks(FLOATS_PER_VERTEX)
+        .filter(|vertex| (vertex[0] - seam_x).abs() < 1e-4)
+        .map(|vertex| (vertex[2] + 0.5).round() as i32)
+        .collect();
+    assert_eq!(seam_zs.len(), config.chunk_depth + 1);
+}
@@ -0,0 +1,113 @@
+use meinkraft::components::{
+    chunk_load_priority, is_past_unload_distance, is_within_render_distance,
+    weighted_chunk_distance_sq, ChunkCoord, LOD,
+};
+use meinkraft::resources::Config;
+use std::collections::HashSet;
+
+#[test]
+fn chunks_far_below_a_flying_camera_are_coarse() {
+    let config = Config::new();
+    let camera = ChunkCoord(0, 15, 0);
+
+    let below = weighted_chunk_distance_sq(&config, camera, ChunkCoord(0, 0, 0));
+    assert_eq!(LOD::for_distance_sq(below, &config), Some(LOD::LOD4));
+
+    let beside = weighted_chunk_distance_sq(&config, camera, ChunkCoord(3, 14, 0));
+    assert_eq!(LOD::for_distance_sq(beside, &config), Some(LOD::LOD1));
+}
+
+#[test]
+fn lod_gets_coarser_with_distance_and_ends_at_render_distance() {
+    let config = Config::new();
+    let camera = ChunkCoord(0, 4, 0);
+    let mut previous = LOD::LOD1;
+    for distance in 0..=config.render_distance {
+        let distance_sq = weighted_chunk_distance_sq(&config, camera, ChunkCoord(distance, 4, 0));
+        let lod = LOD::for_distance_sq(distance_sq, &config).expect("within render distance");
+        assert!(lod >= previous);
+        previous = lod;
+    }
+    let beyond = weighted_chunk_distance_sq(
+        &config,
+        camera,
+        ChunkCoord(config.render_distance + 1, 4, 0),
+    );
+    assert_eq!(LOD::for_distance_sq(beyond, &config), None);
+}
+
+#[test]
+fn vertical_weight_scales_only_the_vertical_axis() {
+    let mut config = Config::new();
+    config.lod_vertical_weight = 2.0;
+    let camera = ChunkCoord(5, 5, 5);
+    assert_eq!(
+        weighted_chunk_distance_sq(&config, camera, ChunkCoord(5, 8, 5)),
+        36.0
+    );
+    assert_eq!(
+        weighted_chunk_distance_sq(&config, camera, ChunkCoord(8, 5, 5)),
+        9.0
+    );
+}
+
+#[test]
+fn loading_favors_chunks_near_the_camera_height_or_the_surface() {
+    let config = Config::new();
+    let camera = ChunkCoord(0, 12, 0);
+    let surface_chunk_y = 3;
+
+    let at_surface = chunk_load_priority(&config, camera, ChunkCoord(2, 3, 0), surface_chunk_y);
+    let at_camera = chunk_load_priority(&config, camera, ChunkCoord(2, 12, 0), surface_chunk_y);
+    let in_between = chunk_load_priority(&config, camera, ChunkCoord(2, 8, 0), surface_chunk_y);
+    let deep = chunk_load_priority(&config, camera, ChunkCoord(2, 0, 0), surface_chunk_y);
+
+    assert_eq!(at_surface, at_camera);
+    assert!(at_surface < in_between);
+    assert!(at_surface < deep);
+}
+
+// Moves the camera back and forth between two neighboring chunks, loading and unloading a
+// row of chunks the way the loading system does, and counts every load.
+fn loads_while_pacing(config: &Config) -> usize {
+    let row: Vec<ChunkCoord> = (-40..=40).map(|x| ChunkCoord(x, 4, 0)).collect();
+    let mut loaded = HashSet::new();
+    let mut loads = 0;
+    for step in 0..10 {
+        let camera = ChunkCoord(step % 2, 4, 0);
+        loaded.retain(|coord| !is_past_unload_distance(config, camera, *coord));
+        for coord in &row {
+            if is_within_render_distance(config, camera, *coord) && loaded.insert(*coord) {
+                loads += 1;
+            }
+        }
+    }
+    loads
+}
+
+#[test]
+fn loaded_chunks_stay_loaded_until_past_the_unload_padding() {
+    let mut config = Config::new();
+    assert!(config.unload_distance_padding > 0);
+    let camera = ChunkCoord(0, 4, 0);
+    let edge = ChunkCoord(config.render_distance, 4, 0);
+    let padding_band = ChunkCoord(
+        config.render_distance + config.unload_distance_padding,
+        4,
+        0,
+    );
+    let beyond = ChunkCoord(padding_band.0 + 1, 4, 0);
+
+    assert!(is_within_render_distance(&config, camera, edge));
+    assert!(!is_within_render_distance(&config, camera, padding_band));
+    assert!(!is_past_unload_distance(&config, camera, padding_band));
+    assert!(is_past_unload_distance(&config, camera, beyond));
+
+    // Every chunk in reach of either camera position loads once; pacing reloads nothing.
+    let render_distance = config.render_distance as usize;
+    let in_reach_of_either = 2 * render_distance + 2;
+    assert_eq!(loads_while_pacing(&config), in_reach_of_either);
+
+    config.unload_distance_padding = 0;
+    assert!(loads_while_pacing(&config) > in_reach_of_either);
+}
@@ -0,0 +1,65 @@
+use meinkraft::components::{ChunkCoord, ChunkData};
+use meinkraft::resources::{Config, LruCache};
+
+#[test]
+fn least_recently_used_entries_are_evicted_first() {
+    let mut cache = LruCache::new(30);
+    cache.insert(1, "a", 10);
+    cache.insert(2, "b", 10);
+    cache.insert(3, "c", 10);
+    assert_eq!(cache.bytes(), 30);
+
+    // Touching 1 makes 2 the oldest.
+    assert_eq!(cache.get(&1), Some(&"a"));
+    cache.insert(4, "d", 10);
+
+    assert!(!cache.contains(&2));
+    assert!(cache.contains(&1) && cache.contains(&3) && cache.contains(&4));
+    assert_eq!(cache.bytes(), 30);
+    assert_eq!(cache.evictions(), 1);
+}
+
+#[test]
+fn replacing_and_removing_entries_keeps_the_byte_count() {
+    let mut cache = LruCache::new(100);
+    cache.insert(1, 'a', 40);
+    cache.insert(1, 'b', 25);
+    assert_eq!(cache.len(), 1);
+    assert_eq!(cache.bytes(), 25);
+
+    assert_eq!(cache.remove(&1), Some('b'));
+    assert_eq!(cache.remove(&1), None);
+    assert!(cache.is_empty());
+    assert_eq!(cache.bytes(), 0);
+}
+
+#[test]
+fn shrinking_the_budget_evicts_and_oversized_entries_are_not_kept() {
+    let mut cache = LruCache::new(100);
+    for key in 0..5 {
+        cache.insert(key, key, 20);
+    }
+    cache.set_max_bytes(50);
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&3) && cache.contains(&4));
+
+    cache.insert(9, 9, 51);
+    assert!(!cache.contains(&9));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn chunk_data_reports_its_buffer_size() {
+    let config = Config::new();
+    let data = ChunkData::new(&config);
+    assert_eq!(data.byte_size(), config.chunk_size * 2);
+
+    // A budget sized for two chunks holds exactly two.
+    let mut cache = LruCache::new(data.byte_size() * 2);
+    for x in 0..3 {
+        let bytes = data.byte_size();
+        cache.insert(ChunkCoord(x, 0, 0), data.clone(), bytes);
+    }
+    assert_eq!(cache.len(), 2);
+    assert!(!cache.contains(&ChunkCoord(0, 0, 0)));
+}
@@ -0,0 +1,12 @@
+use meinkraft::resources::bloom_mip_sizes;
+
+#[test]
+fn bloom_mips_halve_and_stop_at_one_pixel() {
+    assert_eq!(
+        bloom_mip_sizes(1920, 1080, 4),
+        vec![(960, 540), (480, 270), (240, 135), (120, 67)]
+    );
+    let tiny = bloom_mip_sizes(8, 3, 6);
+    assert_eq!(tiny, vec![(4, 1)]);
+    assert!(bloom_mip_sizes(0, 0, 5).is_empty());
+}
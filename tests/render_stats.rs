@@ -0,0 +1,17 @@
+use meinkraft::resources::RenderStats;
+
+#[test]
+fn render_stats_summarize_culling() {
+    let stats = RenderStats {
+        chunks_considered: 400,
+        frustum_culled: 200,
+        occlusion_culled: 80,
+        chunks_drawn: 120,
+        shadow_casters_drawn: 90,
+        shadow_casters_culled: 60,
+    };
+    assert_eq!(
+        stats.to_string(),
+        "chunks 120/400 (frustum -200, occlusion -80) | shadow casters 90 (-60)"
+    );
+}
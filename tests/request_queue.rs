@@ -0,0 +1,101 @@
+use glam::{Mat4, Vec3};
+use meinkraft::components::{chunk_view_priority, ChunkCoord};
+use meinkraft::resources::{CancellationSet, Config, FailureTracker, Frustum, RequestQueue};
+
+#[test]
+fn pops_lowest_priority_first_and_ties_in_insertion_order() {
+    let mut queue = RequestQueue::new();
+    queue.push(ChunkCoord(3, 0, 0), 9.0, "far");
+    queue.push(ChunkCoord(1, 0, 0), 1.0, "near");
+    queue.push(ChunkCoord(2, 0, 0), 4.0, "first mid");
+    queue.push(ChunkCoord(2, 1, 0), 4.0, "second mid");
+
+    let order: Vec<&str> = std::iter::from_fn(|| queue.pop().map(|(_, item)| item)).collect();
+    assert_eq!(order, ["near", "first mid", "second mid", "far"]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn pushing_an_existing_key_replaces_the_request() {
+    let mut queue = RequestQueue::new();
+    queue.push(ChunkCoord(0, 0, 0), 5.0, 1);
+    queue.push(ChunkCoord(1, 0, 0), 3.0, 2);
+    queue.push(ChunkCoord(0, 0, 0), 1.0, 3);
+
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.pop(), Some((ChunkCoord(0, 0, 0), 3)));
+    assert_eq!(queue.pop(), Some((ChunkCoord(1, 0, 0), 2)));
+    assert_eq!(queue.pop(), None);
+}
+
+#[test]
+fn reprioritizing_reorders_and_cancels() {
+    let mut queue = RequestQueue::new();
+    for x in 0..6 {
+        queue.push(ChunkCoord(x, 0, 0), x as f32, ());
+    }
+    assert_eq!(queue.remove(&ChunkCoord(5, 0, 0)), Some(()));
+
+    // The camera moved to x = 4; anything more than two chunks away left range.
+    let mut cancelled = queue.reprioritize(|coord, _| {
+        let distance = (coord.0 - 4).abs();
+        (distance <= 2).then_some(distance as f32)
+    });
+    cancelled.sort_by_key(|coord| coord.0);
+
+    assert_eq!(cancelled, [ChunkCoord(0, 0, 0), ChunkCoord(1, 0, 0)]);
+    assert!(!queue.contains(&ChunkCoord(0, 0, 0)));
+    let order: Vec<i32> = std::iter::from_fn(|| queue.pop().map(|(coord, _)| coord.0)).collect();
+    assert_eq!(order, [4, 3, 2]);
+}
+
+#[test]
+fn cancellations_are_taken_once_and_can_be_revoked() {
+    let cancellations = CancellationSet::new();
+    cancellations.cancel(ChunkCoord(1, 2, 3));
+    cancellations.cancel(ChunkCoord(4, 5, 6));
+    cancellations.revoke(&ChunkCoord(4, 5, 6));
+
+    assert!(cancellations.take(&ChunkCoord(1, 2, 3)));
+    assert!(!cancellations.take(&ChunkCoord(1, 2, 3)));
+    assert!(!cancellations.take(&ChunkCoord(4, 5, 6)));
+    assert!(cancellations.is_empty());
+}
+
+#[test]
+fn chunks_behind_the_camera_rank_after_chunks_in_view() {
+    let config = Config::new();
+    let eye = Vec3::new(8.0, 8.0, 8.0);
+    let view = Mat4::look_at_rh(eye, eye + Vec3::X, Vec3::Y);
+    let projection = Mat4::perspective_rh_gl(70f32.to_radians(), 16.0 / 9.0, 0.1, 1000.0);
+    let frustum = Frustum::from_matrix(projection * view);
+
+    let ahead = ChunkCoord(3, 0, 0);
+    let behind = ChunkCoord(-2, 0, 0);
+    let ahead_priority = chunk_view_priority(&config, &frustum, ahead, 9.0);
+    let behind_priority = chunk_view_priority(&config, &frustum, behind, 4.0);
+
+    assert_eq!(ahead_priority, 9.0);
+    assert!(behind_priority > ahead_priority);
+}
+
+#[test]
+fn failed_jobs_are_retried_until_the_attempt_limit() {
+    let mut failures = FailureTracker::new(3);
+    let coord = ChunkCoord(0, 1, 0);
+
+    assert!(failures.record_failure(coord));
+    assert!(failures.record_failure(coord));
+    assert!(!failures.is_errored(&coord));
+    assert!(!failures.record_failure(coord));
+    assert!(failures.is_errored(&coord));
+    assert_eq!(failures.errored_count(), 1);
+
+    failures.clear(&coord);
+    assert!(!failures.is_errored(&coord));
+    assert!(failures.record_failure(coord));
+
+    failures.retain(|c| *c != coord);
+    assert_eq!(failures.errored_count(), 0);
+    assert!(failures.record_failure(coord));
+}
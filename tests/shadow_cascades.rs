@@ -0,0 +1,195 @@
+use glam::Vec3;
+use meinkraft::components::{
+    chunk_coord_to_aabb_center, get_chunk_extents, world_to_chunk_coords, ChunkCoord,
+};
+use meinkraft::resources::{
+    calculate_cascade_splits, calculate_shadow_cascades, light_direction, Camera, Config, Frustum,
+    MAX_SHADOW_CASCADES,
+};
+
+fn test_camera(config: &Config) -> Camera {
+    Camera::new(
+        Vec3::new(13.3, 72.6, -41.9),
+        Vec3::new(40.0, 60.0, 10.0),
+        Vec3::Y,
+        16.0 / 9.0,
+        config,
+    )
+}
+
+// What the renderer passes mid-morning: a vector pointing up towards the sun.
+fn morning_light(config: &Config) -> Vec3 {
+    let direction = light_direction(0.4, config);
+    assert!(direction.y > 0.5);
+    direction
+}
+
+#[test]
+fn splits_increase_and_end_at_far_plane() {
+    for lambda in [0.0, 0.5, 0.75, 1.0] {
+        let splits = calculate_cascade_splits(0.1, 128.0, 4, lambda);
+        assert_eq!(splits.len(), 4);
+        assert!(splits.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!((splits[3] - 128.0).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn lambda_blends_uniform_and_logarithmic_splits() {
+    let uniform = calculate_cascade_splits(1.0, 100.0, 2, 0.0);
+    assert!((uniform[0] - 50.5).abs() < 1e-4);
+
+    let logarithmic = calculate_cascade_splits(1.0, 100.0, 2, 1.0);
+    assert!((logarithmic[0] - 10.0).abs() < 1e-4);
+
+    let mixed = calculate_cascade_splits(1.0, 100.0, 2, 0.5);
+    assert!(mixed[0] > logarithmic[0] && mixed[0] < uniform[0]);
+}
+
+#[test]
+fn cascade_count_is_clamped() {
+    let mut config = Config::new();
+    config.shadow_cascade_count = 16;
+    let mut camera = test_camera(&config);
+    let light_direction = morning_light(&config);
+    let cascades = calculate_shadow_cascades(&mut camera, light_direction, &config);
+    assert_eq!(cascades.len(), MAX_SHADOW_CASCADES);
+}
+
+#[test]
+fn each_cascade_contains_its_view_slice() {
+    let config = Config::new();
+    let mut camera = test_camera(&config);
+    let light_direction = morning_light(&config);
+    let cascades = calculate_shadow_cascades(&mut camera, light_direction, &config);
+
+    let mut slice_near = camera.z_near();
+    for cascade in &cascades {
+        for corner in camera.frustum_corners(slice_near, cascade.split_depth) {
+            let clip = cascade.light_space_matrix.project_point3(corner);
+            assert!(clip.x.abs() <= 1.0 + 1e-3, "x out of cascade: {}", clip.x);
+            assert!(clip.y.abs() <= 1.0 + 1e-3, "y out of cascade: {}", clip.y);
+            assert!(clip.z >= -1e-3 && clip.z <= 1.0 + 1e-3, "z: {}", clip.z);
+        }
+        slice_near = cascade.split_depth;
+    }
+}
+
+#[test]
+fn cascades_extend_towards_the_sun() {
+    let config = Config::new();
+    let mut camera = test_camera(&config);
+    let light_direction = morning_light(&config);
+    let cascades = calculate_shadow_cascades(&mut camera, light_direction, &config);
+    let shadow_distance_world = config.shadow_distance as f32 * config.chunk_width as f32;
+
+    let mut slice_near = camera.z_near();
+    for cascade in &cascades {
+        let corners = camera.frustum_corners(slice_near, cascade.split_depth);
+        let center = corners.iter().copied().sum::<Vec3>() / corners.len() as f32;
+        let radius = corners
+            .iter()
+            .map(|corner| corner.distance(center))
+            .fold(0.0f32, f32::max);
+        slice_near = cascade.split_depth;
+
+        let sunward = cascade
+            .light_space_matrix
+            .project_point3(center + light_direction * (radius + shadow_distance_world * 0.9));
+        assert!(
+            sunward.z >= 0.0 && sunward.z <= 1.0,
+            "sunward z: {}",
+            sunward.z
+        );
+
+        let below = cascade
+            .light_space_matrix
+            .project_point3(center - light_direction * (radius + 1.0));
+        assert!(below.z > 1.0, "z below the slice: {}", below.z);
+    }
+}
+
+#[test]
+fn near_cascades_have_finer_texels() {
+    let config = Config::new();
+    let mut camera = test_camera(&config);
+    let light_direction = light_direction(0.6, &config);
+    let cascades = calculate_shadow_cascades(&mut camera, light_direction, &config);
+    assert!(cascades
+        .windows(2)
+        .all(|pair| pair[0].world_units_per_texel < pair[1].world_units_per_texel));
+}
+
+#[test]
+fn cascades_are_snapped_to_whole_texels() {
+    let config = Config::new();
+    let mut camera = test_camera(&config);
+    let light_direction = morning_light(&config);
+    let half_resolution = config.shadow_map_resolution as f32 / 2.0;
+
+    for cascade in calculate_shadow_cascades(&mut camera, light_direction, &config) {
+        let origin = cascade.light_space_matrix.project_point3(Vec3::ZERO);
+        for texels in [origin.x * half_resolution, origin.y * half_resolution] {
+            assert!(
+                (texels - texels.round()).abs() < 0.05,
+                "world origin is {} texels from the cascade center",
+                texels
+            );
+        }
+    }
+}
+
+#[test]
+fn light_frustum_keeps_nearby_casters_and_rejects_distant_chunks() {
+    let config = Config::new();
+    let mut camera = test_camera(&config);
+    let light_direction = morning_light(&config);
+    let cascades = calculate_shadow_cascades(&mut camera, light_direction, &config);
+    let extents = get_chunk_extents(&config);
+
+    let camera_chunk = ChunkCoord(0, 4, -3);
+    let far_away = ChunkCoord(200, 4, 200);
+    for cascade in &cascades {
+        let light_frustum = Frustum::from_matrix(cascade.light_space_matrix);
+        assert!(
+            !light_frustum.intersects_aabb(chunk_coord_to_aabb_center(&config, far_away), extents)
+        );
+    }
+
+    let nearest = Frustum::from_matrix(cascades[0].light_space_matrix);
+    assert!(nearest.intersects_aabb(chunk_coord_to_aabb_center(&config, camera_chunk), extents));
+}
+
+#[test]
+fn light_frustum_keeps_casters_between_the_sun_and_the_camera() {
+    let config = Config::new();
+    let mut camera = Camera::new(
+        Vec3::new(8.0, 40.0, 8.0),
+        Vec3::new(40.0, 40.0, 8.0),
+        Vec3::Y,
+        16.0 / 9.0,
+        &config,
+    );
+    let light_direction = morning_light(&config);
+    let cascades = calculate_shadow_cascades(&mut camera, light_direction, &config);
+    let extents = get_chunk_extents(&config);
+
+    // Chunks up the sun ray from the camera shade the ground the player stands on.
+    for distance in [32.0, 64.0, 96.0] {
+        let caster = camera.position + light_direction * distance;
+        let coord = world_to_chunk_coords(
+            &config,
+            caster.x.floor() as i32,
+            caster.y.floor() as i32,
+            caster.z.floor() as i32,
+        );
+        for cascade in &cascades[..2] {
+            let light_frustum = Frustum::from_matrix(cascade.light_space_matrix);
+            assert!(
+                light_frustum.intersects_aabb(chunk_coord_to_aabb_center(&config, coord), extents),
+                "caster {:?} culled from a cascade",
+                coord
+            );
+        }
+    }
+}
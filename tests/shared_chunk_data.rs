@@ -0,0 +1,46 @@
+use meinkraft::components::{BlockType, ChunkData};
+use meinkraft::resources::Config;
+
+#[test]
+fn cloning_shares_storage_until_written() {
+    let config = Config::new();
+    let mut chunk = ChunkData::new(&config);
+    chunk.set_block(&config, 1, 2, 3, BlockType::Stone);
+
+    let snapshot = chunk.clone();
+    assert!(snapshot.blocks.ptr_eq(&chunk.blocks));
+    assert!(chunk.blocks.is_shared());
+
+    chunk.set_block(&config, 1, 2, 3, BlockType::Glass);
+    assert!(!snapshot.blocks.ptr_eq(&chunk.blocks));
+    assert_eq!(snapshot.get_block(&config, 1, 2, 3), BlockType::Stone);
+    assert_eq!(chunk.get_block(&config, 1, 2, 3), BlockType::Glass);
+    assert!(!snapshot.blocks.is_shared());
+}
+
+#[test]
+fn serialized_layout_matches_plain_vectors() {
+    #[derive(serde::Serialize)]
+    struct PlainChunk {
+        blocks: Vec<BlockType>,
+        fluid_levels: Vec<u8>,
+    }
+
+    let config = Config::new();
+    let mut chunk = ChunkData::new(&config);
+    chunk.set_fluid(&config, 4, 5, 6, BlockType::Water, 3);
+    let plain = PlainChunk {
+        blocks: chunk.blocks.to_vec(),
+        fluid_levels: chunk.fluid_levels.to_vec(),
+    };
+
+    let bincode_config = bincode::config::standard();
+    let shared_bytes = bincode::serde::encode_to_vec(&chunk, bincode_config).unwrap();
+    let plain_bytes = bincode::serde::encode_to_vec(&plain, bincode_config).unwrap();
+    assert_eq!(shared_bytes, plain_bytes);
+
+    let (decoded, _): (ChunkData, usize) =
+        bincode::serde::decode_from_slice(&shared_bytes, bincode_config).unwrap();
+    assert_eq!(decoded.get_block(&config, 4, 5, 6), BlockType::Water);
+    assert_eq!(decoded.get_fluid_level(&config, 4, 5, 6), 3);
+}
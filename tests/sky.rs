@@ -0,0 +1,42 @@
+use glam::Vec3;
+use meinkraft::resources::{fog_range, scattering_sky_color, Config};
+
+#[test]
+fn midday_sky_is_blue_overhead_and_paler_at_the_horizon() {
+    let config = Config::new();
+    let sun = Vec3::new(0.3, 1.0, 0.0).normalize();
+    let overhead = scattering_sky_color(Vec3::new(-0.3, 1.0, 0.2), sun, &config);
+    let horizon = scattering_sky_color(Vec3::new(-1.0, 0.02, 0.0), sun, &config);
+
+    assert!(overhead.z > overhead.y && overhead.y > overhead.x);
+    assert!(horizon.x / horizon.z > overhead.x / overhead.z);
+}
+
+#[test]
+fn sunset_reddens_the_horizon_towards_the_sun() {
+    let config = Config::new();
+    let sun = Vec3::new(1.0, 0.03, 0.0).normalize();
+    let towards_sun = scattering_sky_color(Vec3::new(1.0, 0.05, 0.0), sun, &config);
+    let away_from_sun = scattering_sky_color(Vec3::new(-1.0, 0.05, 0.0), sun, &config);
+
+    assert!(towards_sun.x > towards_sun.z);
+    assert!(towards_sun.length() > away_from_sun.length());
+}
+
+#[test]
+fn night_sky_falls_back_to_midnight_color() {
+    let config = Config::new();
+    let sun = Vec3::new(0.5, -0.8, 0.0).normalize();
+    let color = scattering_sky_color(Vec3::Y, sun, &config);
+    assert!((color - config.midnight_color).length() < 1e-5);
+}
+
+#[test]
+fn fog_is_opaque_before_the_render_edge() {
+    let mut config = Config::new();
+    config.far_terrain_enabled = false;
+    let (fog_start, fog_end) = fog_range(&config);
+    let render_edge = config.render_distance as f32 * config.chunk_width as f32;
+
+    assert!(fog_start > 0.0 && fog_start < fog_end && fog_end < render_edge);
+}
@@ -0,0 +1,66 @@
+use meinkraft::components::BlockType;
+use meinkraft::resources::{Config, WorldGenerator};
+
+#[test]
+fn snow_settles_only_on_solid_tops() {
+    for block in [
+        BlockType::Stone,
+        BlockType::Dirt,
+        BlockType::GrassyDirt,
+        BlockType::SnowyDirt,
+        BlockType::Log,
+    ] {
+        assert!(block.can_hold_snow(), "{:?} should hold snow", block);
+    }
+    for block in [
+        BlockType::Air,
+        BlockType::Water,
+        BlockType::Ice,
+        BlockType::Leaves,
+        BlockType::Glass,
+        BlockType::Snow,
+    ] {
+        assert!(!block.can_hold_snow(), "{:?} should not hold snow", block);
+    }
+}
+
+#[test]
+fn snow_accumulates_only_above_the_snow_line() {
+    let config = Config::new();
+    let generator = WorldGenerator::new(config.clone());
+
+    // A temperate lowland column, where the snow line sits at `snow_level`.
+    let (world_x, world_z) = (0..2048)
+        .map(|i| (i * 16, 0))
+        .find(|(x, z)| {
+            generator.snow_line(*x, *z) == config.snow_level
+                && generator.calculate_terrain_height(*x, *z) < config.sea_level + 20
+        })
+        .expect("no temperate lowland column found");
+    let ground = generator.calculate_terrain_height(world_x, world_z);
+
+    assert!(!generator.is_freezing(world_x, ground + 1, world_z));
+    assert!(generator.is_freezing(world_x, config.snow_level, world_z));
+    assert!(generator.is_freezing(world_x, config.snow_level + 40, world_z));
+}
+
+#[test]
+fn only_rivers_freeze_at_sea_level_in_cold_regions() {
+    let config = Config::new();
+    let generator = WorldGenerator::new(config.clone());
+    let cold_surface = |block: BlockType| {
+        (-300..300)
+            .flat_map(|x| (-300..300).map(move |z| (x * 16, z * 16)))
+            .find(|(x, z)| {
+                generator.rivers_freeze(*x, *z)
+                    && generator.surface_block(*x, *z) == (config.sea_level, block)
+            })
+    };
+
+    let (ocean_x, ocean_z) = cold_surface(BlockType::Water).expect("no cold ocean found");
+    assert!(!generator.is_freezing(ocean_x, config.sea_level, ocean_z));
+
+    let (river_x, river_z) = cold_surface(BlockType::Ice).expect("no frozen river found");
+    assert!(generator.is_freezing(river_x, config.sea_level, river_z));
+    assert!(!generator.is_freezing(river_x, config.sea_level - 1, river_z));
+}
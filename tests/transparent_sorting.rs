@@ -0,0 +1,60 @@
+use glam::Vec3;
+use meinkraft::resources::Mesh;
+
+fn vertex(position: [f32; 3]) -> [f32; 9] {
+    [
+        position[0],
+        position[1],
+        position[2],
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        1.0,
+        0.0,
+    ]
+}
+
+// Three single-triangle "faces" stacked along +X at x = 0, 5 and 10.
+fn stacked_triangles() -> Mesh {
+    let mut vertices = Vec::new();
+    for x in [0.0, 5.0, 10.0] {
+        vertices.extend_from_slice(&vertex([x, 0.0, 0.0]));
+        vertices.extend_from_slice(&vertex([x, 1.0, 0.0]));
+        vertices.extend_from_slice(&vertex([x, 0.0, 1.0]));
+    }
+    Mesh {
+        vertices,
+        indices: vec![3, 4, 5, 0, 1, 2, 6, 7, 8],
+    }
+}
+
+fn triangle_order(mesh: &Mesh) -> Vec<u32> {
+    mesh.indices.chunks_exact(3).map(|t| t[0] / 3).collect()
+}
+
+#[test]
+fn triangles_are_ordered_farthest_first() {
+    let mut mesh = stacked_triangles();
+    mesh.sort_triangles_back_to_front(Vec3::new(-10.0, 0.0, 0.0));
+    assert_eq!(triangle_order(&mesh), vec![2, 1, 0]);
+}
+
+#[test]
+fn moving_the_camera_reverses_the_order() {
+    let mut mesh = stacked_triangles();
+    mesh.sort_triangles_back_to_front(Vec3::new(-10.0, 0.0, 0.0));
+    mesh.sort_triangles_back_to_front(Vec3::new(20.0, 0.0, 0.0));
+    assert_eq!(triangle_order(&mesh), vec![0, 1, 2]);
+}
+
+#[test]
+fn sorting_keeps_triangle_winding() {
+    let mut mesh = stacked_triangles();
+    mesh.sort_triangles_back_to_front(Vec3::new(20.0, 0.0, 0.0));
+    for triangle in mesh.indices.chunks_exact(3) {
+        assert_eq!(triangle[1], triangle[0] + 1);
+        assert_eq!(triangle[2], triangle[0] + 2);
+    }
+    assert_eq!(mesh.indices.len(), 9);
+}
@@ -0,0 +1,98 @@
+use meinkraft::components::{BlockType, ChunkCoord, ChunkData, LOD};
+use meinkraft::resources::{ChunkMeshData, Config, Mesh, MeshGenerator};
+use std::collections::HashMap;
+
+const FLOATS_PER_VERTEX: usize = 9;
+
+fn texture_layers() -> HashMap<String, f32> {
+    ["stone", "water", "glass"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_string(), i as f32))
+        .collect()
+}
+
+// Stone floor at y = 0 and a pool of still water filling y = 1..8 with air above.
+fn pool_chunk(config: &Config) -> ChunkData {
+    let mut chunk = ChunkData::new(config);
+    for x in 0..config.chunk_width {
+        for z in 0..config.chunk_depth {
+            chunk.set_block(config, x, 0, z, BlockType::Stone);
+            for y in 1..8 {
+                chunk.set_block(config, x, y, z, BlockType::Water);
+            }
+        }
+    }
+    chunk.set_block(config, 4, 10, 4, BlockType::Glass);
+    chunk
+}
+
+fn mesh_pool(config: &Config, lod: LOD) -> ChunkMeshData {
+    let neighbors: [Option<ChunkData>; 6] = Default::default();
+    MeshGenerator::new()
+        .generate_chunk_mesh(
+            ChunkCoord(0, 0, 0),
+            &pool_chunk(config),
+            &neighbors,
+            &[lod; 6],
+            &texture_layers(),
+            lod,
+            config,
+        )
+        .expect("pool chunk should produce a mesh")
+}
+
+fn max_y(mesh: &Mesh) -> f32 {
+    mesh.vertices
+        .chunks(FLOATS_PER_VERTEX)
+        .map(|vertex| vertex[1])
+        .fold(f32::MIN, f32::max)
+}
+
+#[test]
+fn water_gets_its_own_mesh_when_enabled() {
+    let mut config = Config::new();
+    config.water_rendering = true;
+    let mesh = mesh_pool(&config, LOD::LOD1);
+    let water = mesh.water.expect("water mesh");
+    let transparent = mesh.transparent.expect("glass mesh");
+    assert!(!water.indices.is_empty());
+    assert!(
+        max_y(&transparent) > 9.0,
+        "only the glass block should be transparent"
+    );
+    assert!(max_y(&water) < 8.0);
+}
+
+#[test]
+fn water_stays_in_the_transparent_mesh_when_disabled() {
+    let mut config = Config::new();
+    config.water_rendering = false;
+    let mesh = mesh_pool(&config, LOD::LOD1);
+    assert!(mesh.water.is_none());
+    let transparent = mesh.transparent.expect("transparent mesh");
+    config.water_rendering = true;
+    let water_only = mesh_pool(&config, LOD::LOD1);
+    assert!(transparent.indices.len() > water_only.transparent.unwrap().indices.len());
+}
+
+#[test]
+fn coarse_water_surface_matches_full_detail() {
+    let config = Config::new();
+    let full_detail = max_y(&mesh_pool(&config, LOD::LOD1).water.expect("water mesh"));
+    assert!(
+        full_detail < 7.5,
+        "still water sits below the top of its block"
+    );
+
+    for lod in [LOD::LOD2, LOD::LOD4] {
+        let surface = max_y(&mesh_pool(&config, lod).water.expect("water mesh"));
+        assert!(
+            (surface - full_detail).abs() < 1e-4,
+            "{:?} surface {} vs {}",
+            lod,
+            surface,
+            full_detail
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use glam::IVec2;
+use meinkraft::resources::{
+    precipitation_at, Config, Precipitation, PrecipitationHeightmap, Weather, WeatherKind,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const ALL_KINDS: [WeatherKind; 4] = [
+    WeatherKind::Clear,
+    WeatherKind::Rain,
+    WeatherKind::Thunderstorm,
+    WeatherKind::Snow,
+];
+
+#[test]
+fn every_state_can_eventually_clear_up() {
+    for kind in ALL_KINDS {
+        let mut current = kind;
+        for _ in 0..2 {
+            if current != WeatherKind::Clear {
+                current = current.next(0.0);
+            }
+        }
+        assert_eq!(current, WeatherKind::Clear, "{:?} never clears up", kind);
+    }
+}
+
+#[test]
+fn transitions_blend_over_the_configured_duration() {
+    let config = Config::new();
+    let mut weather = Weather::new();
+    let mut rng = StdRng::seed_from_u64(7);
+
+    weather.set_weather(WeatherKind::Rain, 1000.0);
+    assert_eq!(weather.precipitation_intensity(), 0.0);
+
+    weather.update(config.weather_transition_duration * 0.5, &config, &mut rng);
+    let halfway = weather.precipitation_intensity();
+    assert!((halfway - 0.5).abs() < 1e-4);
+    assert!((weather.darkening(&config) - config.rain_darkening * 0.5).abs() < 1e-4);
+
+    weather.update(config.weather_transition_duration, &config, &mut rng);
+    assert_eq!(weather.precipitation_intensity(), 1.0);
+    assert!(weather.cloud_coverage(&config) > config.cloud_coverage);
+}
+
+#[test]
+fn fading_out_keeps_the_previous_precipitation() {
+    let mut weather = Weather::new();
+    weather.set_weather(WeatherKind::Snow, 100.0);
+    weather.set_weather(WeatherKind::Clear, 100.0);
+    assert_eq!(weather.precipitation_kind(), WeatherKind::Snow);
+}
+
+#[test]
+fn weather_changes_when_its_time_runs_out() {
+    let mut config = Config::new();
+    config.weather_min_duration = 10.0;
+    config.weather_max_duration = 20.0;
+    let mut weather = Weather::new();
+    let mut rng = StdRng::seed_from_u64(42);
+
+    let mut seen = vec![weather.current()];
+    for _ in 0..2000 {
+        weather.update(1.0, &config, &mut rng);
+        assert!(weather.time_remaining() <= config.weather_max_duration);
+        if seen.last() != Some(&weather.current()) {
+            seen.push(weather.current());
+        }
+    }
+    for kind in ALL_KINDS {
+        assert!(seen.contains(&kind), "{:?} never happened", kind);
+    }
+}
+
+#[test]
+fn rain_turns_to_snow_above_the_snow_line() {
+    let snow_line = 40;
+    assert_eq!(precipitation_at(WeatherKind::Clear, 0, snow_line), None);
+    assert_eq!(
+        precipitation_at(WeatherKind::Rain, snow_line - 1, snow_line),
+        Some(Precipitation::Rain)
+    );
+    assert_eq!(
+        precipitation_at(WeatherKind::Thunderstorm, snow_line, snow_line),
+        Some(Precipitation::Snow)
+    );
+    assert_eq!(
+        precipitation_at(WeatherKind::Snow, 0, snow_line),
+        Some(Precipitation::Snow)
+    );
+}
+
+#[test]
+fn heightmap_covers_a_square_around_its_center() {
+    let mut heightmap = PrecipitationHeightmap::new(4);
+    assert_eq!(heightmap.size(), 9);
+    let entered = heightmap.recenter(IVec2::new(100, -50));
+    assert_eq!(entered.len(), 81);
+    assert!(heightmap.take_dirty());
+
+    assert_eq!(
+        heightmap.height_at(100, -50),
+        Some(PrecipitationHeightmap::NO_GROUND)
+    );
+    heightmap.set_column(100, -50, 12.0, 40);
+    assert_eq!(heightmap.height_at(100, -50), Some(12.0));
+    assert!(heightmap.take_dirty());
+    heightmap.set_column(100, -50, 12.0, 40);
+    assert!(!heightmap.take_dirty());
+
+    assert_eq!(
+        heightmap.height_at(96, -54),
+        Some(PrecipitationHeightmap::NO_GROUND)
+    );
+    assert_eq!(heightmap.height_at(95, -50), None);
+    assert_eq!(heightmap.height_at(100, -45), None);
+}
+
+#[test]
+fn moving_the_heightmap_keeps_the_columns_it_still_covers() {
+    let mut heightmap = PrecipitationHeightmap::new(4);
+    heightmap.recenter(IVec2::ZERO);
+    for column in heightmap.columns() {
+        heightmap.set_column(column.x, column.y, column.x as f32, 40);
+    }
+
+    let entered = heightmap.recenter(IVec2::new(2, -1));
+    assert_eq!(entered.len(), 9 * 9 - 7 * 8);
+    for column in &entered {
+        assert!(
+            column.x > 4 || column.y < -4,
+            "{:?} was already covered",
+            column
+        );
+        assert_eq!(
+            heightmap.height_at(column.x, column.y),
+            Some(PrecipitationHeightmap::NO_GROUND)
+        );
+    }
+    assert_eq!(heightmap.height_at(4, -4), Some(4.0));
+    assert_eq!(heightmap.height_at(-2, 3), Some(-2.0));
+    assert_eq!(heightmap.height_at(-3, 0), None);
+}
+
+#[test]
+fn heightmap_snows_on_columns_above_their_snow_line() {
+    let mut heightmap = PrecipitationHeightmap::new(1);
+    heightmap.recenter(IVec2::ZERO);
+    heightmap.set_column(-1, -1, 50.0, 40);
+    heightmap.set_column(0, -1, 50.0, 60);
+    assert_eq!(heightmap.precipitation_at(-1, -1), None);
+
+    heightmap.set_precipitation_kind(WeatherKind::Rain);
+    assert_eq!(
+        heightmap.precipitation_at(-1, -1),
+        Some(Precipitation::Snow)
+    );
+    assert_eq!(heightmap.precipitation_at(0, -1), Some(Precipitation::Rain));
+    let texels = heightmap.texels();
+    assert_eq!(texels.len(), 9 * 2);
+    assert_eq!(&texels[..4], &[50.0, 1.0, 50.0, 0.0]);
+
+    heightmap.set_precipitation_kind(WeatherKind::Snow);
+    assert!(heightmap.take_dirty());
+    assert_eq!(heightmap.precipitation_at(0, -1), Some(Precipitation::Snow));
+}
@@ -0,0 +1,196 @@
+use meinkraft::components::ChunkCoord;
+use meinkraft::resources::{
+    run_catching_panics, worker_thread_budget, Config, FailureTracker, JobCounts, JobKind,
+    JobStats, WorkerReport, WorkerStats, WorkerThreads, WorldGenerator,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn wait_for(mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn automatic_budget_splits_cores_and_keeps_one_thread_per_pool() {
+    let config = Config::new();
+    assert_eq!(worker_thread_budget(&config, 7), (4, 3));
+    assert_eq!(worker_thread_budget(&config, 8), (4, 4));
+    assert_eq!(worker_thread_budget(&config, 1), (1, 1));
+    assert_eq!(worker_thread_budget(&config, 0), (1, 1));
+}
+
+#[test]
+fn configured_thread_counts_override_the_split() {
+    let mut config = Config::new();
+    config.meshing_threads = 5;
+    assert_eq!(worker_thread_budget(&config, 8), (3, 5));
+
+    config.generation_threads = 2;
+    assert_eq!(worker_thread_budget(&config, 8), (2, 5));
+}
+
+#[test]
+fn stats_are_kept_per_job_kind() {
+    let stats = WorkerStats::new();
+    stats.record(JobKind::Meshing, Duration::from_millis(3));
+    stats.record(JobKind::Meshing, Duration::from_millis(5));
+    stats.record(JobKind::Generation, Duration::from_millis(10));
+
+    assert_eq!(
+        stats.counts(JobKind::Meshing),
+        JobCounts {
+            completed: 2,
+            busy_nanos: 8_000_000,
+        }
+    );
+    assert_eq!(stats.counts(JobKind::Generation).completed, 1);
+    assert_eq!(stats.counts(JobKind::FarTerrain), JobCounts::default());
+}
+
+#[test]
+fn job_rates_cover_only_the_sample_window() {
+    let previous = JobCounts {
+        completed: 10,
+        busy_nanos: 50_000_000,
+    };
+    let current = JobCounts {
+        completed: 30,
+        busy_nanos: 90_000_000,
+    };
+    let stats = JobStats::between(previous, current, 2.0, 3, 7);
+
+    assert_eq!(stats.threads, 3);
+    assert_eq!(stats.queue_len, 7);
+    assert_eq!(stats.jobs_completed, 30);
+    assert_eq!(stats.jobs_per_sec, 10.0);
+    assert_eq!(stats.average_job_ms, 2.0);
+
+    let idle = JobStats::between(current, current, 1.0, 3, 0);
+    assert_eq!(idle.jobs_per_sec, 0.0);
+    assert_eq!(idle.average_job_ms, 0.0);
+}
+
+#[test]
+fn worker_report_summarizes_each_pool() {
+    let mut report = WorkerReport::default();
+    *report.get_mut(JobKind::Generation) = JobStats::between(
+        JobCounts::default(),
+        JobCounts {
+            completed: 20,
+            busy_nanos: 50_000_000,
+        },
+        2.0,
+        3,
+        7,
+    );
+    assert_eq!(
+        report.to_string(),
+        "gen 10/s 2.5ms q7 | mesh 0/s 0.0ms q0 | far 0/s 0.0ms q0"
+    );
+
+    report.respawned_workers = 1;
+    assert!(report.to_string().ends_with(" | respawned 1"));
+}
+
+#[test]
+fn parallel_generation_matches_serial_generation() {
+    let serial_config = Config::new();
+    let mut parallel_config = serial_config.clone();
+    parallel_config.parallel_chunk_generation = true;
+    let serial = WorldGenerator::new(serial_config);
+    let parallel = WorldGenerator::new(parallel_config);
+
+    for coord in [
+        ChunkCoord(0, 0, 0),
+        ChunkCoord(-3, 1, 5),
+        ChunkCoord(7, 2, -2),
+    ] {
+        let expected = serial.generate_chunk_data(coord);
+        let actual = parallel.generate_chunk_data(coord);
+        assert_eq!(actual.blocks, expected.blocks);
+        assert_eq!(actual.fluid_levels, expected.fluid_levels);
+    }
+}
+
+#[test]
+fn panicking_jobs_become_errors() {
+    assert_eq!(run_catching_panics(|| 7), Ok(7));
+    assert_eq!(
+        run_catching_panics(|| -> i32 { panic!("bad chunk") }),
+        Err("bad chunk".to_string())
+    );
+    let coord = (1, 2);
+    assert_eq!(
+        run_catching_panics(|| -> i32 { panic!("bad chunk {:?}", coord) }),
+        Err("bad chunk (1, 2)".to_string())
+    );
+}
+
+#[test]
+fn dead_workers_are_respawned() {
+    let starts = Arc::new(AtomicUsize::new(0));
+    let (stop_tx, stop_rx) = crossbeam_channel::bounded::<()>(0);
+    let mut workers = WorkerThreads::new();
+    let worker_starts = Arc::clone(&starts);
+    workers.spawn("test-worker-0".to_string(), move || {
+        if worker_starts.fetch_add(1, Ordering::SeqCst) == 0 {
+            panic!("worker died outside a job");
+        }
+        let _ = stop_rx.recv();
+    });
+
+    let mut respawned = 0;
+    wait_for(|| {
+        respawned += workers.respawn_dead();
+        respawned > 0
+    });
+    assert_eq!(respawned, 1);
+    wait_for(|| starts.load(Ordering::SeqCst) == 2);
+    assert_eq!(workers.respawn_dead(), 0);
+    assert_eq!(workers.len(), 1);
+
+    drop(stop_tx);
+    workers.join();
+}
+
+// The loading and meshing systems requeue a job whose worker reported a panic until the
+// tracker runs out of attempts, then give up on it.
+#[test]
+fn panicking_jobs_are_retried_then_given_up_without_killing_the_worker() {
+    let (request_tx, request_rx) = crossbeam_channel::unbounded::<ChunkCoord>();
+    let (result_tx, result_rx) = crossbeam_channel::unbounded();
+    let mut workers = WorkerThreads::new();
+    workers.spawn("test-worker-0".to_string(), move || {
+        for coord in request_rx.iter() {
+            let result = run_catching_panics(|| -> ChunkCoord { panic!("bad chunk {:?}", coord) });
+            let _ = result_tx.send((coord, result));
+        }
+    });
+
+    let mut failures = FailureTracker::new(3);
+    let coord = ChunkCoord(2, 1, -4);
+    let mut attempts = 0;
+    request_tx.send(coord).unwrap();
+    loop {
+        let (failed, result) = result_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        attempts += 1;
+        assert_eq!(failed, coord);
+        assert_eq!(result, Err("bad chunk ChunkCoord(2, 1, -4)".to_string()));
+        if !failures.record_failure(failed) {
+            break;
+        }
+        request_tx.send(failed).unwrap();
+    }
+
+    assert_eq!(attempts, 3);
+    assert!(failures.is_errored(&coord));
+    assert_eq!(workers.respawn_dead(), 0);
+
+    drop(request_tx);
+    workers.join();
+}
@@ -0,0 +1,293 @@
+use fnv::FnvHasher;
+use meinkraft::components::{
+    world_to_chunk_coords, world_to_local_coords, BlockType, ChunkCoord, ChunkData,
+};
+use meinkraft::resources::{Config, WorldGenerator};
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::thread;
+
+const SEEDS: [u32; 4] = [42069, 1, 1337, 987_654_321];
+
+// Per seed: plains surface, river, ocean floor, mountain peak, mountain slope, snow-covered
+// cold lowland and a frozen river, found by scanning each seed's terrain.
+const CASES: [[ChunkCoord; 7]; 4] = [
+    [
+        ChunkCoord(0, 1, 1),
+        ChunkCoord(-5, 0, 3),
+        ChunkCoord(0, 0, -2),
+        ChunkCoord(-2, 10, 11),
+        ChunkCoord(-1, 7, 11),
+        ChunkCoord(-17, 1, -18),
+        ChunkCoord(-20, 0, -21),
+    ],
+    [
+        ChunkCoord(0, 1, 0),
+        ChunkCoord(0, 0, 3),
+        ChunkCoord(-2, 0, -1),
+        ChunkCoord(20, 10, 2),
+        ChunkCoord(24, 7, 2),
+        ChunkCoord(21, 1, -19),
+        ChunkCoord(24, 0, -15),
+    ],
+    [
+        ChunkCoord(0, 1, 1),
+        ChunkCoord(-2, 0, 1),
+        ChunkCoord(2, 0, -1),
+        ChunkCoord(15, 12, -15),
+        ChunkCoord(18, 9, -15),
+        ChunkCoord(23, 1, 11),
+        ChunkCoord(30, 0, 4),
+    ],
+    [
+        ChunkCoord(1, 1, -1),
+        ChunkCoord(0, 0, -1),
+        ChunkCoord(-2, 0, -2),
+        ChunkCoord(11, 9, 16),
+        ChunkCoord(13, 6, 16),
+        ChunkCoord(-23, 1, -20),
+        ChunkCoord(-30, 0, -6),
+    ],
+];
+
+// One row per seed, one column per coordinate in `CASES`. Regenerate only when a
+// change to world generation is intended to alter existing worlds.
+const GOLDEN_HASHES: [[u64; 7]; 4] = [
+    [
+        0x958fefb54b476542,
+        0x3b4045891cac8372,
+        0x0144f1fdd6b871aa,
+        0xb1af403268ab02de,
+        0x1e6203a94fd0b8c6,
+        0x7e9bea9c3c7962f0,
+        0x9dbd06d30b416323,
+    ],
+    [
+        0x9b0f2fea2964f5f0,
+        0x9a52147923cb11eb,
+        0xcaf287ea894c77c6,
+        0xf3db895392895f18,
+        0x923cdbc01e80cec0,
+        0xa9266b753f4bb53f,
+        0xacd24e4e9ca61a64,
+    ],
+    [
+        0x0ad2c9387583ea0e,
+        0x772fac76f7a67ddb,
+        0x99ca6ce9d61988df,
+        0xed0991b6948c4e99,
+        0xb9e1ee778912b58e,
+        0x5b7aa5bb77b78647,
+        0xbcef263cd14e8912,
+    ],
+    [
+        0x003f3b2f06f382d3,
+        0xf883668f9978a240,
+        0xe9f3bad0d7c54ee4,
+        0x8934887cd91012af,
+        0xa0f263b60064209a,
+        0xb732c1877ad0238b,
+        0xb9cc33ab7ec3314f,
+    ],
+];
+
+fn hash_chunk(chunk_data: &ChunkData) -> u64 {
+    let mut hasher = FnvHasher::default();
+    for block in &chunk_data.blocks {
+        hasher.write_u8(*block as u8);
+    }
+    hasher.write(&chunk_data.fluid_levels);
+    hasher.finish()
+}
+
+fn generator_for_seed(seed: u32) -> WorldGenerator {
+    let mut config = Config::new();
+    config.world_seed = seed;
+    WorldGenerator::new(config)
+}
+
+fn golden_mismatches(parallel: bool) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    for (seed_index, seed) in SEEDS.iter().enumerate() {
+        let mut config = Config::new();
+        config.world_seed = *seed;
+        config.parallel_chunk_generation = parallel;
+        let generator = WorldGenerator::new(config);
+        for (coord_index, coord) in CASES[seed_index].iter().enumerate() {
+            let hash = hash_chunk(&generator.generate_chunk_data(*coord));
+            let expected = GOLDEN_HASHES[seed_index][coord_index];
+            if hash != expected {
+                mismatches.push(format!(
+                    "seed {} chunk {:?}: expected {:#018x}, got {:#018x}",
+                    seed, coord, expected, hash
+                ));
+            }
+        }
+    }
+    mismatches
+}
+
+#[test]
+fn generated_chunks_match_golden_hashes() {
+    let mismatches = golden_mismatches(false);
+    assert!(
+        mismatches.is_empty(),
+        "World generation output changed:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn parallel_generation_matches_golden_hashes() {
+    let mismatches = golden_mismatches(true);
+    assert!(
+        mismatches.is_empty(),
+        "Parallel world generation output changed:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+// Guards the golden set itself: every case must cross real terrain, not empty air or solid
+// filler that would hide a regression.
+#[test]
+fn golden_chunks_contain_varied_terrain() {
+    for (seed_index, seed) in SEEDS.iter().enumerate() {
+        let generator = generator_for_seed(*seed);
+        let mut hashes = Vec::new();
+        for coord in CASES[seed_index] {
+            let chunk = generator.generate_chunk_data(coord);
+            let mut kinds: Vec<BlockType> = chunk.blocks.to_vec();
+            kinds.sort_unstable();
+            kinds.dedup();
+            assert!(
+                kinds.len() >= 2,
+                "seed {} chunk {:?} is uniform {:?}",
+                seed,
+                coord,
+                kinds
+            );
+            hashes.push(hash_chunk(&chunk));
+        }
+        hashes.sort_unstable();
+        hashes.dedup();
+        assert_eq!(
+            hashes.len(),
+            CASES[seed_index].len(),
+            "seed {} repeats a chunk",
+            seed
+        );
+    }
+}
+
+#[test]
+fn generation_is_independent_of_thread_and_order() {
+    let generator = Arc::new(generator_for_seed(SEEDS[0]));
+    let coords = CASES[0];
+    let expected: Vec<u64> = coords
+        .iter()
+        .map(|coord| hash_chunk(&generator.generate_chunk_data(*coord)))
+        .collect();
+
+    let num_threads = 4;
+    let handles: Vec<_> = (0..num_threads)
+        .map(|thread_index| {
+            let generator = Arc::clone(&generator);
+            thread::spawn(move || {
+                let mut order: Vec<usize> = (0..coords.len()).collect();
+                order.rotate_left(thread_index * 3 % coords.len());
+                if thread_index % 2 == 1 {
+                    order.reverse();
+                }
+                order
+                    .into_iter()
+                    .map(|i| (i, hash_chunk(&generator.generate_chunk_data(coords[i]))))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        for (i, hash) in handle.join().expect("generation thread panicked") {
+            assert_eq!(
+                hash, expected[i],
+                "chunk {:?} differs when generated on another thread",
+                coords[i]
+            );
+        }
+    }
+}
+
+#[test]
+fn different_seeds_produce_different_terrain() {
+    let coord = ChunkCoord(0, 0, 0);
+    let hashes: Vec<u64> = SEEDS
+        .iter()
+        .map(|seed| hash_chunk(&generator_for_seed(*seed).generate_chunk_data(coord)))
+        .collect();
+
+    for i in 0..hashes.len() {
+        for j in (i + 1)..hashes.len() {
+            assert_ne!(
+                hashes[i], hashes[j],
+                "seeds {} and {} generated identical chunks",
+                SEEDS[i], SEEDS[j]
+            );
+        }
+    }
+}
+
+#[test]
+fn rivers_freeze_over_in_cold_regions() {
+    let config = Config::new();
+    let generator = generator_for_seed(config.world_seed);
+
+    // Walk outward in rings of columns 16 blocks apart until a frozen river turns up.
+    let frozen = (0..400)
+        .flat_map(|ring: i32| {
+            (-ring..=ring).flat_map(move |i| [(i, -ring), (i, ring), (-ring, i), (ring, i)])
+        })
+        .map(|(x, z)| (x * 16, z * 16))
+        .find(|(x, z)| generator.surface_block(*x, *z).1 == BlockType::Ice);
+    let (world_x, world_z) = frozen.expect("no frozen river found near the origin");
+    assert!(generator.rivers_freeze(world_x, world_z));
+
+    let block_at = |world_y: i32| {
+        let chunk = generator
+            .generate_chunk_data(world_to_chunk_coords(&config, world_x, world_y, world_z));
+        let (x, y, z) = world_to_local_coords(&config, world_x, world_y, world_z);
+        chunk.get_block(&config, x, y, z)
+    };
+    assert_eq!(block_at(config.sea_level), BlockType::Ice);
+    assert_eq!(block_at(config.sea_level - 1), BlockType::Water);
+}
+
+#[test]
+fn cold_shores_stay_free_of_snow() {
+    let config = Config::new();
+    let generator = generator_for_seed(config.world_seed);
+    let shore_top = config.sea_level + config.river_bank_height;
+
+    let mut cold_columns = 0;
+    for x in -200..200 {
+        for z in -200..200 {
+            let (world_x, world_z) = (x * 16, z * 16);
+            assert!(generator.snow_line(world_x, world_z) > shore_top);
+            if !generator.rivers_freeze(world_x, world_z) {
+                continue;
+            }
+            cold_columns += 1;
+            let (height, block) = generator.surface_block(world_x, world_z);
+            if height <= shore_top {
+                assert_ne!(
+                    block,
+                    BlockType::Snow,
+                    "snow on the shore at {}, {}",
+                    world_x,
+                    world_z
+                );
+            }
+        }
+    }
+    assert!(cold_columns > 0, "no frozen region in the sampled area");
+}